@@ -28,6 +28,8 @@ pub struct Model {
     pub file_path: String,
     pub created_at: DateTimeWithTimeZone,
     pub normalized_date: Date,
+    pub computed_passfail: Option<String>,
+    pub tested_at: Option<DateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -5,6 +5,15 @@ mod m20241115_000002_add_test_order;
 mod m20251115_150148_events_and_fixes;
 mod m20251119_000001_add_test_source_fields;
 mod m20251120_000001_add_fg_serialized;
+mod m20261018_000001_create_report_templates;
+mod m20261018_000002_add_fg_customer_pn;
+mod m20261018_000003_create_coc_overrides;
+mod m20261018_000004_create_report_snapshots;
+mod m20261018_000005_add_snapshot_raw_records;
+mod m20261018_000006_add_report_retest_policy;
+mod m20261018_000007_create_fg_part_mappings;
+mod m20261018_000008_create_other_test_results;
+mod m20261018_000009_add_coc_override_reasons;
 
 pub struct Migrator;
 
@@ -17,6 +26,15 @@ impl MigratorTrait for Migrator {
             Box::new(m20251115_150148_events_and_fixes::Migration),
             Box::new(m20251119_000001_add_test_source_fields::Migration),
             Box::new(m20251120_000001_add_fg_serialized::Migration),
+            Box::new(m20261018_000001_create_report_templates::Migration),
+            Box::new(m20261018_000002_add_fg_customer_pn::Migration),
+            Box::new(m20261018_000003_create_coc_overrides::Migration),
+            Box::new(m20261018_000004_create_report_snapshots::Migration),
+            Box::new(m20261018_000005_add_snapshot_raw_records::Migration),
+            Box::new(m20261018_000006_add_report_retest_policy::Migration),
+            Box::new(m20261018_000007_create_fg_part_mappings::Migration),
+            Box::new(m20261018_000008_create_other_test_results::Migration),
+            Box::new(m20261018_000009_add_coc_override_reasons::Migration),
         ]
     }
 }
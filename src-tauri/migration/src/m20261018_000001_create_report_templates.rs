@@ -0,0 +1,110 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Named report templates, selectable per FG or per customer
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReportTemplates::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ReportTemplates::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ReportTemplates::Name)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(ReportTemplates::Customer).string())
+                    .col(ColumnDef::new(ReportTemplates::FgId).integer())
+                    .col(
+                        ColumnDef::new(ReportTemplates::Definition)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ReportTemplates::IsDefault)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(ReportTemplates::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(ReportTemplates::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_report_templates_fg")
+                            .from(ReportTemplates::Table, ReportTemplates::FgId)
+                            .to(Fg::Table, Fg::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_report_templates_fg_id")
+                    .table(ReportTemplates::Table)
+                    .col(ReportTemplates::FgId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_report_templates_customer")
+                    .table(ReportTemplates::Table)
+                    .col(ReportTemplates::Customer)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ReportTemplates::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ReportTemplates {
+    Table,
+    Id,
+    Name,
+    Customer,
+    FgId,
+    Definition,
+    IsDefault,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Fg {
+    Table,
+    Id,
+}
@@ -0,0 +1,78 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Admin approvals to issue a CoC for an incomplete or failing shipment
+        manager
+            .create_table(
+                Table::create()
+                    .table(CocOverrides::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CocOverrides::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(CocOverrides::ReportId).integer().not_null())
+                    .col(ColumnDef::new(CocOverrides::Scope).string().not_null())
+                    .col(ColumnDef::new(CocOverrides::Reason).text().not_null())
+                    .col(ColumnDef::new(CocOverrides::ApprovedBy).string().not_null())
+                    .col(
+                        ColumnDef::new(CocOverrides::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_coc_overrides_report")
+                            .from(CocOverrides::Table, CocOverrides::ReportId)
+                            .to(Report::Table, Report::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_coc_overrides_report_scope")
+                    .table(CocOverrides::Table)
+                    .col(CocOverrides::ReportId)
+                    .col(CocOverrides::Scope)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CocOverrides::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum CocOverrides {
+    Table,
+    Id,
+    ReportId,
+    Scope,
+    Reason,
+    ApprovedBy,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Report {
+    Table,
+    Id,
+}
@@ -0,0 +1,127 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Every generated report, exactly as it was produced
+        // No foreign keys: snapshots must outlive edits to reports and FGs
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReportSnapshots::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ReportSnapshots::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ReportSnapshots::ReportId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ReportSnapshots::FgId).integer().not_null())
+                    .col(ColumnDef::new(ReportSnapshots::Format).string().not_null())
+                    .col(
+                        ColumnDef::new(ReportSnapshots::Parameters)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ReportSnapshots::ReportData)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ReportSnapshots::SpecRows).text().not_null())
+                    .col(ColumnDef::new(ReportSnapshots::Template).text().not_null())
+                    .col(
+                        ColumnDef::new(ReportSnapshots::FileHash)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ReportSnapshots::FileBytes).blob().not_null())
+                    .col(
+                        ColumnDef::new(ReportSnapshots::GeneratedBy)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ReportSnapshots::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_report_snapshots_report_id")
+                    .table(ReportSnapshots::Table)
+                    .col(ReportSnapshots::ReportId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Snapshots are write-once: never changed and never removed
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            r#"
+            CREATE TRIGGER IF NOT EXISTS report_snapshots_immutable
+            BEFORE UPDATE ON report_snapshots
+            BEGIN
+                SELECT RAISE(ABORT, 'report snapshots are immutable');
+            END
+            "#,
+        )
+        .await?;
+        db.execute_unprepared(
+            r#"
+            CREATE TRIGGER IF NOT EXISTS report_snapshots_undeletable
+            BEFORE DELETE ON report_snapshots
+            BEGIN
+                SELECT RAISE(ABORT, 'report snapshots cannot be deleted');
+            END
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        db.execute_unprepared("DROP TRIGGER IF EXISTS report_snapshots_undeletable")
+            .await?;
+        db.execute_unprepared("DROP TRIGGER IF EXISTS report_snapshots_immutable")
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ReportSnapshots::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ReportSnapshots {
+    Table,
+    Id,
+    ReportId,
+    FgId,
+    Format,
+    Parameters,
+    ReportData,
+    SpecRows,
+    Template,
+    FileHash,
+    FileBytes,
+    GeneratedBy,
+    CreatedAt,
+}
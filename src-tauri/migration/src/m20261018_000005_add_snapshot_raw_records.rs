@@ -0,0 +1,36 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Raw source rows behind workbooks exported with raw data or traceability sheets
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReportSnapshots::Table)
+                    .add_column(text_null(ReportSnapshots::RawRecords))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReportSnapshots::Table)
+                    .drop_column(ReportSnapshots::RawRecords)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ReportSnapshots {
+    Table,
+    RawRecords,
+}
@@ -0,0 +1,80 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Tester part numbers (exact or regex) that belong to an FG
+        manager
+            .create_table(
+                Table::create()
+                    .table(FgPartMappings::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(FgPartMappings::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(FgPartMappings::FgId).integer().not_null())
+                    .col(ColumnDef::new(FgPartMappings::Source).string().not_null())
+                    .col(ColumnDef::new(FgPartMappings::Kind).string().not_null())
+                    .col(ColumnDef::new(FgPartMappings::Value).string().not_null())
+                    .col(ColumnDef::new(FgPartMappings::Rev).string())
+                    .col(
+                        ColumnDef::new(FgPartMappings::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_fg_part_mappings_fg")
+                            .from(FgPartMappings::Table, FgPartMappings::FgId)
+                            .to(Fg::Table, Fg::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_fg_part_mappings_fg_source")
+                    .table(FgPartMappings::Table)
+                    .col(FgPartMappings::FgId)
+                    .col(FgPartMappings::Source)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(FgPartMappings::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum FgPartMappings {
+    Table,
+    Id,
+    FgId,
+    Source,
+    Kind,
+    Value,
+    Rev,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Fg {
+    Table,
+    Id,
+}
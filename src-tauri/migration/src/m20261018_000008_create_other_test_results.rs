@@ -0,0 +1,114 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Outcomes recorded by hand for "other" tests, per report run (batch or serial range)
+        manager
+            .create_table(
+                Table::create()
+                    .table(OtherTestResults::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(OtherTestResults::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(OtherTestResults::ReportId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OtherTestResults::TestId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(OtherTestResults::Scope).string().not_null())
+                    .col(ColumnDef::new(OtherTestResults::SerialNumber).string())
+                    .col(ColumnDef::new(OtherTestResults::Value).double())
+                    .col(ColumnDef::new(OtherTestResults::Result).string().not_null())
+                    .col(ColumnDef::new(OtherTestResults::Notes).text())
+                    .col(ColumnDef::new(OtherTestResults::Attachment).string())
+                    .col(
+                        ColumnDef::new(OtherTestResults::RecordedBy)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OtherTestResults::RecordedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_other_test_results_report")
+                            .from(OtherTestResults::Table, OtherTestResults::ReportId)
+                            .to(Report::Table, Report::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_other_test_results_test")
+                            .from(OtherTestResults::Table, OtherTestResults::TestId)
+                            .to(Test::Table, Test::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_other_test_results_report_scope")
+                    .table(OtherTestResults::Table)
+                    .col(OtherTestResults::ReportId)
+                    .col(OtherTestResults::Scope)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OtherTestResults::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum OtherTestResults {
+    Table,
+    Id,
+    ReportId,
+    TestId,
+    Scope,
+    SerialNumber,
+    Value,
+    Result,
+    Notes,
+    Attachment,
+    RecordedBy,
+    RecordedAt,
+}
+
+#[derive(DeriveIden)]
+enum Report {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Test {
+    Table,
+    Id,
+}
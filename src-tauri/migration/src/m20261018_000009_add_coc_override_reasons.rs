@@ -0,0 +1,37 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The blocking reasons an override was approved for (JSON array)
+        // Overrides recorded before this column no longer apply
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CocOverrides::Table)
+                    .add_column(text_null(CocOverrides::BlockingReasons))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CocOverrides::Table)
+                    .drop_column(CocOverrides::BlockingReasons)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum CocOverrides {
+    Table,
+    BlockingReasons,
+}
@@ -1,12 +1,20 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20220101_000001_create_table;
+mod m20261018_000002_add_computed_passfail;
+mod m20261018_000003_add_tested_at;
+mod m20261018_000004_add_report_scope_index;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20220101_000001_create_table::Migration)]
+        vec![
+            Box::new(m20220101_000001_create_table::Migration),
+            Box::new(m20261018_000002_add_computed_passfail::Migration),
+            Box::new(m20261018_000003_add_tested_at::Migration),
+            Box::new(m20261018_000004_add_report_scope_index::Migration),
+        ]
     }
 }
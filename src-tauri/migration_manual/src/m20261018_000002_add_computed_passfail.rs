@@ -0,0 +1,54 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Add computed_passfail column (verdict recomputed from minimum/reading/maximum)
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ManualTestResults::Table)
+                    .add_column(string_null(ManualTestResults::ComputedPassfail))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Backfill from existing rows using inclusive limits
+        let db = manager.get_connection();
+        let sql = r#"
+            UPDATE manual_test_results
+            SET computed_passfail =
+                CASE
+                    WHEN reading >= minimum AND reading <= maximum THEN 'PASS'
+                    ELSE 'FAIL'
+                END
+            WHERE computed_passfail IS NULL
+        "#;
+
+        db.execute_unprepared(sql).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ManualTestResults::Table)
+                    .drop_column(ManualTestResults::ComputedPassfail)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ManualTestResults {
+    Table,
+    ComputedPassfail,
+}
@@ -0,0 +1,77 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Add tested_at column (normalized_date combined with the raw time string)
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ManualTestResults::Table)
+                    .add_column(date_time_null(ManualTestResults::TestedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Backfill rows whose time is already 24-hour HH:MM or HH:MM:SS
+        // Other formats are filled in when the file is re-imported
+        let db = manager.get_connection();
+        let sql = r#"
+            UPDATE manual_test_results
+            SET tested_at =
+                CASE
+                    WHEN length(time) = 5 THEN normalized_date || ' ' || time || ':00'
+                    ELSE normalized_date || ' ' || time
+                END
+            WHERE tested_at IS NULL
+              AND (time GLOB '[0-2][0-9]:[0-5][0-9]'
+                   OR time GLOB '[0-2][0-9]:[0-5][0-9]:[0-5][0-9]')
+        "#;
+
+        db.execute_unprepared(sql).await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_manual_tested_at")
+                    .table(ManualTestResults::Table)
+                    .col(ManualTestResults::TestedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_manual_tested_at")
+                    .table(ManualTestResults::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ManualTestResults::Table)
+                    .drop_column(ManualTestResults::TestedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ManualTestResults {
+    Table,
+    TestedAt,
+}
@@ -0,0 +1,42 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Reports select by FG, then batch and session dates, in one query
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_manual_fg_batch_normalized_date")
+                    .table(ManualTestResults::Table)
+                    .col(ManualTestResults::Fg)
+                    .col(ManualTestResults::Batch)
+                    .col(ManualTestResults::NormalizedDate)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_manual_fg_batch_normalized_date")
+                    .table(ManualTestResults::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ManualTestResults {
+    Table,
+    Fg,
+    Batch,
+    NormalizedDate,
+}
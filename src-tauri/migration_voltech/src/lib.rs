@@ -4,6 +4,7 @@ mod m20220101_000001_create_table;
 mod m20251118_000002_add_errors_and_lock;
 mod m20251119_000003_add_relative_path;
 mod m20251120_174215_add_normalized_date_to_voltech;
+mod m20261018_000005_add_report_scope_index;
 
 pub struct Migrator;
 
@@ -15,6 +16,7 @@ impl MigratorTrait for Migrator {
             Box::new(m20251118_000002_add_errors_and_lock::Migration),
             Box::new(m20251119_000003_add_relative_path::Migration),
             Box::new(m20251120_174215_add_normalized_date_to_voltech::Migration),
+            Box::new(m20261018_000005_add_report_scope_index::Migration),
         ]
     }
 }
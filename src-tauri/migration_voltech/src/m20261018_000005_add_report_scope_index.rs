@@ -0,0 +1,42 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Reports select by part, then batch and session dates, in one query
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_part_batch_normalized_date")
+                    .table(TestResults::Table)
+                    .col(TestResults::Part)
+                    .col(TestResults::Batch)
+                    .col(TestResults::NormalizedDate)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_part_batch_normalized_date")
+                    .table(TestResults::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TestResults {
+    Table,
+    Part,
+    Batch,
+    NormalizedDate,
+}
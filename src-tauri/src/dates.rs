@@ -0,0 +1,190 @@
+// Shared date/time normalization for the Voltech and manual importers
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+/// Settings key holding the preferred date order for a source
+pub const DATE_FORMAT_SETTING: &str = "date_format";
+
+/// Field order for ambiguous numeric dates such as "05/06/25"
+/// ISO dates (YYYY-MM-DD) are always accepted regardless of preference
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DateOrder {
+    Iso, // YYYY-MM-DD
+    Us,  // MM/DD/YYYY
+    Eu,  // DD/MM/YYYY
+}
+
+impl DateOrder {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DateOrder::Iso => "iso",
+            DateOrder::Us => "us",
+            DateOrder::Eu => "eu",
+        }
+    }
+
+    /// Parse a stored setting value ("iso", "us" or "eu")
+    pub fn from_setting(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "iso" => Ok(DateOrder::Iso),
+            "us" => Ok(DateOrder::Us),
+            "eu" => Ok(DateOrder::Eu),
+            other => Err(format!(
+                "Invalid date format '{}', expected one of: iso, us, eu",
+                other
+            )),
+        }
+    }
+}
+
+/// Expand a two-digit year (00-69 -> 20xx, 70-99 -> 19xx)
+fn expand_year(year: i32, digits: usize) -> i32 {
+    if digits > 2 {
+        year
+    } else if year < 70 {
+        2000 + year
+    } else {
+        1900 + year
+    }
+}
+
+/// Parse a date using the given field order
+/// Accepts "/", "-" and "." separators and two- or four-digit years
+pub fn parse_date(date_str: &str, order: DateOrder) -> Result<NaiveDate, String> {
+    let trimmed = date_str.trim();
+    let parts: Vec<&str> = trimmed.split(['/', '-', '.']).collect();
+
+    if parts.len() != 3 || parts.iter().any(|p| p.is_empty()) {
+        return Err(format!("Could not parse date: {}", date_str));
+    }
+
+    let nums: Vec<i32> = parts
+        .iter()
+        .map(|p| p.trim().parse::<i32>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("Could not parse date: {}", date_str))?;
+
+    // A leading four-digit field is unambiguous ISO
+    let (year, month, day) = if parts[0].trim().len() == 4 {
+        (nums[0], nums[1], nums[2])
+    } else {
+        let year = expand_year(nums[2], parts[2].trim().len());
+        match order {
+            DateOrder::Us => (year, nums[0], nums[1]),
+            DateOrder::Eu => (year, nums[1], nums[0]),
+            DateOrder::Iso => {
+                return Err(format!("Could not parse date as ISO: {}", date_str));
+            }
+        }
+    };
+
+    if month < 1 || day < 1 {
+        return Err(format!("Invalid date components: {}", date_str));
+    }
+
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+        .ok_or_else(|| format!("Invalid date components: {}", date_str))
+}
+
+/// Parse a time of day ("14:05", "14:05:09", "2:05 PM", "2:05:09pm")
+pub fn parse_time(time_str: &str) -> Option<NaiveTime> {
+    let trimmed = time_str.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    for format in ["%H:%M:%S", "%H:%M", "%H:%M:%S%.f"] {
+        if let Ok(time) = NaiveTime::parse_from_str(trimmed, format) {
+            return Some(time);
+        }
+    }
+
+    let upper = trimmed.to_ascii_uppercase();
+    for format in ["%I:%M:%S %p", "%I:%M %p", "%I:%M:%S%p", "%I:%M%p"] {
+        if let Ok(time) = NaiveTime::parse_from_str(&upper, format) {
+            return Some(time);
+        }
+    }
+
+    None
+}
+
+/// Combine a date and a raw time string into a full timestamp
+pub fn combine_date_time(date: NaiveDate, time_str: &str) -> Option<NaiveDateTime> {
+    parse_time(time_str).map(|time| date.and_time(time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_parse_date_orders() {
+        assert_eq!(
+            parse_date("2025-11-19", DateOrder::Iso).unwrap(),
+            ymd(2025, 11, 19)
+        );
+        assert_eq!(
+            parse_date("2025/11/19", DateOrder::Us).unwrap(),
+            ymd(2025, 11, 19)
+        );
+        assert_eq!(
+            parse_date("11/19/2025", DateOrder::Us).unwrap(),
+            ymd(2025, 11, 19)
+        );
+        assert_eq!(
+            parse_date("19.11.2025", DateOrder::Eu).unwrap(),
+            ymd(2025, 11, 19)
+        );
+        assert_eq!(
+            parse_date("05/06/25", DateOrder::Us).unwrap(),
+            ymd(2025, 5, 6)
+        );
+        assert_eq!(
+            parse_date("05/06/25", DateOrder::Eu).unwrap(),
+            ymd(2025, 6, 5)
+        );
+        assert_eq!(
+            parse_date("19-11-25", DateOrder::Eu).unwrap(),
+            ymd(2025, 11, 19)
+        );
+        assert_eq!(
+            parse_date("1/1/99", DateOrder::Us).unwrap(),
+            ymd(1999, 1, 1)
+        );
+        assert_eq!(
+            parse_date("1/5/2025", DateOrder::Us).unwrap(),
+            ymd(2025, 1, 5)
+        );
+        assert_eq!(
+            parse_date("12/31/2024", DateOrder::Us).unwrap(),
+            ymd(2024, 12, 31)
+        );
+    }
+
+    #[test]
+    fn test_parse_date_invalid() {
+        assert!(parse_date("19/11/2025", DateOrder::Us).is_err());
+        assert!(parse_date("11/19/2025", DateOrder::Eu).is_err());
+        assert!(parse_date("11/19/2025", DateOrder::Iso).is_err());
+        assert!(parse_date("", DateOrder::Us).is_err());
+        assert!(parse_date("1//2025", DateOrder::Us).is_err());
+        assert!(parse_date("invalid", DateOrder::Us).is_err());
+        assert!(parse_date("13/1/2025", DateOrder::Us).is_err()); // Invalid month
+        assert!(parse_date("1/32/2025", DateOrder::Us).is_err()); // Invalid day
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(parse_time("14:05"), NaiveTime::from_hms_opt(14, 5, 0));
+        assert_eq!(parse_time("14:05:09"), NaiveTime::from_hms_opt(14, 5, 9));
+        assert_eq!(parse_time("2:05 pm"), NaiveTime::from_hms_opt(14, 5, 0));
+        assert_eq!(parse_time("12:30:00 AM"), NaiveTime::from_hms_opt(0, 30, 0));
+        assert_eq!(parse_time("noon"), None);
+    }
+}
@@ -1,7 +1,11 @@
 use crate::AppState;
 use entity::fg;
-use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbBackend, DbConn, EntityTrait,
+    FromQueryResult, QueryFilter, Set, Statement,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use tauri::State;
 
 // ============================================================================
@@ -14,6 +18,8 @@ pub struct FGData {
     pub rev: String,
     pub customer: String,
     pub serialized: bool,
+    #[serde(default)]
+    pub customer_pn: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -23,6 +29,70 @@ pub struct FGResponse {
     pub rev: String,
     pub customer: String,
     pub serialized: bool,
+    pub customer_pn: Option<String>,
+}
+
+// ============================================================================
+// Customer Part Number
+// ============================================================================
+//
+// `fg.customer_pn` is not part of the generated entity, so it is read and
+// written with SQL.
+
+#[derive(Debug, FromQueryResult)]
+struct CustomerPnRow {
+    id: i32,
+    customer_pn: Option<String>,
+}
+
+/// Get the customer part number of an FG
+pub(crate) async fn get_customer_pn(db: &DbConn, fg_id: i32) -> Result<Option<String>, String> {
+    let row = CustomerPnRow::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT id, customer_pn FROM fg WHERE id = ?",
+        [fg_id.into()],
+    ))
+    .one(db)
+    .await
+    .map_err(|e| format!("Failed to fetch customer PN: {}", e))?;
+
+    Ok(row.and_then(|r| r.customer_pn))
+}
+
+/// Get the customer part numbers of all FGs, keyed by FG id
+async fn get_all_customer_pns(db: &DbConn) -> Result<HashMap<i32, String>, String> {
+    let rows = CustomerPnRow::find_by_statement(Statement::from_string(
+        DbBackend::Sqlite,
+        "SELECT id, customer_pn FROM fg WHERE customer_pn IS NOT NULL",
+    ))
+    .all(db)
+    .await
+    .map_err(|e| format!("Failed to fetch customer PNs: {}", e))?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|r| r.customer_pn.map(|pn| (r.id, pn)))
+        .collect())
+}
+
+async fn set_customer_pn(
+    db: &DbConn,
+    fg_id: i32,
+    customer_pn: Option<String>,
+) -> Result<Option<String>, String> {
+    let customer_pn = customer_pn
+        .map(|pn| pn.trim().to_string())
+        .filter(|pn| !pn.is_empty());
+
+    db.execute_raw(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "UPDATE fg SET customer_pn = ? WHERE id = ?",
+        [customer_pn.clone().into(), fg_id.into()],
+    ))
+    .await
+    .map_err(|e| format!("Failed to save customer PN: {}", e))?;
+
+    Ok(customer_pn)
 }
 
 // ============================================================================
@@ -57,12 +127,15 @@ pub async fn create_fg(state: State<'_, AppState>, fg_data: FGData) -> Result<FG
         .await
         .map_err(|e| format!("Failed to create FG: {}", e))?;
 
+    let customer_pn = set_customer_pn(db, fg_model.id, fg_data.customer_pn).await?;
+
     Ok(FGResponse {
         id: fg_model.id,
         fg: fg_model.fg,
         rev: fg_model.rev,
         customer: fg_model.customer,
         serialized: fg_model.serialized,
+        customer_pn,
     })
 }
 
@@ -76,12 +149,15 @@ pub async fn get_fg(state: State<'_, AppState>, id: i32) -> Result<FGResponse, S
         .map_err(|e| format!("Failed to fetch FG: {}", e))?
         .ok_or_else(|| "FG not found".to_string())?;
 
+    let customer_pn = get_customer_pn(db, fg_model.id).await?;
+
     Ok(FGResponse {
         id: fg_model.id,
         fg: fg_model.fg,
         rev: fg_model.rev,
         customer: fg_model.customer,
         serialized: fg_model.serialized,
+        customer_pn,
     })
 }
 
@@ -99,12 +175,15 @@ pub async fn get_fg_by_number(
         .map_err(|e| format!("Failed to fetch FG: {}", e))?
         .ok_or_else(|| "FG not found".to_string())?;
 
+    let customer_pn = get_customer_pn(db, fg_model.id).await?;
+
     Ok(FGResponse {
         id: fg_model.id,
         fg: fg_model.fg,
         rev: fg_model.rev,
         customer: fg_model.customer,
         serialized: fg_model.serialized,
+        customer_pn,
     })
 }
 
@@ -116,10 +195,12 @@ pub async fn get_all_fgs(state: State<'_, AppState>) -> Result<Vec<FGResponse>,
         .all(db)
         .await
         .map_err(|e| format!("Failed to fetch FGs: {}", e))?;
+    let mut customer_pns = get_all_customer_pns(db).await?;
 
     Ok(fgs
         .into_iter()
         .map(|fg_model| FGResponse {
+            customer_pn: customer_pns.remove(&fg_model.id),
             id: fg_model.id,
             fg: fg_model.fg,
             rev: fg_model.rev,
@@ -154,12 +235,15 @@ pub async fn update_fg(
         .await
         .map_err(|e| format!("Failed to update FG: {}", e))?;
 
+    let customer_pn = set_customer_pn(db, fg_model.id, fg_data.customer_pn).await?;
+
     Ok(FGResponse {
         id: fg_model.id,
         fg: fg_model.fg,
         rev: fg_model.rev,
         customer: fg_model.customer,
         serialized: fg_model.serialized,
+        customer_pn,
     })
 }
 
@@ -6,8 +6,9 @@ use serde::Serialize;
 use tauri::State;
 
 // Re-export response types from other modules
-use crate::fg::FGResponse;
+use crate::fg::{get_customer_pn, FGResponse};
 use crate::report::ReportResponse;
+use crate::reports::retest::{all_report_policies, report_policy, RetestPolicy};
 use crate::test::TestResponse;
 
 // ============================================================================
@@ -63,6 +64,7 @@ pub struct ReportCompleteResponse {
     pub id: i32,
     pub fg_id: i32,
     pub attributes: String,
+    pub retest_policy: Option<RetestPolicy>,
     pub fg: FGResponse,
     pub tests: Vec<TestResponse>,
 }
@@ -128,6 +130,9 @@ pub async fn get_fg_with_reports(
         .all(db)
         .await
         .map_err(|e| format!("Failed to fetch reports: {}", e))?;
+    let policies = all_report_policies(db)
+        .await
+        .map_err(|e| format!("Failed to fetch retest policies: {}", e))?;
 
     Ok(FGWithReportsResponse {
         id: fg_model.id,
@@ -137,6 +142,7 @@ pub async fn get_fg_with_reports(
         reports: reports
             .into_iter()
             .map(|r| ReportResponse {
+                retest_policy: policies.get(&r.id).copied(),
                 id: r.id,
                 fg_id: r.fg_id,
                 attributes: r.attributes,
@@ -214,6 +220,9 @@ pub async fn get_fg_complete(
         .all(db)
         .await
         .map_err(|e| format!("Failed to fetch reports: {}", e))?;
+    let policies = all_report_policies(db)
+        .await
+        .map_err(|e| format!("Failed to fetch retest policies: {}", e))?;
 
     let tests = fg_model
         .find_related(test::Entity)
@@ -229,6 +238,7 @@ pub async fn get_fg_complete(
         reports: reports
             .into_iter()
             .map(|r| ReportResponse {
+                retest_policy: policies.get(&r.id).copied(),
                 id: r.id,
                 fg_id: r.fg_id,
                 attributes: r.attributes,
@@ -284,6 +294,8 @@ pub async fn get_report_with_fg(
         .map_err(|e| format!("Failed to fetch FG: {}", e))?
         .ok_or_else(|| "FG not found".to_string())?;
 
+    let customer_pn = get_customer_pn(db, fg_model.id).await?;
+
     Ok(ReportWithFGResponse {
         id: report_model.id,
         fg_id: report_model.fg_id,
@@ -294,6 +306,7 @@ pub async fn get_report_with_fg(
             rev: fg_model.rev,
             customer: fg_model.customer,
             serialized: fg_model.serialized,
+            customer_pn,
         },
     })
 }
@@ -373,16 +386,23 @@ pub async fn get_report_complete(
         .await
         .map_err(|e| format!("Failed to fetch tests: {}", e))?;
 
+    let customer_pn = get_customer_pn(db, fg_model.id).await?;
+    let retest_policy = report_policy(db, report_model.id)
+        .await
+        .map_err(|e| format!("Failed to fetch retest policy: {}", e))?;
+
     Ok(ReportCompleteResponse {
         id: report_model.id,
         fg_id: report_model.fg_id,
         attributes: report_model.attributes,
+        retest_policy,
         fg: FGResponse {
             id: fg_model.id,
             fg: fg_model.fg,
             rev: fg_model.rev,
             customer: fg_model.customer,
             serialized: fg_model.serialized,
+            customer_pn,
         },
         tests: tests
             .into_iter()
@@ -434,6 +454,8 @@ pub async fn get_test_with_fg(
         .map_err(|e| format!("Failed to fetch FG: {}", e))?
         .ok_or_else(|| "FG not found".to_string())?;
 
+    let customer_pn = get_customer_pn(db, fg_model.id).await?;
+
     Ok(TestWithFGResponse {
         id: test_model.id,
         report_id: test_model.report_id,
@@ -449,6 +471,7 @@ pub async fn get_test_with_fg(
             rev: fg_model.rev,
             customer: fg_model.customer,
             serialized: fg_model.serialized,
+            customer_pn,
         },
     })
 }
@@ -471,11 +494,15 @@ pub async fn get_test_with_report(
             .one(db)
             .await
             .map_err(|e| format!("Failed to fetch report: {}", e))?;
+        let retest_policy = report_policy(db, report_id)
+            .await
+            .map_err(|e| format!("Failed to fetch retest policy: {}", e))?;
 
         report_model.map(|r| ReportResponse {
             id: r.id,
             fg_id: r.fg_id,
             attributes: r.attributes,
+            retest_policy,
         })
     } else {
         None
@@ -518,16 +545,22 @@ pub async fn get_test_complete(
             .one(db)
             .await
             .map_err(|e| format!("Failed to fetch report: {}", e))?;
+        let retest_policy = report_policy(db, report_id)
+            .await
+            .map_err(|e| format!("Failed to fetch retest policy: {}", e))?;
 
         report_model.map(|r| ReportResponse {
             id: r.id,
             fg_id: r.fg_id,
             attributes: r.attributes,
+            retest_policy,
         })
     } else {
         None
     };
 
+    let customer_pn = get_customer_pn(db, fg_model.id).await?;
+
     Ok(TestCompleteResponse {
         id: test_model.id,
         test_type: test_model.test_type,
@@ -542,6 +575,7 @@ pub async fn get_test_complete(
             rev: fg_model.rev,
             customer: fg_model.customer,
             serialized: fg_model.serialized,
+            customer_pn,
         },
         report: report_response,
     })
@@ -571,6 +605,8 @@ pub async fn get_all_reports_with_fg(
             .map_err(|e| format!("Failed to fetch FG: {}", e))?
             .ok_or_else(|| format!("FG {} not found", report_model.fg_id))?;
 
+        let customer_pn = get_customer_pn(db, fg_model.id).await?;
+
         result.push(ReportWithFGResponse {
             id: report_model.id,
             fg_id: report_model.fg_id,
@@ -581,6 +617,7 @@ pub async fn get_all_reports_with_fg(
                 rev: fg_model.rev,
                 customer: fg_model.customer,
                 serialized: fg_model.serialized,
+                customer_pn,
             },
         });
     }
@@ -1,14 +1,19 @@
 mod auth;
+mod dates;
 mod event;
 mod fg;
 mod joins;
 mod manual;
+mod part_mapping;
 mod report;
 mod reports;
+mod serials;
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 mod sheets;
+mod sources;
 mod test;
 mod test_types;
+mod units;
 mod user;
 mod voltech;
 
@@ -39,6 +44,7 @@ pub struct AppState {
     pub manual_db: Arc<DbConn>,
     pub voltech_watcher_state: Arc<Mutex<voltech::WatcherState>>,
     pub instance_id: String,
+    pub report_jobs: reports::jobs::ReportJobs,
 }
 
 pub async fn establish_core_connection() -> Result<DbConn, CoreDbErr> {
@@ -177,6 +183,11 @@ pub fn run() {
             fg::get_all_fgs,
             fg::update_fg,
             fg::delete_fg,
+            // FG Part Number Mapping
+            part_mapping::get_part_mappings,
+            part_mapping::add_part_mapping,
+            part_mapping::delete_part_mapping,
+            part_mapping::get_unmatched_part_numbers,
             // Report CRUD
             report::create_report,
             report::get_report,
@@ -262,11 +273,31 @@ pub fn run() {
             // Manual Test Import & Operations
             manual::commands::import_manual_file,
             manual::commands::import_manual_fg_folder,
+            manual::commands::preview_manual_fg_folder,
             manual::commands::get_manual_test_names,
             manual::commands::get_manual_tests,
             manual::commands::get_manual_summary,
             manual::commands::get_manual_base_path,
             manual::commands::set_manual_base_path,
+            manual::commands::get_manual_date_format,
+            manual::commands::set_manual_date_format,
+            manual::commands::get_manual_include_patterns,
+            manual::commands::set_manual_include_patterns,
+            manual::commands::search_manual_tests,
+            manual::commands::get_manual_failed_tests,
+            manual::commands::get_manual_batch_tests,
+            manual::commands::get_manual_batches_for_fg,
+            manual::commands::get_all_manual_fgs,
+            // Unified Source Queries (Voltech or Manual)
+            sources::search_source_tests,
+            sources::count_source_tests,
+            sources::get_source_failed_tests,
+            sources::search_source_batches,
+            sources::get_source_batch_details,
+            sources::get_source_part_summary,
+            sources::get_source_daily_stats,
+            sources::get_source_operator_stats,
+            sources::get_source_overall_stats,
             // Test Type Mapping
             test_types::get_test_types,
             test_types::find_tests_for_type,
@@ -275,8 +306,37 @@ pub fn run() {
             reports::collector::collect_report,
             reports::generate_excel_report,
             reports::save_excel_report,
+            reports::generate_pdf_report,
+            reports::save_pdf_report,
+            reports::coc::preview_coc,
+            reports::coc::generate_coc,
+            reports::coc::save_coc,
+            reports::coc::record_coc_override,
+            reports::reconcile::reconcile_sources,
+            reports::other_results::get_other_results,
+            reports::other_results::record_other_result,
+            reports::other_results::delete_other_result,
+            reports::jobs::submit_report_jobs,
+            reports::jobs::list_report_jobs,
+            reports::jobs::cancel_report_job,
+            reports::jobs::get_report_job_artifact,
+            reports::jobs::save_report_job_artifact,
+            reports::jobs::remove_report_job,
+            reports::bulk::generate_bulk_reports,
+            reports::snapshot::list_report_snapshots,
+            reports::snapshot::get_report_snapshot,
+            reports::snapshot::download_report_snapshot,
+            reports::snapshot::diff_report_snapshots,
+            reports::snapshot::regenerate_report_snapshot,
             reports::debug::debug_voltech_query,
-            reports::excel::create_ba_report
+            reports::excel::create_ba_report,
+            reports::excel::save_ba_report,
+            // Report Templates
+            reports::template_store::list_report_templates,
+            reports::template_store::get_report_template,
+            reports::template_store::save_report_template,
+            reports::template_store::delete_report_template,
+            reports::template_store::get_default_report_template
         ])
         .setup(|app| {
             if cfg!(debug_assertions) {
@@ -461,6 +521,7 @@ pub fn run() {
                 manual_db: Arc::new(manual_db),
                 voltech_watcher_state: watcher_state,
                 instance_id,
+                report_jobs: reports::jobs::ReportJobs::new(),
             });
 
             Ok(())
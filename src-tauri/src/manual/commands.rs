@@ -1,3 +1,6 @@
+use crate::dates::DateOrder;
+use crate::manual::limits::{self, ImportWarning};
+use crate::manual::operations::{FileImportOutcome, FilePreview};
 use crate::manual::{operations, queries};
 use crate::AppState;
 use entity_manual::manual_test_results;
@@ -12,6 +15,8 @@ use tauri::State;
 pub struct ImportResult {
     pub files_processed: usize,
     pub records_imported: usize,
+    pub warnings: Vec<ImportWarning>,
+    pub files: Vec<FileImportOutcome>, // Per-file outcomes (folder imports only)
 }
 
 #[derive(Debug, Serialize)]
@@ -33,6 +38,15 @@ pub struct ManualTestResponse {
     pub uom: String,
     pub file_path: String,
     pub normalized_date: String,
+    pub tested_at: Option<String>,
+    pub computed_passfail: String,
+    pub verdict_mismatch: bool, // File verdict disagrees with the limits
+}
+
+#[derive(Debug, Serialize)]
+pub struct ManualTestPage {
+    pub items: Vec<ManualTestResponse>,
+    pub total: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -49,7 +63,13 @@ pub struct ManualTestFilter {
 // Helper Functions
 // ============================================================================
 
-fn model_to_response(model: manual_test_results::Model) -> ManualTestResponse {
+pub(crate) fn model_to_response(model: manual_test_results::Model) -> ManualTestResponse {
+    // Rows imported before computed_passfail existed are recomputed on the fly
+    let computed_passfail = model.computed_passfail.clone().unwrap_or_else(|| {
+        limits::compute_passfail(model.minimum, model.reading, model.maximum).to_string()
+    });
+    let verdict_mismatch = !limits::verdicts_agree(&model.passfail, &computed_passfail);
+
     ManualTestResponse {
         id: model.id,
         result: model.result,
@@ -68,6 +88,9 @@ fn model_to_response(model: manual_test_results::Model) -> ManualTestResponse {
         uom: model.uom,
         file_path: model.file_path,
         normalized_date: model.normalized_date.to_string(),
+        tested_at: model.tested_at.map(|t| t.to_string()),
+        computed_passfail,
+        verdict_mismatch,
     }
 }
 
@@ -79,8 +102,15 @@ fn model_to_response(model: manual_test_results::Model) -> ManualTestResponse {
 pub async fn import_manual_file(
     state: State<'_, AppState>,
     file_path: String,
-) -> Result<usize, String> {
-    operations::import_manual_csv_file(&state.manual_db, &file_path).await
+) -> Result<ImportResult, String> {
+    let (records, warnings) =
+        operations::import_manual_csv_file(&state.manual_db, &state.core_db, &file_path).await?;
+    Ok(ImportResult {
+        files_processed: if records > 0 { 1 } else { 0 },
+        records_imported: records,
+        warnings,
+        files: Vec::new(),
+    })
 }
 
 #[tauri::command]
@@ -88,13 +118,25 @@ pub async fn import_manual_fg_folder(
     state: State<'_, AppState>,
     fg: String,
 ) -> Result<ImportResult, String> {
-    let (files, records) = operations::import_manual_fg_folder(&state.manual_db, &fg).await?;
+    let (files, warnings) =
+        operations::import_manual_fg_folder(&state.manual_db, &state.core_db, &fg).await?;
     Ok(ImportResult {
-        files_processed: files,
-        records_imported: records,
+        files_processed: files.iter().filter(|f| f.status == "imported").count(),
+        records_imported: files.iter().map(|f| f.records_imported).sum(),
+        warnings,
+        files,
     })
 }
 
+/// Dry run of `import_manual_fg_folder`: returns what would be imported without writing
+#[tauri::command]
+pub async fn preview_manual_fg_folder(
+    state: State<'_, AppState>,
+    fg: String,
+) -> Result<Vec<FilePreview>, String> {
+    operations::preview_manual_fg_folder(&state.manual_db, &state.core_db, &fg).await
+}
+
 #[tauri::command]
 pub async fn get_manual_test_names(
     state: State<'_, AppState>,
@@ -135,6 +177,70 @@ pub async fn get_manual_tests(
     Ok(results.into_iter().map(model_to_response).collect())
 }
 
+#[tauri::command]
+pub async fn search_manual_tests(
+    state: State<'_, AppState>,
+    filter: queries::ManualTestSearchFilter,
+) -> Result<ManualTestPage, String> {
+    let total = queries::count_manual_tests(&state.manual_db, filter.clone())
+        .await
+        .map_err(|e| format!("Failed to count manual tests: {}", e))?;
+
+    let results = queries::search_manual_tests(&state.manual_db, filter)
+        .await
+        .map_err(|e| format!("Failed to search manual tests: {}", e))?;
+
+    Ok(ManualTestPage {
+        items: results.into_iter().map(model_to_response).collect(),
+        total,
+    })
+}
+
+#[tauri::command]
+pub async fn get_manual_failed_tests(
+    state: State<'_, AppState>,
+    fg: Option<String>,
+    limit: Option<u64>,
+) -> Result<Vec<ManualTestResponse>, String> {
+    let results = queries::get_manual_failed_tests(&state.manual_db, fg.as_deref(), limit)
+        .await
+        .map_err(|e| format!("Failed to get failed manual tests: {}", e))?;
+
+    Ok(results.into_iter().map(model_to_response).collect())
+}
+
+#[tauri::command]
+pub async fn get_manual_batch_tests(
+    state: State<'_, AppState>,
+    batch: String,
+) -> Result<Vec<ManualTestResponse>, String> {
+    let results = queries::get_manual_batch_tests(&state.manual_db, &batch)
+        .await
+        .map_err(|e| format!("Failed to get manual batch tests: {}", e))?;
+
+    Ok(results.into_iter().map(model_to_response).collect())
+}
+
+#[tauri::command]
+pub async fn get_manual_batches_for_fg(
+    state: State<'_, AppState>,
+    fg: String,
+) -> Result<Vec<String>, String> {
+    queries::get_manual_batches_for_fg(&state.manual_db, &fg)
+        .await
+        .map_err(|e| format!("Failed to get manual batches: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_all_manual_fgs(
+    state: State<'_, AppState>,
+    limit: Option<u64>,
+) -> Result<Vec<queries::ManualFgListItem>, String> {
+    queries::get_all_manual_fgs(&state.manual_db, limit)
+        .await
+        .map_err(|e| format!("Failed to get manual FGs: {}", e))
+}
+
 #[tauri::command]
 pub async fn get_manual_summary(
     state: State<'_, AppState>,
@@ -154,3 +260,30 @@ pub async fn get_manual_base_path(state: State<'_, AppState>) -> Result<String,
 pub async fn set_manual_base_path(state: State<'_, AppState>, path: String) -> Result<(), String> {
     operations::set_base_path(&state.manual_db, &path).await
 }
+
+#[tauri::command]
+pub async fn get_manual_date_format(state: State<'_, AppState>) -> Result<DateOrder, String> {
+    operations::get_date_order(&state.manual_db).await
+}
+
+#[tauri::command]
+pub async fn set_manual_date_format(
+    state: State<'_, AppState>,
+    format: String,
+) -> Result<(), String> {
+    let order = DateOrder::from_setting(&format)?;
+    operations::set_date_order(&state.manual_db, order).await
+}
+
+#[tauri::command]
+pub async fn get_manual_include_patterns(state: State<'_, AppState>) -> Result<String, String> {
+    operations::get_include_patterns_value(&state.manual_db).await
+}
+
+#[tauri::command]
+pub async fn set_manual_include_patterns(
+    state: State<'_, AppState>,
+    patterns: String,
+) -> Result<(), String> {
+    operations::set_include_patterns(&state.manual_db, &patterns).await
+}
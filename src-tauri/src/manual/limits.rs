@@ -0,0 +1,299 @@
+use crate::manual::parser::ManualTestResult;
+use crate::units::Unit;
+use ::entity::{fg, test};
+use entity_manual::manual_test_results;
+use sea_orm::*;
+use serde::Serialize;
+
+/// Relative tolerance used when comparing file limits against spec limits
+const LIMIT_TOLERANCE: f64 = 1e-9;
+
+/// Absolute difference always treated as equal, so a zero limit matches
+/// rounding noise; far below the smallest limits in use (pF, pH)
+const LIMIT_FLOOR: f64 = 1e-18;
+
+/// A warning raised while importing a manual test file
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportWarning {
+    pub file_path: String,
+    pub line_number: usize,
+    pub sn: String,
+    pub test: String,
+    pub kind: String, // "verdict_mismatch", "spec_minimum", "spec_maximum", "spec_unit" or "no_spec"
+    pub message: String,
+}
+
+/// Spec limits for a manual test, taken from the core `test` table
+#[derive(Debug, Clone)]
+pub struct SpecLimit {
+    pub test_id: i32,
+    pub associated_test: String,
+    pub minimum: Option<f64>,
+    pub maximum: Option<f64>,
+    pub uo_m: String,
+}
+
+/// Recompute PASS/FAIL from the limits (inclusive on both ends)
+pub fn compute_passfail(minimum: f64, reading: f64, maximum: f64) -> &'static str {
+    if reading.is_nan() {
+        return "FAIL";
+    }
+
+    if reading >= minimum && reading <= maximum {
+        "PASS"
+    } else {
+        "FAIL"
+    }
+}
+
+/// Verdict of a stored row: recomputed from its limits, or the file's own
+/// PASS/FAIL for rows imported before verdicts were recomputed
+pub fn manual_verdict(record: &manual_test_results::Model) -> &str {
+    record
+        .computed_passfail
+        .as_deref()
+        .unwrap_or(&record.passfail)
+}
+
+/// Check if two verdict strings agree ("Pass", "PASS" and "pass" are equivalent)
+pub fn verdicts_agree(file_verdict: &str, computed: &str) -> bool {
+    file_verdict.trim().eq_ignore_ascii_case(computed)
+}
+
+fn limits_equal(a: f64, b: f64) -> bool {
+    (a - b).abs() <= (LIMIT_TOLERANCE * a.abs().max(b.abs())).max(LIMIT_FLOOR)
+}
+
+/// Whether two declared units mean the same thing ("uH" and "µH", "ohm" and "Ω")
+/// Free-text units are compared ignoring case
+fn units_match(a: &str, b: &str) -> bool {
+    match (Unit::parse(a), Unit::parse(b)) {
+        (Some(a), Some(b)) => a == b,
+        (None, None) => a.trim().eq_ignore_ascii_case(b.trim()),
+        _ => false,
+    }
+}
+
+/// Find the spec for a manual test name
+/// Matches the full manual test name ("132520-LFG-DCR1") or its suffix ("DCR1")
+pub fn find_spec<'a>(specs: &'a [SpecLimit], test_name: &str) -> Option<&'a SpecLimit> {
+    let test_name = test_name.trim();
+
+    specs
+        .iter()
+        .find(|s| s.associated_test.eq_ignore_ascii_case(test_name))
+        .or_else(|| {
+            specs.iter().find(|s| {
+                test_name
+                    .to_ascii_uppercase()
+                    .ends_with(&format!("-{}", s.associated_test.to_ascii_uppercase()))
+            })
+        })
+}
+
+/// Cross-check a parsed row against its own verdict and the spec limits
+pub fn check_row(row: &ManualTestResult, specs: &[SpecLimit]) -> Vec<ImportWarning> {
+    let mut warnings = Vec::new();
+
+    let warning = |kind: &str, message: String| ImportWarning {
+        file_path: row.file_path.clone(),
+        line_number: row.line_number,
+        sn: row.sn.clone(),
+        test: row.test.clone(),
+        kind: kind.to_string(),
+        message,
+    };
+
+    let computed = compute_passfail(row.minimum, row.reading, row.maximum);
+    if !verdicts_agree(&row.passfail, computed) {
+        warnings.push(warning(
+            "verdict_mismatch",
+            format!(
+                "File says {} but reading {} is {} limits [{}, {}]",
+                row.passfail,
+                row.reading,
+                if computed == "PASS" {
+                    "within"
+                } else {
+                    "outside"
+                },
+                row.minimum,
+                row.maximum
+            ),
+        ));
+    }
+
+    match find_spec(specs, &row.test) {
+        Some(spec) => {
+            if let Some(spec_min) = spec.minimum {
+                if !limits_equal(spec_min, row.minimum) {
+                    warnings.push(warning(
+                        "spec_minimum",
+                        format!(
+                            "File minimum {} differs from spec minimum {} (test #{})",
+                            row.minimum, spec_min, spec.test_id
+                        ),
+                    ));
+                }
+            }
+
+            if let Some(spec_max) = spec.maximum {
+                if !limits_equal(spec_max, row.maximum) {
+                    warnings.push(warning(
+                        "spec_maximum",
+                        format!(
+                            "File maximum {} differs from spec maximum {} (test #{})",
+                            row.maximum, spec_max, spec.test_id
+                        ),
+                    ));
+                }
+            }
+
+            if !spec.uo_m.trim().is_empty() && !units_match(&spec.uo_m, &row.uom) {
+                warnings.push(warning(
+                    "spec_unit",
+                    format!(
+                        "File unit '{}' differs from spec unit '{}' (test #{})",
+                        row.uom, spec.uo_m, spec.test_id
+                    ),
+                ));
+            }
+        }
+        None => {
+            warnings.push(warning(
+                "no_spec",
+                format!("No manual test spec found for FG {}", row.fg),
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// Load spec limits for all manual tests of an FG from the core database
+pub async fn load_spec_limits(core_db: &DbConn, fg_number: &str) -> Result<Vec<SpecLimit>, DbErr> {
+    let fg_model = match fg::Entity::find()
+        .filter(fg::Column::Fg.eq(fg_number))
+        .one(core_db)
+        .await?
+    {
+        Some(model) => model,
+        None => return Ok(Vec::new()),
+    };
+
+    let tests = test::Entity::find()
+        .filter(test::Column::FgId.eq(fg_model.id))
+        .filter(test::Column::SourceType.eq("manual"))
+        .all(core_db)
+        .await?;
+
+    Ok(tests
+        .into_iter()
+        .filter_map(|t| {
+            let associated_test = t.associated_test.filter(|a| !a.trim().is_empty())?;
+            Some(SpecLimit {
+                test_id: t.id,
+                associated_test: associated_test.trim().to_string(),
+                minimum: t.minimum,
+                maximum: t.maximum,
+                uo_m: t.uo_m,
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn row(passfail: &str, minimum: f64, reading: f64, maximum: f64) -> ManualTestResult {
+        ManualTestResult {
+            result: 1,
+            test: "132520-LFG-DCR1".to_string(),
+            fg: "132520".to_string(),
+            rev: "A".to_string(),
+            batch: "B1".to_string(),
+            operator: "op".to_string(),
+            date: "11/19/2025".to_string(),
+            time: "12:00".to_string(),
+            sn: "100".to_string(),
+            passfail: passfail.to_string(),
+            minimum,
+            reading,
+            maximum,
+            uom: "Ohm".to_string(),
+            file_path: "f.csv".to_string(),
+            normalized_date: NaiveDate::from_ymd_opt(2025, 11, 19).unwrap(),
+            tested_at: None,
+            line_number: 2,
+        }
+    }
+
+    fn spec(minimum: Option<f64>, maximum: Option<f64>) -> SpecLimit {
+        SpecLimit {
+            test_id: 7,
+            associated_test: "DCR1".to_string(),
+            minimum,
+            maximum,
+            uo_m: "Ohm".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_compute_passfail() {
+        assert_eq!(compute_passfail(1.0, 1.5, 2.0), "PASS");
+        assert_eq!(compute_passfail(1.0, 1.0, 2.0), "PASS");
+        assert_eq!(compute_passfail(1.0, 2.0, 2.0), "PASS");
+        assert_eq!(compute_passfail(1.0, 2.1, 2.0), "FAIL");
+        assert_eq!(compute_passfail(1.0, f64::NAN, 2.0), "FAIL");
+    }
+
+    #[test]
+    fn test_check_row_clean() {
+        let specs = vec![spec(Some(1.0), Some(2.0))];
+        assert!(check_row(&row("PASS", 1.0, 1.5, 2.0), &specs).is_empty());
+    }
+
+    #[test]
+    fn test_check_row_verdict_mismatch() {
+        let specs = vec![spec(Some(1.0), Some(2.0))];
+        let warnings = check_row(&row("PASS", 1.0, 2.5, 2.0), &specs);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, "verdict_mismatch");
+    }
+
+    #[test]
+    fn test_check_row_spec_mismatch() {
+        let specs = vec![spec(Some(1.0), Some(3.0))];
+        let warnings = check_row(&row("PASS", 1.0, 1.5, 2.0), &specs);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, "spec_maximum");
+
+        let warnings = check_row(&row("PASS", 1.0, 1.5, 2.0), &[]);
+        assert_eq!(warnings[0].kind, "no_spec");
+    }
+
+    #[test]
+    fn test_small_limits_compare_relatively() {
+        assert!(limits_equal(1e-12, 1e-12 * (1.0 + 1e-12)));
+        assert!(!limits_equal(1e-12, 2e-12));
+        assert!(!limits_equal(0.0, 1e-12));
+        assert!(limits_equal(0.0, 1e-20));
+        assert!(limits_equal(1000.0, 1000.0 + 1e-7));
+    }
+
+    #[test]
+    fn test_units_match_by_meaning() {
+        assert!(units_match("uH", "µH"));
+        assert!(units_match("ohm", "Ohm"));
+        assert!(units_match("kohm", "kΩ"));
+        assert!(units_match("Turns", "turns"));
+        assert!(!units_match("mH", "uH"));
+        assert!(!units_match("Ohm", "Turns"));
+
+        let mut specs = vec![spec(Some(1.0), Some(2.0))];
+        specs[0].uo_m = "Ω".to_string();
+        assert!(check_row(&row("PASS", 1.0, 1.5, 2.0), &specs).is_empty());
+    }
+}
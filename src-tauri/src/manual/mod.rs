@@ -1,7 +1,9 @@
 pub mod commands;
+pub mod limits;
 pub mod operations;
 pub mod parser;
 pub mod queries;
+pub mod scan;
 
 // Re-export commands for easier access
 pub use commands::*;
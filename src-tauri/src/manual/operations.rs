@@ -1,10 +1,64 @@
+use crate::dates::{DateOrder, DATE_FORMAT_SETTING};
+use crate::manual::limits::{self, ImportWarning, SpecLimit};
 use crate::manual::parser::{parse_manual_csv, ManualTestResult};
+use crate::manual::scan::{
+    list_matching_files, IncludePatterns, DEFAULT_INCLUDE_PATTERNS, INCLUDE_PATTERNS_SETTING,
+};
 use entity_manual::{manual_test_results, processed_files, settings};
 use sea_orm::*;
-use std::path::Path;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Maximum number of files imported at the same time from one folder
+const MAX_CONCURRENT_IMPORTS: usize = 4;
+
+/// Maximum time to wait for a folder listing on the network share
+const DIR_LIST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Outcome of importing one file during a folder import
+#[derive(Debug, Clone, Serialize)]
+pub struct FileImportOutcome {
+    pub file_path: String,
+    pub status: String, // "imported", "skipped" or "failed"
+    pub records_imported: usize,
+    pub warning_count: usize,
+    pub error: Option<String>,
+}
+
+/// Cross-check parsed rows against their own verdicts and the FG spec limits
+async fn check_against_specs(
+    core_db: &DbConn,
+    test_results: &[ManualTestResult],
+) -> Result<Vec<ImportWarning>, String> {
+    let mut specs_by_fg: HashMap<String, Vec<SpecLimit>> = HashMap::new();
+    let mut warnings = Vec::new();
+
+    for row in test_results {
+        if !specs_by_fg.contains_key(&row.fg) {
+            let specs = limits::load_spec_limits(core_db, &row.fg)
+                .await
+                .map_err(|e| format!("Failed to load spec limits for FG {}: {}", row.fg, e))?;
+            specs_by_fg.insert(row.fg.clone(), specs);
+        }
+
+        warnings.extend(limits::check_row(row, &specs_by_fg[&row.fg]));
+    }
+
+    Ok(warnings)
+}
 
 /// Import a single manual test CSV file into the database
-pub async fn import_manual_csv_file(db: &DbConn, file_path: &str) -> Result<usize, String> {
+/// Returns the number of records imported and any limit/verdict warnings
+pub async fn import_manual_csv_file(
+    db: &DbConn,
+    core_db: &DbConn,
+    file_path: &str,
+) -> Result<(usize, Vec<ImportWarning>), String> {
     // Check if file has already been processed
     let existing = processed_files::Entity::find()
         .filter(processed_files::Column::FilePath.eq(file_path))
@@ -13,20 +67,27 @@ pub async fn import_manual_csv_file(db: &DbConn, file_path: &str) -> Result<usiz
         .map_err(|e| format!("Database error checking processed files: {}", e))?;
 
     if existing.is_some() {
-        return Ok(0); // Already processed
+        return Ok((0, Vec::new())); // Already processed
     }
 
     // Parse CSV file
-    let test_results = parse_manual_csv(file_path)?;
+    let date_order = get_date_order(db).await?;
+    let test_results = parse_manual_csv(file_path, date_order).await?;
     let record_count = test_results.len();
 
     if record_count == 0 {
-        return Ok(0);
+        return Ok((0, Vec::new()));
     }
 
+    let warnings = check_against_specs(core_db, &test_results).await?;
+
     let active_models: Vec<manual_test_results::ActiveModel> = test_results
         .into_iter()
         .map(|result| manual_test_results::ActiveModel {
+            computed_passfail: Set(Some(
+                limits::compute_passfail(result.minimum, result.reading, result.maximum)
+                    .to_string(),
+            )),
             result: Set(result.result),
             test: Set(result.test),
             fg: Set(result.fg),
@@ -44,6 +105,7 @@ pub async fn import_manual_csv_file(db: &DbConn, file_path: &str) -> Result<usiz
             file_path: Set(result.file_path),
             created_at: Set(chrono::Utc::now().into()),
             normalized_date: Set(result.normalized_date),
+            tested_at: Set(result.tested_at),
             ..Default::default()
         })
         .collect();
@@ -73,53 +135,220 @@ pub async fn import_manual_csv_file(db: &DbConn, file_path: &str) -> Result<usiz
         .await
         .map_err(|e| format!("Failed to mark file as processed: {}", e))?;
 
-    Ok(record_count)
+    Ok((record_count, warnings))
+}
+
+/// Summary of what importing one file would do, without writing anything
+#[derive(Debug, Clone, Serialize)]
+pub struct FilePreview {
+    pub file_path: String,
+    pub already_processed: bool,
+    pub row_count: usize,
+    pub fgs: Vec<String>,
+    pub revs: Vec<String>,
+    pub batches: Vec<String>,
+    pub warnings: Vec<ImportWarning>,
+    pub error: Option<String>,
 }
 
-/// Import all CSV files from an FG folder
-pub async fn import_manual_fg_folder(db: &DbConn, fg: &str) -> Result<(usize, usize), String> {
+/// Folder of an FG under the base path
+/// Rejects FGs that are empty, absolute or step outside the base path
+fn fg_folder(base_path: &str, fg: &str) -> Result<PathBuf, String> {
+    let fg_path = Path::new(fg.trim());
+    let is_plain = fg_path.components().next().is_some()
+        && fg_path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+    if !is_plain {
+        return Err(format!("Invalid FG folder name: {}", fg));
+    }
+    Ok(Path::new(base_path).join(fg_path))
+}
+
+/// Resolve the FG folder under the base path and list the files to import
+/// Listing runs on the blocking pool, bounded by `DIR_LIST_TIMEOUT`
+async fn list_fg_files(db: &DbConn, fg: &str) -> Result<Vec<PathBuf>, String> {
     let base_path = get_base_path(db).await?;
-    let fg_folder = format!("{}{}", base_path, fg);
+    let fg_folder = fg_folder(&base_path, fg)?;
+    let include = get_include_patterns(db).await?;
+
+    let listing = tokio::task::spawn_blocking(move || list_matching_files(&fg_folder, &include));
 
-    // Check if folder exists
-    let path = Path::new(&fg_folder);
-    if !path.exists() || !path.is_dir() {
-        return Err(format!("FG folder does not exist: {}", fg_folder));
+    match tokio::time::timeout(DIR_LIST_TIMEOUT, listing).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => Err(format!("Directory listing task failed: {}", e)),
+        Err(_) => Err(format!(
+            "Timed out after {}s listing directory",
+            DIR_LIST_TIMEOUT.as_secs()
+        )),
     }
+}
 
-    let mut total_files = 0;
-    let mut total_records = 0;
+/// Dry run of a folder import: parse and check every file without writing to the database
+pub async fn preview_manual_fg_folder(
+    db: &DbConn,
+    core_db: &DbConn,
+    fg: &str,
+) -> Result<Vec<FilePreview>, String> {
+    let files = list_fg_files(db, fg).await?;
+    let date_order = get_date_order(db).await?;
+    let mut previews = Vec::with_capacity(files.len());
 
-    // Read all CSV files in directory
-    let entries =
-        std::fs::read_dir(path).map_err(|e| format!("Failed to read directory: {}", e))?;
+    for file_path in files {
+        let file_path = file_path.to_string_lossy().to_string();
 
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
-        let file_path = entry.path();
+        let already_processed = processed_files::Entity::find()
+            .filter(processed_files::Column::FilePath.eq(file_path.as_str()))
+            .one(db)
+            .await
+            .map_err(|e| format!("Database error checking processed files: {}", e))?
+            .is_some();
 
-        if file_path.extension().and_then(|s| s.to_str()) == Some("csv") {
-            let file_path_str = file_path
-                .to_str()
-                .ok_or_else(|| "Invalid file path".to_string())?;
+        let mut preview = FilePreview {
+            file_path: file_path.clone(),
+            already_processed,
+            row_count: 0,
+            fgs: Vec::new(),
+            revs: Vec::new(),
+            batches: Vec::new(),
+            warnings: Vec::new(),
+            error: None,
+        };
 
-            match import_manual_csv_file(db, file_path_str).await {
-                Ok(count) if count > 0 => {
-                    total_files += 1;
-                    total_records += count;
-                    println!("Imported {} records from {}", count, file_path_str);
+        match parse_manual_csv(&file_path, date_order).await {
+            Ok(rows) => {
+                preview.row_count = rows.len();
+                preview.fgs = distinct(rows.iter().map(|r| r.fg.as_str()));
+                preview.revs = distinct(rows.iter().map(|r| r.rev.as_str()));
+                preview.batches = distinct(rows.iter().map(|r| r.batch.as_str()));
+
+                match check_against_specs(core_db, &rows).await {
+                    Ok(warnings) => preview.warnings = warnings,
+                    Err(e) => preview.error = Some(e),
                 }
-                Ok(_) => {
-                    // Already processed or empty, skip
+            }
+            Err(e) => preview.error = Some(e),
+        }
+
+        previews.push(preview);
+    }
+
+    Ok(previews)
+}
+
+fn distinct<'a>(values: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut values: Vec<String> = values.map(|v| v.to_string()).collect();
+    values.sort();
+    values.dedup();
+    values
+}
+
+/// Import all matching files under an FG folder (recursively)
+/// Files are imported concurrently (at most `MAX_CONCURRENT_IMPORTS` at a time)
+/// and one outcome is returned per file, sorted by path
+pub async fn import_manual_fg_folder(
+    db: &DbConn,
+    core_db: &DbConn,
+    fg: &str,
+) -> Result<(Vec<FileImportOutcome>, Vec<ImportWarning>), String> {
+    let files = list_fg_files(db, fg).await?;
+
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_IMPORTS));
+    let mut tasks = JoinSet::new();
+    // Task id -> file, so a task that panics still reports its file
+    let mut task_files = HashMap::new();
+    let mut outcomes = Vec::new();
+
+    for file_path in files {
+        let Some(file_path_str) = file_path.to_str().map(|s| s.to_string()) else {
+            eprintln!("Skipping non UTF-8 file path: {}", file_path.display());
+            outcomes.push(FileImportOutcome {
+                file_path: file_path.to_string_lossy().to_string(),
+                status: "failed".to_string(),
+                records_imported: 0,
+                warning_count: 0,
+                error: Some("File path is not valid UTF-8".to_string()),
+            });
+            continue;
+        };
+
+        let db = db.clone();
+        let core_db = core_db.clone();
+        let semaphore = semaphore.clone();
+
+        let task_file = file_path_str.clone();
+        let task = tasks.spawn(async move {
+            // The permit is held until this file's import finishes
+            let result = match semaphore.acquire_owned().await {
+                Ok(_permit) => import_manual_csv_file(&db, &core_db, &file_path_str).await,
+                Err(e) => Err(format!("Import queue closed: {}", e)),
+            };
+            (file_path_str, result)
+        });
+        task_files.insert(task.id(), task_file);
+    }
+
+    let mut all_warnings = Vec::new();
+
+    while let Some(joined) = tasks.join_next_with_id().await {
+        let (file_path, result) = match joined {
+            Ok((_, pair)) => pair,
+            Err(e) => {
+                eprintln!("Manual import task failed: {}", e);
+                let file_path = task_files.remove(&e.id()).unwrap_or_default();
+                (file_path, Err(format!("Import task failed: {}", e)))
+            }
+        };
+
+        let outcome = match result {
+            Ok((count, warnings)) if count > 0 => {
+                println!(
+                    "Imported {} records from {} ({} warnings)",
+                    count,
+                    file_path,
+                    warnings.len()
+                );
+                let warning_count = warnings.len();
+                all_warnings.extend(warnings);
+                FileImportOutcome {
+                    file_path,
+                    status: "imported".to_string(),
+                    records_imported: count,
+                    warning_count,
+                    error: None,
                 }
-                Err(e) => {
-                    eprintln!("Error importing {}: {}", file_path_str, e);
+            }
+            Ok(_) => FileImportOutcome {
+                // Already processed or empty
+                file_path,
+                status: "skipped".to_string(),
+                records_imported: 0,
+                warning_count: 0,
+                error: None,
+            },
+            Err(e) => {
+                eprintln!("Error importing {}: {}", file_path, e);
+                FileImportOutcome {
+                    file_path,
+                    status: "failed".to_string(),
+                    records_imported: 0,
+                    warning_count: 0,
+                    error: Some(e),
                 }
             }
-        }
+        };
+
+        outcomes.push(outcome);
     }
 
-    Ok((total_files, total_records))
+    outcomes.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+    all_warnings.sort_by(|a, b| {
+        a.file_path
+            .cmp(&b.file_path)
+            .then(a.line_number.cmp(&b.line_number))
+    });
+
+    Ok((outcomes, all_warnings))
 }
 
 /// Get the base path from settings
@@ -136,27 +365,74 @@ pub async fn get_base_path(db: &DbConn) -> Result<String, String> {
 
 /// Set the base path in settings
 pub async fn set_base_path(db: &DbConn, path: &str) -> Result<(), String> {
+    upsert_setting(db, "base_path", path).await
+}
+
+/// Get the preferred date order for manual files (defaults to US)
+pub async fn get_date_order(db: &DbConn) -> Result<DateOrder, String> {
+    match get_setting(db, DATE_FORMAT_SETTING).await? {
+        Some(value) => DateOrder::from_setting(&value),
+        None => Ok(DateOrder::Us),
+    }
+}
+
+/// Set the preferred date order for manual files
+pub async fn set_date_order(db: &DbConn, order: DateOrder) -> Result<(), String> {
+    upsert_setting(db, DATE_FORMAT_SETTING, order.as_str()).await
+}
+
+/// Get the include patterns for folder imports as stored (defaults to "*.csv")
+pub async fn get_include_patterns_value(db: &DbConn) -> Result<String, String> {
+    Ok(get_setting(db, INCLUDE_PATTERNS_SETTING)
+        .await?
+        .unwrap_or_else(|| DEFAULT_INCLUDE_PATTERNS.to_string()))
+}
+
+/// Get the compiled include patterns for folder imports
+pub async fn get_include_patterns(db: &DbConn) -> Result<IncludePatterns, String> {
+    IncludePatterns::parse(&get_include_patterns_value(db).await?)
+}
+
+/// Set the include patterns for folder imports (validated before saving)
+pub async fn set_include_patterns(db: &DbConn, patterns: &str) -> Result<(), String> {
+    IncludePatterns::parse(patterns)?;
+    upsert_setting(db, INCLUDE_PATTERNS_SETTING, patterns.trim()).await
+}
+
+/// Get a setting value by key
+async fn get_setting(db: &DbConn, key: &str) -> Result<Option<String>, String> {
+    let setting = settings::Entity::find()
+        .filter(settings::Column::Key.eq(key))
+        .one(db)
+        .await
+        .map_err(|e| format!("Failed to get {} setting: {}", key, e))?;
+
+    Ok(setting.map(|s| s.value))
+}
+
+/// Insert or update a setting by key
+async fn upsert_setting(db: &DbConn, key: &str, value: &str) -> Result<(), String> {
     let existing = settings::Entity::find()
-        .filter(settings::Column::Key.eq("base_path"))
+        .filter(settings::Column::Key.eq(key))
         .one(db)
         .await
-        .map_err(|e| format!("Failed to check base_path setting: {}", e))?;
+        .map_err(|e| format!("Failed to check {} setting: {}", key, e))?;
 
     if let Some(setting) = existing {
         // Update existing
         let mut active: settings::ActiveModel = setting.into();
-        active.value = Set(path.to_string());
+        active.value = Set(value.to_string());
         active.updated_at = Set(chrono::Utc::now().into());
 
         active
             .update(db)
             .await
-            .map_err(|e| format!("Failed to update base_path: {}", e))?;
+            .map_err(|e| format!("Failed to update {}: {}", key, e))?;
     } else {
         // Insert new
         let new_setting = settings::ActiveModel {
-            key: Set("base_path".to_string()),
-            value: Set(path.to_string()),
+            key: Set(key.to_string()),
+            value: Set(value.to_string()),
             updated_at: Set(chrono::Utc::now().into()),
             ..Default::default()
         };
@@ -164,8 +440,24 @@ pub async fn set_base_path(db: &DbConn, path: &str) -> Result<(), String> {
         new_setting
             .insert(db)
             .await
-            .map_err(|e| format!("Failed to insert base_path: {}", e))?;
+            .map_err(|e| format!("Failed to insert {}: {}", key, e))?;
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fg_folder_stays_under_base_path() {
+        assert_eq!(
+            fg_folder("/share", " 12345 ").unwrap(),
+            Path::new("/share").join("12345")
+        );
+        for fg in ["", "  ", "..", "../other", "12345/../..", "/etc", "./12345"] {
+            assert!(fg_folder("/share", fg).is_err(), "{fg:?} was accepted");
+        }
+    }
+}
@@ -1,10 +1,12 @@
-use chrono::NaiveDate;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use crate::dates::{self, DateOrder};
+use chrono::{NaiveDate, NaiveDateTime};
+use std::future::Future;
 use std::path::Path;
-use std::thread;
 use std::time::Duration;
 
+/// Maximum time to wait for a single read of a file on the network share
+const FILE_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// Represents a parsed manual test result from CSV
 #[derive(Debug, Clone)]
 pub struct ManualTestResult {
@@ -24,77 +26,70 @@ pub struct ManualTestResult {
     pub uom: String,
     pub file_path: String,
     pub normalized_date: NaiveDate,
+    pub tested_at: Option<NaiveDateTime>, // None if the time column could not be parsed
+    pub line_number: usize,               // 1-based line in the source file
 }
 
-/// Parse a date string in format "11/19/2025" or "MM/DD/YYYY" to NaiveDate
-pub fn parse_date(date_str: &str) -> Result<NaiveDate, String> {
-    // Try MM/DD/YYYY format first
-    if let Ok(date) = NaiveDate::parse_from_str(date_str, "%m/%d/%Y") {
-        return Ok(date);
-    }
-
-    // Try M/D/YYYY format
-    if let Ok(date) = NaiveDate::parse_from_str(date_str, "%-m/%-d/%Y") {
-        return Ok(date);
-    }
-
-    // Try parsing manually
-    let parts: Vec<&str> = date_str.split('/').collect();
-    if parts.len() == 3 {
-        if let (Ok(month), Ok(day), Ok(year)) = (
-            parts[0].parse::<u32>(),
-            parts[1].parse::<u32>(),
-            parts[2].parse::<i32>(),
-        ) {
-            return NaiveDate::from_ymd_opt(year, month, day)
-                .ok_or_else(|| format!("Invalid date components: {}/{}/{}", month, day, year));
-        }
-    }
-
-    Err(format!("Could not parse date: {}", date_str))
-}
-
-/// Retry file open with exponential backoff for network share file locks
-pub fn retry_with_backoff<F, T, E>(mut f: F) -> Result<T, E>
+/// Retry an async operation with exponential backoff for network share file locks
+/// Waits with `tokio::time::sleep` so no runtime worker is blocked between attempts
+pub async fn retry_with_backoff<F, Fut, T, E>(mut f: F) -> Result<T, E>
 where
-    F: FnMut() -> Result<T, E>,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
 {
     const MAX_RETRIES: u32 = 3;
     const INITIAL_DELAY_MS: u64 = 1000;
 
-    for attempt in 0..MAX_RETRIES {
-        match f() {
+    let mut attempt = 0;
+    loop {
+        match f().await {
             Ok(result) => return Ok(result),
             Err(e) => {
-                if attempt < MAX_RETRIES - 1 {
-                    let delay = INITIAL_DELAY_MS * 2u64.pow(attempt);
-                    thread::sleep(Duration::from_millis(delay));
-                } else {
+                attempt += 1;
+                if attempt >= MAX_RETRIES {
                     return Err(e);
                 }
+                let delay = INITIAL_DELAY_MS * 2u64.pow(attempt - 1);
+                tokio::time::sleep(Duration::from_millis(delay)).await;
             }
         }
     }
+}
 
-    unreachable!()
+/// Read a whole file on the blocking pool, giving up after `FILE_READ_TIMEOUT`
+pub async fn read_file_with_timeout(file_path: &str) -> Result<String, String> {
+    match tokio::time::timeout(FILE_READ_TIMEOUT, tokio::fs::read_to_string(file_path)).await {
+        Ok(Ok(content)) => Ok(content),
+        Ok(Err(e)) => Err(format!("Failed to read file: {}", e)),
+        Err(_) => Err(format!(
+            "Timed out after {}s reading file",
+            FILE_READ_TIMEOUT.as_secs()
+        )),
+    }
 }
 
-/// Parse a manual test CSV file
+/// Read and parse a manual test CSV file
+/// Reads are retried with backoff and each attempt is bounded by a timeout
+pub async fn parse_manual_csv(
+    file_path: &str,
+    date_order: DateOrder,
+) -> Result<Vec<ManualTestResult>, String> {
+    let content = retry_with_backoff(|| read_file_with_timeout(file_path)).await?;
+    parse_manual_csv_content(&content, file_path, date_order)
+}
+
+/// Parse the contents of a manual test CSV file
 /// CSV format: result,test,fg,rev,batch,operator,date,time,sn,passfail,minimum,reading,maximum,uom
 /// Lines starting with "#" are skipped as headers
-pub fn parse_manual_csv(file_path: &str) -> Result<Vec<ManualTestResult>, String> {
-    // Open file with retry for network shares
-    let file = retry_with_backoff(|| {
-        File::open(file_path).map_err(|e| format!("Failed to open file: {}", e))
-    })?;
-
-    let reader = BufReader::new(file);
+/// Dates are read using the configured `date_order`
+pub fn parse_manual_csv_content(
+    content: &str,
+    file_path: &str,
+    date_order: DateOrder,
+) -> Result<Vec<ManualTestResult>, String> {
     let mut results = Vec::new();
 
-    for (line_num, line_result) in reader.lines().enumerate() {
-        let line =
-            line_result.map_err(|e| format!("Failed to read line {}: {}", line_num + 1, e))?;
-
+    for (line_num, line) in content.lines().enumerate() {
         // Skip empty lines and headers (lines starting with #)
         if line.trim().is_empty() || line.trim().starts_with('#') {
             continue;
@@ -119,8 +114,9 @@ pub fn parse_manual_csv(file_path: &str) -> Result<Vec<ManualTestResult>, String
             .map_err(|e| format!("Invalid result number on line {}: {}", line_num + 1, e))?;
 
         let date_str = fields[6].trim();
-        let normalized_date =
-            parse_date(date_str).map_err(|e| format!("Line {}: {}", line_num + 1, e))?;
+        let normalized_date = dates::parse_date(date_str, date_order)
+            .map_err(|e| format!("Line {}: {}", line_num + 1, e))?;
+        let time_str = fields[7].trim();
 
         let minimum = fields[10]
             .trim()
@@ -145,7 +141,7 @@ pub fn parse_manual_csv(file_path: &str) -> Result<Vec<ManualTestResult>, String
             batch: fields[4].trim().to_string(),
             operator: fields[5].trim().to_string(),
             date: date_str.to_string(),
-            time: fields[7].trim().to_string(),
+            time: time_str.to_string(),
             sn: fields[8].trim().to_string(),
             passfail: fields[9].trim().to_string(),
             minimum,
@@ -154,6 +150,8 @@ pub fn parse_manual_csv(file_path: &str) -> Result<Vec<ManualTestResult>, String
             uom: fields[13].trim().to_string(),
             file_path: file_path.to_string(),
             normalized_date,
+            tested_at: dates::combine_date_time(normalized_date, time_str),
+            line_number: line_num + 1,
         });
     }
 
@@ -165,25 +163,22 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_parse_date() {
-        assert_eq!(
-            parse_date("11/19/2025").unwrap(),
-            NaiveDate::from_ymd_opt(2025, 11, 19).unwrap()
-        );
+    fn test_parse_manual_csv_content() {
+        let content = "# header\n\
+            1,132520-LFG-DCR1,132520,A,B1,op,11/19/2025,14:05,100,PASS,1.0,1.5,2.0,Ohm\n\
+            \n\
+            2,132520-LFG-DCR1,132520,A,B1,op,11/19/2025,14:06,101,FAIL,1.0,2.5,2.0,Ohm\n";
+
+        let results = parse_manual_csv_content(content, "f.csv", DateOrder::Us).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].line_number, 2);
+        assert_eq!(results[1].line_number, 4);
+        assert_eq!(results[1].sn, "101");
         assert_eq!(
-            parse_date("1/5/2025").unwrap(),
-            NaiveDate::from_ymd_opt(2025, 1, 5).unwrap()
+            results[0].tested_at,
+            NaiveDate::from_ymd_opt(2025, 11, 19)
+                .unwrap()
+                .and_hms_opt(14, 5, 0)
         );
-        assert_eq!(
-            parse_date("12/31/2024").unwrap(),
-            NaiveDate::from_ymd_opt(2024, 12, 31).unwrap()
-        );
-    }
-
-    #[test]
-    fn test_parse_date_invalid() {
-        assert!(parse_date("invalid").is_err());
-        assert!(parse_date("13/1/2025").is_err()); // Invalid month
-        assert!(parse_date("1/32/2025").is_err()); // Invalid day
     }
 }
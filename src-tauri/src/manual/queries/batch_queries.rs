@@ -0,0 +1,163 @@
+use super::VERDICT;
+use crate::voltech::queries::{BatchListItem, BatchSearchFilter, BatchSummary};
+use entity_manual::manual_test_results;
+use sea_orm::*;
+
+// Manual batch rows reuse the Voltech batch shapes, with `part` holding the FG
+// and `date` holding the normalized date (YYYY-MM-DD)
+
+/// Get detailed batch summary
+/// Counts cover the whole batch; a batch run for several FGs lists them all in `part`
+pub async fn get_manual_batch_details(
+    db: &DbConn,
+    batch: &str,
+) -> Result<Option<BatchSummary>, DbErr> {
+    let sql = format!(
+        r#"
+        SELECT
+            batch,
+            GROUP_CONCAT(DISTINCT fg) as part,
+            MIN(normalized_date) as date,
+            GROUP_CONCAT(DISTINCT operator) as operator,
+            COUNT(*) as total_tests,
+            SUM(CASE WHEN {verdict} = 'PASS' THEN 1 ELSE 0 END) as passed,
+            SUM(CASE WHEN {verdict} != 'PASS' THEN 1 ELSE 0 END) as failed,
+            CAST(SUM(CASE WHEN {verdict} = 'PASS' THEN 1.0 ELSE 0.0 END) * 100.0 / COUNT(*) AS REAL) as pass_rate
+        FROM manual_test_results
+        WHERE batch = ?
+        GROUP BY batch
+    "#,
+        verdict = VERDICT
+    );
+
+    BatchSummary::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        &sql,
+        [batch.into()],
+    ))
+    .one(db)
+    .await
+}
+
+/// Get all manual tests for a batch
+pub async fn get_manual_batch_tests(
+    db: &DbConn,
+    batch: &str,
+) -> Result<Vec<manual_test_results::Model>, DbErr> {
+    manual_test_results::Entity::find()
+        .filter(manual_test_results::Column::Batch.eq(batch))
+        .order_by_asc(manual_test_results::Column::Sn)
+        .order_by_asc(manual_test_results::Column::Result)
+        .all(db)
+        .await
+}
+
+/// Get all unique batch numbers for an FG
+pub async fn get_manual_batches_for_fg(db: &DbConn, fg: &str) -> Result<Vec<String>, DbErr> {
+    manual_test_results::Entity::find()
+        .select_only()
+        .column(manual_test_results::Column::Batch)
+        .filter(manual_test_results::Column::Fg.eq(fg))
+        .distinct()
+        .order_by_desc(manual_test_results::Column::Batch)
+        .into_tuple::<String>()
+        .all(db)
+        .await
+}
+
+/// Search manual batches with filters
+pub async fn search_manual_batches(
+    db: &DbConn,
+    filter: BatchSearchFilter,
+) -> Result<Vec<BatchListItem>, DbErr> {
+    let mut conditions = Vec::new();
+    let mut params: Vec<Value> = Vec::new();
+
+    if let Some(fg) = &filter.part {
+        conditions.push("fg = ?");
+        params.push(fg.clone().into());
+    }
+
+    if let Some(from) = &filter.date_from {
+        conditions.push("normalized_date >= ?");
+        params.push(from.clone().into());
+    }
+
+    if let Some(to) = &filter.date_to {
+        conditions.push("normalized_date <= ?");
+        params.push(to.clone().into());
+    }
+
+    if let Some(op) = &filter.operator {
+        conditions.push("operator = ?");
+        params.push(op.clone().into());
+    }
+
+    let where_clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    };
+
+    let limit = filter.limit.unwrap_or(100);
+    let offset = filter.offset.unwrap_or(0);
+
+    let sql = format!(
+        r#"
+        SELECT
+            batch,
+            fg as part,
+            normalized_date as date,
+            operator,
+            COUNT(*) as total_tests,
+            SUM(CASE WHEN {verdict} = 'PASS' THEN 1 ELSE 0 END) as passed,
+            SUM(CASE WHEN {verdict} != 'PASS' THEN 1 ELSE 0 END) as failed,
+            CAST(SUM(CASE WHEN {verdict} = 'PASS' THEN 1.0 ELSE 0.0 END) * 100.0 / COUNT(*) AS REAL) as pass_rate
+        FROM manual_test_results
+        {where_clause}
+        GROUP BY batch, fg, normalized_date, operator
+        ORDER BY MAX(created_at) DESC
+        LIMIT ? OFFSET ?
+        "#,
+        verdict = VERDICT,
+        where_clause = where_clause
+    );
+
+    params.push((limit as i64).into());
+    params.push((offset as i64).into());
+
+    BatchListItem::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        &sql,
+        params,
+    ))
+    .all(db)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manual::queries::test_db;
+
+    #[tokio::test]
+    async fn test_batch_details_cover_every_fg_and_use_computed_verdict() {
+        let db = test_db(&[
+            ("132520", "B1", "AB", "PASS", Some("FAIL")), // Verdict from the file was wrong
+            ("132520", "B1", "AB", "PASS", Some("PASS")),
+            ("140010", "B1", "CD", "Pass", None), // Imported before recomputation
+            ("140010", "B2", "CD", "PASS", Some("PASS")),
+        ])
+        .await;
+
+        let summary = get_manual_batch_details(&db, "B1").await.unwrap().unwrap();
+        assert_eq!(summary.total_tests, 3);
+        assert_eq!(summary.passed, 2);
+        assert_eq!(summary.failed, 1);
+        let mut fgs: Vec<&str> = summary.part.split(',').collect();
+        fgs.sort();
+        assert_eq!(fgs, vec!["132520", "140010"]);
+
+        assert!(get_manual_batch_details(&db, "B9").await.unwrap().is_none());
+    }
+}
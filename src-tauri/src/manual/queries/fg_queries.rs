@@ -0,0 +1,173 @@
+use super::VERDICT;
+use crate::manual::limits::manual_verdict;
+use entity_manual::manual_test_results;
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromQueryResult)]
+pub struct ManualFgListItem {
+    pub fg: String,
+    pub total_tests: i64,
+    pub passed: i64,
+    pub failed: i64,
+    pub pass_rate: f64,
+}
+
+/// Get all FGs with manual test statistics
+pub async fn get_all_manual_fgs(
+    db: &DbConn,
+    limit: Option<u64>,
+) -> Result<Vec<ManualFgListItem>, DbErr> {
+    let limit = limit.unwrap_or(100);
+
+    let sql = format!(
+        r#"
+        SELECT
+            fg,
+            COUNT(*) as total_tests,
+            SUM(CASE WHEN {verdict} = 'PASS' THEN 1 ELSE 0 END) as passed,
+            SUM(CASE WHEN {verdict} != 'PASS' THEN 1 ELSE 0 END) as failed,
+            CAST(SUM(CASE WHEN {verdict} = 'PASS' THEN 1.0 ELSE 0.0 END) * 100.0 / COUNT(*) AS REAL) as pass_rate
+        FROM manual_test_results
+        GROUP BY fg
+        ORDER BY total_tests DESC
+        LIMIT ?
+    "#,
+        verdict = VERDICT
+    );
+
+    ManualFgListItem::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        &sql,
+        [(limit as i64).into()],
+    ))
+    .all(db)
+    .await
+}
+
+/// Get all unique FG numbers
+pub async fn get_all_manual_fg_numbers(db: &DbConn) -> Result<Vec<String>, DbErr> {
+    #[derive(Debug, FromQueryResult)]
+    struct FgResult {
+        fg: String,
+    }
+
+    let sql = r#"
+        SELECT DISTINCT fg
+        FROM manual_test_results
+        ORDER BY fg ASC
+    "#;
+
+    let results =
+        FgResult::find_by_statement(Statement::from_sql_and_values(DbBackend::Sqlite, sql, []))
+            .all(db)
+            .await?;
+
+    Ok(results.into_iter().map(|r| r.fg).collect())
+}
+
+/// Get detailed FG summary with batch count and date range
+/// Shaped like the Voltech part summary so both sources can share one API
+pub async fn get_manual_fg_summary(
+    db: &DbConn,
+    fg: &str,
+) -> Result<Option<crate::voltech::queries::PartSummary>, DbErr> {
+    let sql = format!(
+        r#"
+        SELECT
+            fg as part,
+            COUNT(DISTINCT batch) as total_batches,
+            COUNT(*) as total_tests,
+            SUM(CASE WHEN {verdict} = 'PASS' THEN 1 ELSE 0 END) as passed,
+            SUM(CASE WHEN {verdict} != 'PASS' THEN 1 ELSE 0 END) as failed,
+            CAST(SUM(CASE WHEN {verdict} = 'PASS' THEN 1.0 ELSE 0.0 END) * 100.0 / COUNT(*) AS REAL) as pass_rate,
+            MIN(normalized_date) as first_date,
+            MAX(normalized_date) as last_date
+        FROM manual_test_results
+        WHERE fg = ?
+        GROUP BY fg
+    "#,
+        verdict = VERDICT
+    );
+
+    crate::voltech::queries::PartSummary::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        &sql,
+        [fg.into()],
+    ))
+    .one(db)
+    .await
+}
+
+/// Get summary of manual test data for an FG
+pub async fn get_manual_test_summary_for_fg(
+    db: &DbConn,
+    fg: &str,
+) -> Result<ManualTestSummary, DbErr> {
+    let tests = manual_test_results::Entity::find()
+        .filter(manual_test_results::Column::Fg.eq(fg))
+        .all(db)
+        .await?;
+
+    let total_tests = tests.len();
+    let passed = tests
+        .iter()
+        .filter(|t| manual_verdict(t).eq_ignore_ascii_case("PASS"))
+        .count();
+    let failed = total_tests - passed;
+
+    let unique_batches: std::collections::HashSet<_> =
+        tests.iter().map(|t| t.batch.clone()).collect();
+
+    let unique_tests: std::collections::HashSet<_> = tests.iter().map(|t| t.test.clone()).collect();
+
+    Ok(ManualTestSummary {
+        fg: fg.to_string(),
+        total_tests,
+        passed_tests: passed,
+        failed_tests: failed,
+        unique_batches: unique_batches.len(),
+        unique_test_types: unique_tests.len(),
+    })
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ManualTestSummary {
+    pub fg: String,
+    pub total_tests: usize,
+    pub passed_tests: usize,
+    pub failed_tests: usize,
+    pub unique_batches: usize,
+    pub unique_test_types: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manual::queries::test_db;
+
+    #[tokio::test]
+    async fn test_fg_summaries_count_computed_verdicts() {
+        let db = test_db(&[
+            ("132520", "B1", "AB", "PASS", Some("FAIL")),
+            ("132520", "B2", "AB", "PASS", Some("PASS")),
+            ("140010", "B3", "CD", "PASS", None),
+        ])
+        .await;
+
+        let fgs = get_all_manual_fgs(&db, None).await.unwrap();
+        assert_eq!(fgs[0].fg, "132520");
+        assert_eq!((fgs[0].passed, fgs[0].failed), (1, 1));
+        assert_eq!(
+            get_all_manual_fg_numbers(&db).await.unwrap(),
+            vec!["132520", "140010"]
+        );
+
+        let summary = get_manual_fg_summary(&db, "132520").await.unwrap().unwrap();
+        assert_eq!(summary.total_batches, 2);
+        assert_eq!(summary.failed, 1);
+
+        let tests = get_manual_test_summary_for_fg(&db, "132520").await.unwrap();
+        assert_eq!((tests.passed_tests, tests.failed_tests), (1, 1));
+    }
+}
@@ -0,0 +1,53 @@
+pub mod batch_queries;
+pub mod fg_queries;
+pub mod stats_queries;
+pub mod test_queries;
+
+pub use batch_queries::*;
+pub use fg_queries::*;
+pub use stats_queries::*;
+pub use test_queries::*;
+
+/// Verdict the statistics count: recomputed from the limits at import, or the
+/// file's own verdict for rows imported before it was recomputed
+pub(crate) const VERDICT: &str = "UPPER(COALESCE(computed_passfail, passfail))";
+
+/// In-memory manual database holding the given rows, for query tests
+/// Rows are (fg, batch, operator, file verdict, computed verdict)
+#[cfg(test)]
+pub(crate) async fn test_db(rows: &[(&str, &str, &str, &str, Option<&str>)]) -> sea_orm::DbConn {
+    use entity_manual::manual_test_results;
+    use migration_manual::{Migrator, MigratorTrait};
+    use sea_orm::{ActiveModelTrait, Database, Set};
+
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    Migrator::up(&db, None).await.unwrap();
+
+    for (sn, (fg, batch, operator, passfail, computed)) in rows.iter().enumerate() {
+        manual_test_results::ActiveModel {
+            result: Set(1),
+            test: Set(format!("{}-LFG-DCR1", fg)),
+            fg: Set(fg.to_string()),
+            rev: Set("A".to_string()),
+            batch: Set(batch.to_string()),
+            operator: Set(operator.to_string()),
+            date: Set("11/19/2025".to_string()),
+            time: Set("12:00".to_string()),
+            sn: Set((1001 + sn).to_string()),
+            passfail: Set(passfail.to_string()),
+            minimum: Set(1.0),
+            reading: Set(1.5),
+            maximum: Set(2.0),
+            uom: Set("Ohm".to_string()),
+            file_path: Set("f.csv".to_string()),
+            created_at: Set(chrono::Utc::now().into()),
+            normalized_date: Set(chrono::NaiveDate::from_ymd_opt(2025, 11, 19).unwrap()),
+            computed_passfail: Set(computed.map(str::to_string)),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+    }
+    db
+}
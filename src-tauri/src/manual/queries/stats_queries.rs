@@ -0,0 +1,171 @@
+use super::VERDICT;
+use crate::voltech::queries::{DailyStats, OperatorStats, OverallStats};
+use sea_orm::*;
+
+// Manual stats reuse the Voltech stats shapes; `parts` count distinct FGs
+// and dates are the normalized date (YYYY-MM-DD)
+
+fn date_where_clause(date_from: Option<&str>, date_to: Option<&str>) -> (String, Vec<Value>) {
+    let mut conditions = Vec::new();
+    let mut params: Vec<Value> = Vec::new();
+
+    if let Some(from) = date_from {
+        conditions.push("normalized_date >= ?");
+        params.push(from.into());
+    }
+
+    if let Some(to) = date_to {
+        conditions.push("normalized_date <= ?");
+        params.push(to.into());
+    }
+
+    let where_clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    };
+
+    (where_clause, params)
+}
+
+/// Get daily statistics for manual tests
+pub async fn get_manual_daily_stats(
+    db: &DbConn,
+    date_from: Option<&str>,
+    date_to: Option<&str>,
+) -> Result<Vec<DailyStats>, DbErr> {
+    let (where_clause, params) = date_where_clause(date_from, date_to);
+
+    let sql = format!(
+        r#"
+        SELECT
+            normalized_date as date,
+            COUNT(*) as total_tests,
+            COALESCE(SUM(CASE WHEN {verdict} = 'PASS' THEN 1 ELSE 0 END), 0) as passed,
+            COALESCE(SUM(CASE WHEN {verdict} != 'PASS' THEN 1 ELSE 0 END), 0) as failed,
+            COALESCE(CAST(SUM(CASE WHEN {verdict} = 'PASS' THEN 1.0 ELSE 0.0 END) * 100.0 / NULLIF(COUNT(*), 0) AS REAL), 0.0) as pass_rate,
+            COUNT(DISTINCT fg) as total_parts,
+            COUNT(DISTINCT batch) as total_batches
+        FROM manual_test_results
+        {where_clause}
+        GROUP BY normalized_date
+        ORDER BY normalized_date DESC
+        "#,
+        verdict = VERDICT,
+        where_clause = where_clause
+    );
+
+    DailyStats::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        &sql,
+        params,
+    ))
+    .all(db)
+    .await
+}
+
+/// Get operator statistics for manual tests
+pub async fn get_manual_operator_stats(
+    db: &DbConn,
+    date_from: Option<&str>,
+    date_to: Option<&str>,
+) -> Result<Vec<OperatorStats>, DbErr> {
+    let (where_clause, params) = date_where_clause(date_from, date_to);
+
+    let sql = format!(
+        r#"
+        SELECT
+            operator,
+            COUNT(*) as total_tests,
+            COALESCE(SUM(CASE WHEN {verdict} = 'PASS' THEN 1 ELSE 0 END), 0) as passed,
+            COALESCE(SUM(CASE WHEN {verdict} != 'PASS' THEN 1 ELSE 0 END), 0) as failed,
+            COALESCE(CAST(SUM(CASE WHEN {verdict} = 'PASS' THEN 1.0 ELSE 0.0 END) * 100.0 / NULLIF(COUNT(*), 0) AS REAL), 0.0) as pass_rate,
+            COUNT(DISTINCT fg) as parts_tested,
+            COUNT(DISTINCT batch) as batches_completed
+        FROM manual_test_results
+        {where_clause}
+        GROUP BY operator
+        ORDER BY total_tests DESC
+        "#,
+        verdict = VERDICT,
+        where_clause = where_clause
+    );
+
+    OperatorStats::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        &sql,
+        params,
+    ))
+    .all(db)
+    .await
+}
+
+/// Get overall statistics for manual tests, optionally for one FG
+pub async fn get_manual_overall_stats(
+    db: &DbConn,
+    fg: Option<&str>,
+) -> Result<Option<OverallStats>, DbErr> {
+    let (where_clause, params) = match fg {
+        Some(fg) => ("WHERE fg = ?".to_string(), vec![fg.into()]),
+        None => (String::new(), Vec::new()),
+    };
+
+    let sql = format!(
+        r#"
+        SELECT
+            COUNT(*) as total_tests,
+            COUNT(DISTINCT fg) as total_parts,
+            COUNT(DISTINCT batch) as total_batches,
+            COUNT(DISTINCT operator) as total_operators,
+            COALESCE(SUM(CASE WHEN {verdict} = 'PASS' THEN 1 ELSE 0 END), 0) as passed,
+            COALESCE(SUM(CASE WHEN {verdict} != 'PASS' THEN 1 ELSE 0 END), 0) as failed,
+            COALESCE(CAST(SUM(CASE WHEN {verdict} = 'PASS' THEN 1.0 ELSE 0.0 END) * 100.0 / NULLIF(COUNT(*), 0) AS REAL), 0.0) as pass_rate
+        FROM manual_test_results
+        {where_clause}
+        "#,
+        verdict = VERDICT,
+        where_clause = where_clause
+    );
+
+    OverallStats::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        &sql,
+        params,
+    ))
+    .one(db)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manual::queries::test_db;
+
+    #[tokio::test]
+    async fn test_stats_count_computed_verdicts() {
+        let db = test_db(&[
+            ("132520", "B1", "AB", "PASS", Some("FAIL")),
+            ("132520", "B1", "AB", "FAIL", Some("PASS")),
+            ("140010", "B2", "CD", "fail", None),
+        ])
+        .await;
+
+        let overall = get_manual_overall_stats(&db, None).await.unwrap().unwrap();
+        assert_eq!((overall.passed, overall.failed), (1, 2));
+        assert_eq!(overall.total_parts, 2);
+
+        let operators = get_manual_operator_stats(&db, None, None).await.unwrap();
+        assert_eq!(operators[0].operator, "AB");
+        assert_eq!((operators[0].passed, operators[0].failed), (1, 1));
+
+        let daily = get_manual_daily_stats(&db, Some("2025-11-19"), Some("2025-11-19"))
+            .await
+            .unwrap();
+        assert_eq!(daily.len(), 1);
+        assert_eq!(daily[0].total_batches, 2);
+        assert!(get_manual_daily_stats(&db, Some("2025-11-20"), None)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
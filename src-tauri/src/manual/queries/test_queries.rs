@@ -0,0 +1,226 @@
+use super::VERDICT;
+use crate::serials::SerialSpec;
+use entity_manual::manual_test_results;
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+
+/// Get all unique test names for a specific FG
+pub async fn get_manual_test_names_for_fg(db: &DbConn, fg: &str) -> Result<Vec<String>, DbErr> {
+    let test_names = manual_test_results::Entity::find()
+        .select_only()
+        .column(manual_test_results::Column::Test)
+        .filter(manual_test_results::Column::Fg.eq(fg))
+        .distinct()
+        .into_tuple::<String>()
+        .all(db)
+        .await?;
+
+    let mut test_names = test_names;
+    test_names.sort();
+    Ok(test_names)
+}
+
+/// Get manual tests by FG and batch, optionally filtered by date
+pub async fn get_manual_tests_by_fg_batch(
+    db: &DbConn,
+    fg: &str,
+    batch: &str,
+    date: Option<chrono::NaiveDate>,
+) -> Result<Vec<manual_test_results::Model>, DbErr> {
+    let mut query = manual_test_results::Entity::find()
+        .filter(manual_test_results::Column::Fg.eq(fg))
+        .filter(manual_test_results::Column::Batch.eq(batch));
+
+    if let Some(d) = date {
+        query = query.filter(manual_test_results::Column::NormalizedDate.eq(d));
+    }
+
+    query.all(db).await
+}
+
+/// Get manual tests filtered by multiple criteria
+pub async fn get_manual_tests_filtered(
+    db: &DbConn,
+    fg: Option<&str>,
+    batch: Option<&str>,
+    test_name: Option<&str>,
+    serial_num: Option<&str>,
+    date_from: Option<chrono::NaiveDate>,
+    date_to: Option<chrono::NaiveDate>,
+) -> Result<Vec<manual_test_results::Model>, DbErr> {
+    let mut query = manual_test_results::Entity::find();
+
+    if let Some(f) = fg {
+        query = query.filter(manual_test_results::Column::Fg.eq(f));
+    }
+
+    if let Some(b) = batch {
+        query = query.filter(manual_test_results::Column::Batch.eq(b));
+    }
+
+    if let Some(t) = test_name {
+        query = query.filter(manual_test_results::Column::Test.eq(t));
+    }
+
+    if let Some(s) = serial_num {
+        query = match SerialSpec::parse(s) {
+            Ok(serials) => query.filter(serials.condition(manual_test_results::Column::Sn)),
+            Err(_) => query.filter(manual_test_results::Column::Sn.eq(s)),
+        };
+    }
+
+    if let Some(from) = date_from {
+        query = query.filter(manual_test_results::Column::NormalizedDate.gte(from));
+    }
+
+    if let Some(to) = date_to {
+        query = query.filter(manual_test_results::Column::NormalizedDate.lte(to));
+    }
+
+    query
+        .order_by_desc(manual_test_results::Column::CreatedAt)
+        .all(db)
+        .await
+}
+
+/// Search manual tests with filters
+/// Dates are compared against `normalized_date` (YYYY-MM-DD)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ManualTestSearchFilter {
+    pub fg: Option<String>,
+    pub batch: Option<String>,
+    pub test_name: Option<String>,
+    pub operator: Option<String>,
+    pub date_from: Option<String>,
+    pub date_to: Option<String>,
+    pub pass_fail: Option<String>,
+    pub serial_num: Option<String>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+fn apply_search_filter(
+    mut query: Select<manual_test_results::Entity>,
+    filter: &ManualTestSearchFilter,
+) -> Select<manual_test_results::Entity> {
+    if let Some(fg) = &filter.fg {
+        query = query.filter(manual_test_results::Column::Fg.eq(fg.as_str()));
+    }
+
+    if let Some(batch) = &filter.batch {
+        query = query.filter(manual_test_results::Column::Batch.eq(batch.as_str()));
+    }
+
+    if let Some(test_name) = &filter.test_name {
+        query = query.filter(manual_test_results::Column::Test.eq(test_name.as_str()));
+    }
+
+    if let Some(operator) = &filter.operator {
+        query = query.filter(manual_test_results::Column::Operator.eq(operator.as_str()));
+    }
+
+    if let Some(date_from) = &filter.date_from {
+        query = query.filter(manual_test_results::Column::NormalizedDate.gte(date_from.as_str()));
+    }
+
+    if let Some(date_to) = &filter.date_to {
+        query = query.filter(manual_test_results::Column::NormalizedDate.lte(date_to.as_str()));
+    }
+
+    if let Some(pass_fail) = &filter.pass_fail {
+        // Manual files mix "Pass", "PASS" and "pass"
+        query = query.filter(Expr::cust_with_values(
+            format!("{} = ?", VERDICT),
+            [pass_fail.trim().to_uppercase()],
+        ));
+    }
+
+    if let Some(serial_num) = &filter.serial_num {
+        // Serials, lists and ranges match ignoring leading zeros; anything else must match exactly
+        if let Ok(serials) = SerialSpec::parse(serial_num) {
+            query = query.filter(serials.condition(manual_test_results::Column::Sn));
+        } else {
+            query = query.filter(manual_test_results::Column::Sn.eq(serial_num.as_str()));
+        }
+    }
+
+    query
+}
+
+/// Search manual tests with a paginated filter
+pub async fn search_manual_tests(
+    db: &DbConn,
+    filter: ManualTestSearchFilter,
+) -> Result<Vec<manual_test_results::Model>, DbErr> {
+    let mut query = apply_search_filter(manual_test_results::Entity::find(), &filter)
+        .order_by_desc(manual_test_results::Column::CreatedAt)
+        .order_by_asc(manual_test_results::Column::Id);
+
+    if let Some(limit) = filter.limit {
+        query = query.limit(limit);
+    }
+
+    if let Some(offset) = filter.offset {
+        query = query.offset(offset);
+    }
+
+    query.all(db).await
+}
+
+/// Count manual tests matching a filter (ignores limit/offset)
+pub async fn count_manual_tests(db: &DbConn, filter: ManualTestSearchFilter) -> Result<u64, DbErr> {
+    apply_search_filter(manual_test_results::Entity::find(), &filter)
+        .count(db)
+        .await
+}
+
+/// Get failed manual tests only
+pub async fn get_manual_failed_tests(
+    db: &DbConn,
+    fg: Option<&str>,
+    limit: Option<u64>,
+) -> Result<Vec<manual_test_results::Model>, DbErr> {
+    let mut query = manual_test_results::Entity::find()
+        .filter(Expr::cust(format!("{} != 'PASS'", VERDICT)))
+        .order_by_desc(manual_test_results::Column::CreatedAt);
+
+    if let Some(f) = fg {
+        query = query.filter(manual_test_results::Column::Fg.eq(f));
+    }
+
+    if let Some(limit) = limit {
+        query = query.limit(limit);
+    }
+
+    query.all(db).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manual::queries::test_db;
+
+    #[tokio::test]
+    async fn test_pass_fail_filters_use_computed_verdict() {
+        let db = test_db(&[
+            ("132520", "B1", "AB", "PASS", Some("FAIL")),
+            ("132520", "B1", "AB", "PASS", Some("PASS")),
+            ("132520", "B1", "AB", "Fail", None),
+        ])
+        .await;
+
+        let filter = ManualTestSearchFilter {
+            pass_fail: Some("fail".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(count_manual_tests(&db, filter).await.unwrap(), 2);
+
+        let failed = get_manual_failed_tests(&db, Some("132520"), None)
+            .await
+            .unwrap();
+        let mut serials: Vec<&str> = failed.iter().map(|t| t.sn.as_str()).collect();
+        serials.sort();
+        assert_eq!(serials, vec!["1001", "1003"]);
+    }
+}
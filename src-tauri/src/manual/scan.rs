@@ -0,0 +1,117 @@
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+/// Settings key holding the comma-separated include patterns for folder imports
+pub const INCLUDE_PATTERNS_SETTING: &str = "include_patterns";
+
+/// Include pattern used when none is configured
+pub const DEFAULT_INCLUDE_PATTERNS: &str = "*.csv";
+
+/// Compiled include patterns, matched case-insensitively against file names
+#[derive(Debug, Clone)]
+pub struct IncludePatterns {
+    patterns: Vec<Regex>,
+}
+
+impl IncludePatterns {
+    /// Parse comma-separated glob patterns such as "*.csv, LFG-*.txt"
+    /// Supports `*` (any run of characters) and `?` (one character)
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let patterns = value
+            .split(',')
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+            .map(glob_to_regex)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if patterns.is_empty() {
+            return Err("At least one include pattern is required".to_string());
+        }
+
+        Ok(Self { patterns })
+    }
+
+    pub fn matches(&self, file_name: &str) -> bool {
+        self.patterns.iter().any(|p| p.is_match(file_name))
+    }
+}
+
+fn glob_to_regex(pattern: &str) -> Result<Regex, String> {
+    let mut regex = String::from("(?i)^");
+    for c in pattern.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            _ => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+
+    Regex::new(&regex).map_err(|e| format!("Invalid include pattern '{}': {}", pattern, e))
+}
+
+/// Recursively list files under `folder` whose names match the include patterns
+/// Blocking; call from `spawn_blocking`
+pub fn list_matching_files(
+    folder: &Path,
+    include: &IncludePatterns,
+) -> Result<Vec<PathBuf>, String> {
+    if !folder.is_dir() {
+        return Err(format!("FG folder does not exist: {}", folder.display()));
+    }
+
+    let mut files = Vec::new();
+    let mut pending = vec![folder.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let entries = std::fs::read_dir(&dir)
+            .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?;
+
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
+            let file_type = entry
+                .file_type()
+                .map_err(|e| format!("Failed to read file type: {}", e))?;
+            let path = entry.path();
+
+            // Symlinks are not followed to avoid cycles on the share
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_file() {
+                let name_matches = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .map(|n| include.matches(n))
+                    .unwrap_or(false);
+
+                if name_matches {
+                    files.push(path);
+                }
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_include_patterns() {
+        let include = IncludePatterns::parse("*.csv, LFG-??.txt").unwrap();
+        assert!(include.matches("results.csv"));
+        assert!(include.matches("RESULTS.CSV"));
+        assert!(include.matches("lfg-01.TXT"));
+        assert!(!include.matches("lfg-001.txt"));
+        assert!(!include.matches("results.csv.bak"));
+        assert!(!include.matches("results(csv)"));
+    }
+
+    #[test]
+    fn test_include_patterns_empty() {
+        assert!(IncludePatterns::parse(" , ").is_err());
+    }
+}
@@ -0,0 +1,481 @@
+// Part number mapping between core FGs and tester records
+//
+// Voltech records name the part they tested ("134871FTA") and manual records
+// name the FG ("132520"). An FG's mappings say which of those strings belong
+// to it: exact part numbers, or regex patterns that must match the whole
+// string. A pattern's `rev` group, or else the mapping's revision, is the
+// revision the record was tested at; records of another revision of the FG do
+// not match. FGs without mappings for a source use the default rule: the FG
+// number, optionally followed by a revision starting with a letter, so
+// "134871FTA" is FG 134871 rev FTA and never FG 13487.
+//
+// `fg_part_mappings` is not part of the generated entity, so it is read and
+// written with SQL.
+use crate::manual::queries::get_all_manual_fg_numbers;
+use crate::voltech::commands::{check_admin_permission, get_current_username};
+use crate::voltech::queries::get_all_part_numbers;
+use crate::AppState;
+use ::entity::fg;
+use regex::{Regex, RegexBuilder};
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::State;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PartSource {
+    Voltech, // `test_results.part`
+    Manual,  // `manual_test_results.fg`
+}
+
+impl PartSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PartSource::Voltech => "voltech",
+            PartSource::Manual => "manual",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MappingKind {
+    Exact,   // One tester part number
+    Pattern, // A regex over the whole part number
+}
+
+impl MappingKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MappingKind::Exact => "exact",
+            MappingKind::Pattern => "pattern",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromQueryResult)]
+pub struct PartMapping {
+    pub id: i32,
+    pub fg_id: i32,
+    pub source: String, // "voltech" or "manual"
+    pub kind: String,   // "exact" or "pattern"
+    pub value: String,
+    pub rev: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PartMappingData {
+    pub fg_id: i32,
+    pub source: PartSource,
+    pub kind: MappingKind,
+    pub value: String,
+    #[serde(default)]
+    pub rev: Option<String>,
+}
+
+/// Tester part numbers no FG claims
+#[derive(Debug, Serialize)]
+pub struct UnmatchedParts {
+    pub voltech: Vec<String>,
+    pub manual: Vec<String>,
+}
+
+fn compile_pattern(pattern: &str) -> Result<Regex, String> {
+    RegexBuilder::new(&format!("^(?:{})$", pattern.trim()))
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| format!("Invalid part number pattern '{}': {}", pattern, e))
+}
+
+#[derive(Debug, Clone)]
+enum PartRule {
+    Exact { part: String, rev: Option<String> },
+    Pattern { regex: Regex, rev: Option<String> },
+}
+
+impl PartRule {
+    fn from_mapping(mapping: &PartMapping) -> Result<Self, String> {
+        let rev = mapping
+            .rev
+            .as_deref()
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .map(str::to_string);
+        match mapping.kind.as_str() {
+            "exact" => Ok(PartRule::Exact {
+                part: mapping.value.trim().to_string(),
+                rev,
+            }),
+            "pattern" => Ok(PartRule::Pattern {
+                regex: compile_pattern(&mapping.value)?,
+                rev,
+            }),
+            other => Err(format!("Unknown part mapping kind '{}'", other)),
+        }
+    }
+
+    /// The FG number, optionally followed by a revision starting with a letter
+    fn default_for(fg: &str) -> Self {
+        let pattern = format!("{}(?P<rev>[A-Z][A-Z0-9]*)?", regex::escape(fg.trim()));
+        PartRule::Pattern {
+            regex: compile_pattern(&pattern).expect("escaped FG number is a valid pattern"),
+            rev: None,
+        }
+    }
+
+    /// The revision a matching part was tested at; `Some(None)` when the rule
+    /// matches but does not say
+    fn revision(&self, part: &str) -> Option<Option<String>> {
+        let part = part.trim();
+        match self {
+            PartRule::Exact { part: exact, rev } => {
+                exact.eq_ignore_ascii_case(part).then(|| rev.clone())
+            }
+            PartRule::Pattern { regex, rev } => {
+                let captures = regex.captures(part)?;
+                let captured = captures.name("rev").map(|m| m.as_str().to_string());
+                Some(captured.or_else(|| rev.clone()))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FgRules {
+    rev: Option<String>, // None accepts every revision
+    rules: Vec<PartRule>,
+}
+
+impl FgRules {
+    fn new(fg: &str, rev: Option<&str>, mappings: &[PartMapping]) -> Result<Self, String> {
+        let mut rules = mappings
+            .iter()
+            .map(PartRule::from_mapping)
+            .collect::<Result<Vec<_>, _>>()?;
+        if rules.is_empty() {
+            rules.push(PartRule::default_for(fg));
+        }
+        Ok(Self {
+            rev: rev.map(|r| r.trim().to_string()).filter(|r| !r.is_empty()),
+            rules,
+        })
+    }
+
+    fn matches(&self, part: &str) -> bool {
+        self.rules
+            .iter()
+            .filter_map(|rule| rule.revision(part))
+            .any(|found| match (&self.rev, found) {
+                (Some(rev), Some(found)) => rev.eq_ignore_ascii_case(&found),
+                _ => true,
+            })
+    }
+}
+
+/// Decides which tester part numbers belong to one or more FGs
+#[derive(Debug, Clone, Default)]
+pub struct PartMatcher {
+    fgs: Vec<FgRules>,
+}
+
+impl PartMatcher {
+    pub fn matches(&self, part: &str) -> bool {
+        self.fgs.iter().any(|fg| fg.matches(part))
+    }
+
+    /// The part numbers that match, in the order given
+    pub fn filter(&self, parts: Vec<String>) -> Vec<String> {
+        parts
+            .into_iter()
+            .filter(|part| self.matches(part))
+            .collect()
+    }
+}
+
+async fn mappings_for(
+    db: &DbConn,
+    fg_ids: &[i32],
+    source: PartSource,
+) -> Result<Vec<PartMapping>, DbErr> {
+    if fg_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let placeholders = vec!["?"; fg_ids.len()].join(", ");
+    let mut values: Vec<Value> = vec![source.as_str().into()];
+    values.extend(fg_ids.iter().map(|id| Value::from(*id)));
+
+    PartMapping::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        format!(
+            r#"
+            SELECT id, fg_id, source, kind, value, rev,
+                   CAST(created_at AS TEXT) as created_at
+            FROM fg_part_mappings
+            WHERE source = ? AND fg_id IN ({})
+            ORDER BY id
+            "#,
+            placeholders
+        ),
+        values,
+    ))
+    .all(db)
+    .await
+}
+
+/// Build a matcher for an FG number, at one revision or (without `rev`) any
+/// revision. Unknown FG numbers use the default rule.
+pub async fn part_matcher(
+    core_db: &DbConn,
+    fg_number: &str,
+    rev: Option<&str>,
+    source: PartSource,
+) -> Result<PartMatcher, DbErr> {
+    let mut query = fg::Entity::find().filter(fg::Column::Fg.eq(fg_number));
+    if let Some(rev) = rev {
+        query = query.filter(fg::Column::Rev.eq(rev));
+    }
+    let fg_models = query.all(core_db).await?;
+
+    let ids: Vec<i32> = fg_models.iter().map(|f| f.id).collect();
+    let mut by_fg: HashMap<i32, Vec<PartMapping>> = HashMap::new();
+    for mapping in mappings_for(core_db, &ids, source).await? {
+        by_fg.entry(mapping.fg_id).or_default().push(mapping);
+    }
+
+    let fgs = if fg_models.is_empty() {
+        vec![FgRules::new(fg_number, rev, &[]).map_err(DbErr::Custom)?]
+    } else {
+        fg_models
+            .iter()
+            .map(|f| {
+                let mappings = by_fg.remove(&f.id).unwrap_or_default();
+                FgRules::new(&f.fg, rev, &mappings)
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(DbErr::Custom)?
+    };
+    Ok(PartMatcher { fgs })
+}
+
+/// Voltech part numbers recorded for an FG
+pub async fn voltech_parts(
+    core_db: &DbConn,
+    voltech_db: &DbConn,
+    fg_number: &str,
+    rev: Option<&str>,
+) -> Result<Vec<String>, DbErr> {
+    let matcher = part_matcher(core_db, fg_number, rev, PartSource::Voltech).await?;
+    Ok(matcher.filter(get_all_part_numbers(voltech_db).await?))
+}
+
+/// Manual FG numbers recorded for an FG
+pub async fn manual_parts(
+    core_db: &DbConn,
+    manual_db: &DbConn,
+    fg_number: &str,
+    rev: Option<&str>,
+) -> Result<Vec<String>, DbErr> {
+    let matcher = part_matcher(core_db, fg_number, rev, PartSource::Manual).await?;
+    Ok(matcher.filter(get_all_manual_fg_numbers(manual_db).await?))
+}
+
+/// A matcher for every FG at its own revision
+async fn all_fgs_matcher(core_db: &DbConn, source: PartSource) -> Result<PartMatcher, DbErr> {
+    let fg_models = fg::Entity::find().all(core_db).await?;
+    let ids: Vec<i32> = fg_models.iter().map(|f| f.id).collect();
+    let mut by_fg: HashMap<i32, Vec<PartMapping>> = HashMap::new();
+    for mapping in mappings_for(core_db, &ids, source).await? {
+        by_fg.entry(mapping.fg_id).or_default().push(mapping);
+    }
+
+    let fgs = fg_models
+        .iter()
+        .map(|f| {
+            let mappings = by_fg.remove(&f.id).unwrap_or_default();
+            FgRules::new(&f.fg, Some(&f.rev), &mappings)
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(DbErr::Custom)?;
+    Ok(PartMatcher { fgs })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_part_mappings(
+    fg_id: i32,
+    state: State<'_, AppState>,
+) -> Result<Vec<PartMapping>, String> {
+    let db = &*state.core_db;
+    let mut mappings = Vec::new();
+    for source in [PartSource::Voltech, PartSource::Manual] {
+        mappings.extend(
+            mappings_for(db, &[fg_id], source)
+                .await
+                .map_err(|e| format!("Failed to fetch part mappings: {}", e))?,
+        );
+    }
+    Ok(mappings)
+}
+
+/// Admin-only: Add a part number mapping to an FG
+#[tauri::command]
+pub async fn add_part_mapping(
+    mapping: PartMappingData,
+    state: State<'_, AppState>,
+) -> Result<PartMapping, String> {
+    let username = get_current_username()?;
+    if !check_admin_permission(&state, &username).await? {
+        return Err("Admin permission required to change part mappings".to_string());
+    }
+
+    let value = mapping.value.trim();
+    if value.is_empty() {
+        return Err("A part number or pattern is required".to_string());
+    }
+    if mapping.kind == MappingKind::Pattern {
+        compile_pattern(value)?;
+    }
+    let rev = mapping
+        .rev
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty());
+
+    let db = &*state.core_db;
+    let result = db
+        .execute_raw(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT INTO fg_part_mappings (fg_id, source, kind, value, rev) VALUES (?, ?, ?, ?, ?)",
+            [
+                mapping.fg_id.into(),
+                mapping.source.as_str().into(),
+                mapping.kind.as_str().into(),
+                value.into(),
+                rev.map(str::to_string).into(),
+            ],
+        ))
+        .await
+        .map_err(|e| format!("Failed to add part mapping: {}", e))?;
+    let id = result.last_insert_id() as i32;
+
+    mappings_for(db, &[mapping.fg_id], mapping.source)
+        .await
+        .map_err(|e| format!("Failed to load part mapping: {}", e))?
+        .into_iter()
+        .find(|m| m.id == id)
+        .ok_or_else(|| "Failed to load part mapping".to_string())
+}
+
+/// Admin-only: Remove a part number mapping
+#[tauri::command]
+pub async fn delete_part_mapping(id: i32, state: State<'_, AppState>) -> Result<String, String> {
+    let username = get_current_username()?;
+    if !check_admin_permission(&state, &username).await? {
+        return Err("Admin permission required to change part mappings".to_string());
+    }
+
+    state
+        .core_db
+        .execute_raw(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "DELETE FROM fg_part_mappings WHERE id = ?",
+            [id.into()],
+        ))
+        .await
+        .map_err(|e| format!("Failed to delete part mapping: {}", e))?;
+
+    Ok(format!("Part mapping {} deleted", id))
+}
+
+/// Admin-only: Tester part numbers that no FG's mappings claim
+#[tauri::command]
+pub async fn get_unmatched_part_numbers(
+    state: State<'_, AppState>,
+) -> Result<UnmatchedParts, String> {
+    let username = get_current_username()?;
+    if !check_admin_permission(&state, &username).await? {
+        return Err("Admin permission required to review part numbers".to_string());
+    }
+
+    let unmatched = async {
+        let voltech = all_fgs_matcher(&state.core_db, PartSource::Voltech).await?;
+        let manual = all_fgs_matcher(&state.core_db, PartSource::Manual).await?;
+        Ok::<_, DbErr>(UnmatchedParts {
+            voltech: get_all_part_numbers(&state.voltech_db)
+                .await?
+                .into_iter()
+                .filter(|part| !voltech.matches(part))
+                .collect(),
+            manual: get_all_manual_fg_numbers(&state.manual_db)
+                .await?
+                .into_iter()
+                .filter(|part| !manual.matches(part))
+                .collect(),
+        })
+    };
+    unmatched
+        .await
+        .map_err(|e| format!("Failed to find unmatched part numbers: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(kind: &str, value: &str, rev: Option<&str>) -> PartMapping {
+        PartMapping {
+            id: 1,
+            fg_id: 1,
+            source: "voltech".to_string(),
+            kind: kind.to_string(),
+            value: value.to_string(),
+            rev: rev.map(str::to_string),
+            created_at: String::new(),
+        }
+    }
+
+    fn matcher(fg: &str, rev: Option<&str>, mappings: &[PartMapping]) -> PartMatcher {
+        PartMatcher {
+            fgs: vec![FgRules::new(fg, rev, mappings).unwrap()],
+        }
+    }
+
+    #[test]
+    fn test_default_rule_needs_whole_fg_and_rev() {
+        let fta = matcher("13487", Some("FTA"), &[]);
+        assert!(fta.matches("13487FTA"));
+        assert!(fta.matches(" 13487fta "));
+        assert!(fta.matches("13487"));
+        assert!(!fta.matches("134871FTA"));
+        assert!(!fta.matches("13487PTA"));
+
+        // Any revision
+        let any = matcher("MTPL-2013-0023L", None, &[]);
+        assert!(any.matches("MTPL-2013-0023LFTA"));
+        assert!(any.matches("MTPL-2013-0023LPTA"));
+        assert!(!any.matches("MTPL-2013-0023"));
+    }
+
+    #[test]
+    fn test_mappings_replace_the_default_rule() {
+        let mappings = vec![
+            mapping("exact", "LEGACY-7", Some("FTA")),
+            mapping("pattern", r"X13487-(?P<rev>[A-Z]+)", None),
+        ];
+        let fta = matcher("13487", Some("FTA"), &mappings);
+        assert!(fta.matches("legacy-7"));
+        assert!(fta.matches("X13487-FTA"));
+        assert!(!fta.matches("X13487-PTA"));
+        assert!(!fta.matches("13487FTA"));
+
+        let ptb = matcher("13487", Some("PTB"), &mappings);
+        assert!(!ptb.matches("LEGACY-7"));
+
+        assert!(FgRules::new("1", None, &[mapping("pattern", "(", None)]).is_err());
+    }
+}
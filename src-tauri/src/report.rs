@@ -1,3 +1,4 @@
+use crate::reports::retest::{all_report_policies, report_policy, set_report_policy, RetestPolicy};
 use crate::AppState;
 use entity::report;
 use entity_voltech::test_results;
@@ -10,6 +11,8 @@ pub struct ReportData {
     pub fg_id: i32,
     pub attributes: String,
     pub added_by: Option<i32>,
+    #[serde(default)]
+    pub retest_policy: Option<RetestPolicy>, // Left unchanged when not given
 }
 
 #[derive(Debug, Serialize)]
@@ -17,6 +20,7 @@ pub struct ReportResponse {
     pub id: i32,
     pub fg_id: i32,
     pub attributes: String,
+    pub retest_policy: Option<RetestPolicy>, // None until the report chooses one
 }
 
 #[tauri::command]
@@ -38,10 +42,18 @@ pub async fn create_report(
         .await
         .map_err(|e| format!("Failed to create report: {}", e))?;
 
+    let retest_policy = report_data.retest_policy;
+    if let Some(policy) = retest_policy {
+        set_report_policy(db, report.id, policy)
+            .await
+            .map_err(|e| format!("Failed to save retest policy: {}", e))?;
+    }
+
     Ok(ReportResponse {
         id: report.id,
         fg_id: report.fg_id,
         attributes: report.attributes,
+        retest_policy,
     })
 }
 
@@ -55,10 +67,15 @@ pub async fn get_report(state: State<'_, AppState>, id: i32) -> Result<ReportRes
         .map_err(|e| format!("Failed to fetch report: {}", e))?
         .ok_or_else(|| "Report not found".to_string())?;
 
+    let retest_policy = report_policy(db, report.id)
+        .await
+        .map_err(|e| format!("Failed to fetch retest policy: {}", e))?;
+
     Ok(ReportResponse {
         id: report.id,
         fg_id: report.fg_id,
         attributes: report.attributes,
+        retest_policy,
     })
 }
 
@@ -70,10 +87,14 @@ pub async fn get_all_reports(state: State<'_, AppState>) -> Result<Vec<ReportRes
         .all(db)
         .await
         .map_err(|e| format!("Failed to fetch reports: {}", e))?;
+    let policies = all_report_policies(db)
+        .await
+        .map_err(|e| format!("Failed to fetch retest policies: {}", e))?;
 
     Ok(reports
         .into_iter()
         .map(|report| ReportResponse {
+            retest_policy: policies.get(&report.id).copied(),
             id: report.id,
             fg_id: report.fg_id,
             attributes: report.attributes,
@@ -105,10 +126,20 @@ pub async fn update_report(
         .await
         .map_err(|e| format!("Failed to update report: {}", e))?;
 
+    if let Some(policy) = report_data.retest_policy {
+        set_report_policy(db, report.id, policy)
+            .await
+            .map_err(|e| format!("Failed to save retest policy: {}", e))?;
+    }
+    let retest_policy = report_policy(db, report.id)
+        .await
+        .map_err(|e| format!("Failed to fetch retest policy: {}", e))?;
+
     Ok(ReportResponse {
         id: report.id,
         fg_id: report.fg_id,
         attributes: report.attributes,
+        retest_policy,
     })
 }
 
@@ -0,0 +1,589 @@
+use crate::part_mapping::{manual_parts, voltech_parts};
+use crate::reports::coc::blocking_reasons;
+use crate::reports::collector::RecordSelection;
+use crate::reports::jobs::{self, JobStatus, ReportJobRequest};
+use crate::reports::sessions::SessionSelection;
+use crate::reports::snapshot::ReportParameters;
+use crate::reports::template::TemplateValues;
+use crate::reports::validator::validate_report_data;
+use crate::reports::workbook::WorkbookSheets;
+use crate::reports::write_report_file;
+use crate::serials::SerialSpec;
+use crate::AppState;
+use ::entity::{fg as fg_entity, report as report_entity};
+use chrono::NaiveDate;
+use entity_manual::manual_test_results;
+use entity_voltech::test_results as voltech_test_results;
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+// ============================================================================
+// Bulk Report Generation
+// ============================================================================
+//
+// Generates one report per item into a target directory: items are listed
+// explicitly, or found by a query for every batch of an FG tested in a date
+// range. Each item is validated first; incomplete items are skipped (unless
+// asked to generate anyway) and the rest are run as report jobs, so progress
+// shows in the job list. File names come from a naming template.
+//
+// Every run writes a manifest next to the reports, as JSON and CSV, listing
+// each item with its file name, or the reasons it was not generated.
+// ============================================================================
+
+/// Naming template used when the request gives none
+pub const DEFAULT_NAME_TEMPLATE: &str = "{fg_number}_{fg_revision}_{lot}";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkFormat {
+    #[default]
+    Excel,
+    Pdf,
+}
+
+/// One report to generate, with the shipment details used for its file name
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulkItem {
+    pub report_id: i32,
+    pub batch: Option<String>,
+    pub serial_range: Option<String>,
+    /// Batch mode: test dates to include ("YYYY-MM-DD"); `None` includes every date
+    #[serde(default)]
+    pub selected_dates: Option<Vec<String>>,
+    #[serde(default)]
+    pub job_number: String,
+    #[serde(default)]
+    pub split: String,
+    #[serde(default)]
+    pub date_code: String,
+}
+
+/// Every batch an FG's reports cover, tested between two dates (inclusive)
+/// Serialized FGs get the serials tested in the batch as their serial range;
+/// other FGs get the batch's test dates in the range as their selected dates
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulkQuery {
+    pub fg_id: i32,
+    pub from: String, // "YYYY-MM-DD"
+    pub to: String,
+    #[serde(default)]
+    pub job_number: String,
+    #[serde(default)]
+    pub split: String,
+    #[serde(default)]
+    pub date_code: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulkRequest {
+    #[serde(default)]
+    pub items: Vec<BulkItem>,
+    pub query: Option<BulkQuery>,
+    pub output_dir: String,
+    /// Placeholders: {fg_number} {fg_revision} {customer} {report_id} {batch}
+    /// {serial_range} {lot} {job_number} {split} {date_code}
+    pub name_template: Option<String>,
+    #[serde(default)]
+    pub format: BulkFormat,
+    pub template_id: Option<i32>,
+    #[serde(default)]
+    pub sheets: WorkbookSheets,
+    /// Generate items that fail validation instead of skipping them
+    #[serde(default)]
+    pub include_incomplete: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkStatus {
+    Generated,
+    Invalid, // Failed validation; not generated
+    Failed,  // Generation or saving failed
+    Cancelled,
+}
+
+impl BulkStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            BulkStatus::Generated => "generated",
+            BulkStatus::Invalid => "invalid",
+            BulkStatus::Failed => "failed",
+            BulkStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// One manifest line
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestEntry {
+    pub report_id: i32,
+    pub fg_number: String,
+    pub fg_revision: String,
+    pub batch: Option<String>,
+    pub serial_range: Option<String>,
+    pub job_number: String,
+    pub split: String,
+    pub date_code: String,
+    pub status: BulkStatus,
+    pub file_name: Option<String>,
+    /// Why the item was not generated, or validation issues it was generated with
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkSummary {
+    pub output_dir: String,
+    pub generated: usize,
+    pub invalid: usize,
+    pub failed: usize,
+    pub manifest_json: String,
+    pub manifest_csv: String,
+    pub entries: Vec<ManifestEntry>,
+}
+
+/// Make a rendered name safe to use as a file name
+fn sanitize_file_name(name: &str) -> String {
+    let cleaned: String = name
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | ',' | ';' => '_',
+            c if c.is_whitespace() || c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let cleaned = cleaned.trim_matches(['.', '_']).to_string();
+    if cleaned.is_empty() {
+        "report".to_string()
+    } else {
+        cleaned
+    }
+}
+
+/// Render an item's file name, numbering repeats so no report overwrites another
+fn file_name(
+    template: &str,
+    values: &TemplateValues,
+    extension: &str,
+    used: &mut HashSet<String>,
+) -> String {
+    let stem = sanitize_file_name(&values.render(template));
+    let mut name = format!("{}.{}", stem, extension);
+    let mut copy = 1;
+    while !used.insert(name.to_ascii_lowercase()) {
+        copy += 1;
+        name = format!("{}_{}.{}", stem, copy, extension);
+    }
+    name
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// The manifest as CSV, one row per item; reasons are joined with "; "
+fn manifest_csv(entries: &[ManifestEntry]) -> String {
+    let mut csv = String::from(
+        "report_id,fg_number,fg_revision,batch,serial_range,job_number,split,date_code,status,file_name,reasons\n",
+    );
+    for entry in entries {
+        let fields = [
+            entry.report_id.to_string(),
+            entry.fg_number.clone(),
+            entry.fg_revision.clone(),
+            entry.batch.clone().unwrap_or_default(),
+            entry.serial_range.clone().unwrap_or_default(),
+            entry.job_number.clone(),
+            entry.split.clone(),
+            entry.date_code.clone(),
+            entry.status.as_str().to_string(),
+            entry.file_name.clone().unwrap_or_default(),
+            entry.reasons.join("; "),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date '{}': use YYYY-MM-DD", date))
+}
+
+/// Items for every batch of the FG's reports tested in the query's date range
+async fn query_items(query: &BulkQuery, state: &AppState) -> Result<Vec<BulkItem>, String> {
+    let (from, to) = (parse_date(&query.from)?, parse_date(&query.to)?);
+    if from > to {
+        return Err("The date range ends before it starts".to_string());
+    }
+
+    let core_db = &*state.core_db;
+    let fg = fg_entity::Entity::find_by_id(query.fg_id)
+        .one(core_db)
+        .await
+        .map_err(|e| format!("Failed to load FG: {}", e))?
+        .ok_or_else(|| "FG not found".to_string())?;
+    let reports = report_entity::Entity::find()
+        .filter(report_entity::Column::FgId.eq(fg.id))
+        .order_by_asc(report_entity::Column::Id)
+        .all(core_db)
+        .await
+        .map_err(|e| format!("Failed to load reports: {}", e))?;
+
+    let voltech_parts = voltech_parts(core_db, &state.voltech_db, &fg.fg, Some(&fg.rev))
+        .await
+        .map_err(|e| format!("Failed to load part numbers: {}", e))?;
+    let manual_parts = manual_parts(core_db, &state.manual_db, &fg.fg, Some(&fg.rev))
+        .await
+        .map_err(|e| format!("Failed to load part numbers: {}", e))?;
+
+    // Batch -> serials and dates tested in it
+    let mut batches: BTreeMap<String, (Vec<String>, BTreeSet<NaiveDate>)> = BTreeMap::new();
+    let voltech = voltech_test_results::Entity::find()
+        .filter(voltech_test_results::Column::Part.is_in(voltech_parts))
+        .filter(voltech_test_results::Column::NormalizedDate.between(from, to))
+        .all(&*state.voltech_db)
+        .await
+        .map_err(|e| format!("Failed to load Voltech records: {}", e))?;
+    for record in voltech {
+        let (serials, dates) = batches.entry(record.batch).or_default();
+        serials.push(record.serial_num);
+        dates.extend(record.normalized_date);
+    }
+    let manual = manual_test_results::Entity::find()
+        .filter(manual_test_results::Column::Fg.is_in(manual_parts))
+        .filter(manual_test_results::Column::NormalizedDate.between(from, to))
+        .all(&*state.manual_db)
+        .await
+        .map_err(|e| format!("Failed to load manual records: {}", e))?;
+    for record in manual {
+        let (serials, dates) = batches.entry(record.batch).or_default();
+        serials.push(record.sn);
+        dates.insert(record.normalized_date);
+    }
+
+    let mut items = Vec::new();
+    for (batch, (serials, dates)) in batches.into_iter().filter(|(b, _)| !b.trim().is_empty()) {
+        let (serial_range, selected_dates) = if fg.serialized {
+            let spec = SerialSpec::from_serials(serials.iter().map(String::as_str));
+            (spec.map(|s| s.to_string()), None)
+        } else {
+            let dates = dates.iter().map(|d| d.format("%Y-%m-%d").to_string());
+            (None, Some(dates.collect()))
+        };
+        for report in &reports {
+            items.push(BulkItem {
+                report_id: report.id,
+                batch: Some(batch.clone()),
+                serial_range: serial_range.clone(),
+                selected_dates: selected_dates.clone(),
+                job_number: query.job_number.clone(),
+                split: query.split.clone(),
+                date_code: query.date_code.clone(),
+            });
+        }
+    }
+    Ok(items)
+}
+
+/// An item's manifest entry before it is validated
+fn manifest_entry(item: &BulkItem) -> ManifestEntry {
+    ManifestEntry {
+        report_id: item.report_id,
+        fg_number: String::new(),
+        fg_revision: String::new(),
+        batch: item.batch.clone().filter(|b| !b.trim().is_empty()),
+        serial_range: item.serial_range.clone().filter(|s| !s.trim().is_empty()),
+        job_number: item.job_number.trim().to_string(),
+        split: item.split.trim().to_string(),
+        date_code: item.date_code.trim().to_string(),
+        status: BulkStatus::Invalid,
+        file_name: None,
+        reasons: Vec::new(),
+    }
+}
+
+/// Validate an item and name its file
+/// Returns the manifest entry and whether the item should be generated
+async fn prepare_item(
+    item: &BulkItem,
+    request: &BulkRequest,
+    state: &AppState,
+    used: &mut HashSet<String>,
+) -> Result<(ManifestEntry, bool), String> {
+    let mut entry = manifest_entry(item);
+
+    let core_db = &*state.core_db;
+    let report = report_entity::Entity::find_by_id(item.report_id)
+        .one(core_db)
+        .await
+        .map_err(|e| format!("Failed to load report: {}", e))?;
+    let fg = match report {
+        Some(report) => fg_entity::Entity::find_by_id(report.fg_id)
+            .one(core_db)
+            .await
+            .map_err(|e| format!("Failed to load FG: {}", e))?,
+        None => None,
+    };
+    let Some(fg) = fg else {
+        entry.reasons.push("Report not found".to_string());
+        return Ok((entry, false));
+    };
+    entry.fg_number = fg.fg.clone();
+    entry.fg_revision = fg.rev.clone();
+
+    if entry.batch.is_none() && entry.serial_range.is_none() {
+        entry
+            .reasons
+            .push("A serial range or batch is required".to_string());
+        return Ok((entry, false));
+    }
+
+    let selection = RecordSelection {
+        batch: entry.batch.clone(),
+        serial_range: entry.serial_range.clone(),
+        selected_dates: item.selected_dates.clone(),
+        sessions: SessionSelection::default(),
+    };
+    match validate_report_data(
+        item.report_id,
+        &selection,
+        core_db,
+        &state.voltech_db,
+        &state.manual_db,
+    )
+    .await
+    {
+        Ok(validation) if !validation.is_complete => {
+            entry.reasons = blocking_reasons(&validation, &[]);
+            if entry.reasons.is_empty() {
+                entry.reasons.push("The report has no tests".to_string());
+            }
+            if !request.include_incomplete {
+                return Ok((entry, false));
+            }
+        }
+        Ok(_) => {}
+        Err(e) => {
+            entry.reasons.push(format!("Validation failed: {}", e));
+            return Ok((entry, false));
+        }
+    }
+
+    let lot = if fg.serialized {
+        entry.serial_range.as_deref()
+    } else {
+        entry.batch.as_deref()
+    };
+    let mut values = TemplateValues::new();
+    values
+        .set("fg_number", fg.fg.as_str())
+        .set("fg_revision", fg.rev.as_str())
+        .set("customer", fg.customer.as_str())
+        .set("report_id", item.report_id.to_string())
+        .set("batch", entry.batch.as_deref().unwrap_or(""))
+        .set("serial_range", entry.serial_range.as_deref().unwrap_or(""))
+        .set("lot", lot.unwrap_or(""))
+        .set("job_number", entry.job_number.as_str())
+        .set("split", entry.split.as_str())
+        .set("date_code", entry.date_code.as_str());
+    let extension = match request.format {
+        BulkFormat::Excel => "xlsx",
+        BulkFormat::Pdf => "pdf",
+    };
+    let template = request
+        .name_template
+        .as_deref()
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or(DEFAULT_NAME_TEMPLATE);
+    entry.file_name = Some(file_name(template, &values, extension, used));
+
+    Ok((entry, true))
+}
+
+// ============================================================================
+// Tauri Command
+// ============================================================================
+
+/// Generate a report per item into `request.output_dir` and write a manifest
+/// Items run as report jobs; progress is emitted as `report-job-progress` events
+#[tauri::command]
+pub async fn generate_bulk_reports(
+    app: AppHandle,
+    request: BulkRequest,
+    state: State<'_, AppState>,
+) -> Result<BulkSummary, String> {
+    let output_dir = Path::new(request.output_dir.trim());
+    if request.output_dir.trim().is_empty() {
+        return Err("An output directory is required".to_string());
+    }
+
+    let mut items = request.items.clone();
+    if let Some(query) = &request.query {
+        items.extend(query_items(query, &state).await?);
+    }
+    if items.is_empty() {
+        return Err("No reports to generate".to_string());
+    }
+
+    // Validate and name every item before generating any
+    let mut entries = Vec::new();
+    let mut pending = Vec::new();
+    let mut used = HashSet::new();
+    for item in &items {
+        // An item that cannot be checked fails on its own; the run goes on
+        let (entry, generate) = match prepare_item(item, &request, &state, &mut used).await {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                let mut entry = manifest_entry(item);
+                entry.status = BulkStatus::Failed;
+                entry.reasons.push(e);
+                (entry, false)
+            }
+        };
+        if generate {
+            let params = ReportParameters {
+                report_id: item.report_id,
+                batch: entry.batch.clone(),
+                serial_range: entry.serial_range.clone(),
+                selected_dates: item.selected_dates.clone(),
+                sessions: SessionSelection::default(),
+                template_id: request.template_id,
+                sheets: request.sheets.clone(),
+            };
+            let job = match request.format {
+                BulkFormat::Excel => ReportJobRequest::Excel(params),
+                BulkFormat::Pdf => ReportJobRequest::Pdf(params),
+            };
+            pending.push((entries.len(), jobs::submit(&app, &state.report_jobs, job)));
+        }
+        entries.push(entry);
+    }
+
+    // Save each report as its job finishes, then free it
+    for (index, (job_id, handle)) in pending {
+        let entry = &mut entries[index];
+        if let Err(e) = handle.await {
+            entry.status = BulkStatus::Failed;
+            entry.reasons.push(format!("Report job stopped: {}", e));
+            continue;
+        }
+        let status = state.report_jobs.info(&job_id).map(|info| info.status);
+        entry.status = match state.report_jobs.artifact(&job_id) {
+            Ok(bytes) => {
+                let path = output_dir.join(entry.file_name.as_deref().unwrap_or_default());
+                match write_report_file(&path.to_string_lossy(), bytes) {
+                    Ok(()) => BulkStatus::Generated,
+                    Err(e) => {
+                        entry.reasons.push(e);
+                        BulkStatus::Failed
+                    }
+                }
+            }
+            Err(_) if status == Some(JobStatus::Cancelled) => BulkStatus::Cancelled,
+            Err(e) => {
+                entry.reasons.push(e);
+                BulkStatus::Failed
+            }
+        };
+        if entry.status != BulkStatus::Generated {
+            entry.file_name = None;
+        }
+        let _ = state.report_jobs.remove(&job_id);
+    }
+
+    // Manifest, named for the run so earlier manifests are kept
+    let stamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let json_path = output_dir.join(format!("manifest_{}.json", stamp));
+    let csv_path = output_dir.join(format!("manifest_{}.csv", stamp));
+    let json = serde_json::to_vec_pretty(&entries)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    write_report_file(&json_path.to_string_lossy(), json)?;
+    write_report_file(
+        &csv_path.to_string_lossy(),
+        manifest_csv(&entries).into_bytes(),
+    )?;
+
+    let count = |status: BulkStatus| entries.iter().filter(|e| e.status == status).count();
+    Ok(BulkSummary {
+        output_dir: output_dir.to_string_lossy().to_string(),
+        generated: count(BulkStatus::Generated),
+        invalid: count(BulkStatus::Invalid),
+        failed: count(BulkStatus::Failed),
+        manifest_json: json_path.to_string_lossy().to_string(),
+        manifest_csv: csv_path.to_string_lossy().to_string(),
+        entries,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_names_are_safe_and_unique() {
+        let mut values = TemplateValues::new();
+        values
+            .set("fg_number", "132520")
+            .set("fg_revision", "A")
+            .set("lot", "1001-1050, 1060")
+            .set("job_number", "J/42");
+        let mut used = HashSet::new();
+
+        let first = file_name(
+            "{fg_number}_{fg_revision}_{lot}",
+            &values,
+            "xlsx",
+            &mut used,
+        );
+        assert_eq!(first, "132520_A_1001-1050__1060.xlsx");
+        let repeat = file_name(
+            "{fg_number}_{fg_revision}_{lot}",
+            &values,
+            "xlsx",
+            &mut used,
+        );
+        assert_eq!(repeat, "132520_A_1001-1050__1060_2.xlsx");
+        assert_eq!(
+            file_name("Job {job_number}", &values, "pdf", &mut used),
+            "Job_J_42.pdf"
+        );
+        assert_eq!(file_name(" .. ", &values, "pdf", &mut used), "report.pdf");
+    }
+
+    #[test]
+    fn test_manifest_csv_quotes_fields() {
+        let entry = ManifestEntry {
+            report_id: 3,
+            fg_number: "132520".to_string(),
+            fg_revision: "A".to_string(),
+            batch: None,
+            serial_range: Some("1001-1050, 1060".to_string()),
+            job_number: "J42".to_string(),
+            split: String::new(),
+            date_code: "2546".to_string(),
+            status: BulkStatus::Invalid,
+            file_name: None,
+            reasons: vec![
+                "Missing \"Hipot\"".to_string(),
+                "2 of 51 serials complete".to_string(),
+            ],
+        };
+        let csv = manifest_csv(&[entry]);
+        let row = csv.lines().nth(1).unwrap();
+        assert_eq!(
+            row,
+            "3,132520,A,,\"1001-1050, 1060\",J42,,2546,invalid,,\"Missing \"\"Hipot\"\"; 2 of 51 serials complete\""
+        );
+    }
+}
@@ -0,0 +1,697 @@
+// Certificate of Conformance generation
+use crate::fg::get_customer_pn;
+use crate::reports::collector::{self, RecordSelection, ReportData};
+use crate::reports::retest::{resolve, RetestPolicy};
+use crate::reports::sessions::SessionSelection;
+use crate::reports::snapshot::{self, ReportParameters};
+use crate::reports::validator::{self, ValidationResult};
+use crate::reports::{pdf, template_store, write_report_file};
+use crate::serials::SerialSpec;
+use crate::voltech::commands::{check_admin_permission, get_current_username};
+use crate::AppState;
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use tauri::State;
+
+/// Shipment details for a CoC
+#[derive(Debug, Clone, Deserialize)]
+pub struct CocRequest {
+    pub report_id: i32,
+    pub batch: Option<String>,
+    pub serial_range: Option<String>,
+    pub selected_dates: Option<Vec<String>>,
+    #[serde(default)]
+    pub sessions: SessionSelection,
+    pub job_number: String,
+    /// Defaults to the number of serials tested for serialized FGs
+    pub quantity: Option<i32>,
+    pub date_code: String,
+    pub template_id: Option<i32>,
+}
+
+/// Per-test line of the CoC test summary
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CocTestSummary {
+    pub test_name: String,
+    pub source_type: String,
+    pub units_tested: usize,
+    pub units_passed: usize,
+    pub units_failed: usize,
+}
+
+/// An admin approval to issue a CoC despite blocking issues
+/// Only applies while the CoC is blocked for exactly the reasons approved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CocOverride {
+    pub id: i32,
+    pub report_id: i32,
+    pub scope: String,
+    pub reason: String,
+    pub approved_by: String,
+    pub created_at: String,
+    pub blocking_reasons: Vec<String>,
+}
+
+#[derive(FromQueryResult)]
+struct CocOverrideRow {
+    id: i32,
+    report_id: i32,
+    scope: String,
+    reason: String,
+    approved_by: String,
+    created_at: String,
+    blocking_reasons: String, // JSON array
+}
+
+impl From<CocOverrideRow> for CocOverride {
+    fn from(row: CocOverrideRow) -> Self {
+        CocOverride {
+            id: row.id,
+            report_id: row.report_id,
+            scope: row.scope,
+            reason: row.reason,
+            approved_by: row.approved_by,
+            created_at: row.created_at,
+            blocking_reasons: serde_json::from_str(&row.blocking_reasons).unwrap_or_default(),
+        }
+    }
+}
+
+/// Everything printed on a CoC, plus why it may not be issued
+#[derive(Debug, Clone, Serialize)]
+pub struct CocSummary {
+    pub fg_number: String,
+    pub fg_revision: String,
+    pub customer: String,
+    pub customer_pn: Option<String>,
+    pub job_number: String,
+    pub quantity: Option<i32>,
+    pub scope_label: String, // "Serial Range" or "Batch"
+    pub scope: String,
+    pub date_code: String,
+    pub issue_date: String,
+    pub tests: Vec<CocTestSummary>,
+    pub is_complete: bool,
+    pub all_pass: bool,
+    pub failing_units: Vec<String>,
+    pub blocking_reasons: Vec<String>,
+    pub override_record: Option<CocOverride>,
+    pub can_generate: bool,
+}
+
+/// The serial range or batch a CoC covers, used to key overrides
+fn coc_scope(batch: &Option<String>, serial_range: &Option<String>) -> Result<String, String> {
+    collector::run_scope(batch.as_deref(), serial_range.as_deref())?
+        .ok_or_else(|| "A serial range or batch is required".to_string())
+}
+
+/// Units a serialized CoC covers when no quantity is given: every serial of
+/// the range, tested or not, counted once however it was typed
+fn default_quantity(
+    is_serialized: bool,
+    serial_range: Option<&str>,
+) -> Result<Option<i32>, String> {
+    if !is_serialized {
+        return Ok(None);
+    }
+    Ok(serial_range
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(SerialSpec::parse)
+        .transpose()?
+        .map(|serials| serials.serials().len() as i32))
+}
+
+fn is_fail(result: &str) -> bool {
+    result.to_uppercase().contains("FAIL")
+}
+
+/// Summarize each test by unit, taking each serial's final result the way the
+/// report resolves retests: its policy's pick, or the latest attempt when the
+/// report shows every attempt. Records without a serial are units of their own.
+/// Returns the per-test summary and the units whose final result failed
+pub fn summarize_tests(data: &ReportData) -> (Vec<CocTestSummary>, Vec<String>) {
+    // Collected results are already in `retest::resolve` order per serial
+    let policy = data.retest_policy.final_result();
+    let mut failing = BTreeSet::new();
+
+    let tests = data
+        .test_results
+        .iter()
+        .map(|test| {
+            let finals = resolve(
+                test.results.iter().enumerate().collect(),
+                policy,
+                |(_, r)| {
+                    r.serial_number
+                        .as_deref()
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .map(str::to_string)
+                },
+                |(_, r)| !is_fail(&r.result),
+                |(index, _)| *index,
+            );
+
+            let mut units_failed = 0;
+            for resolved in &finals {
+                let (index, result) = resolved.record;
+                if is_fail(&result.result) {
+                    units_failed += 1;
+                    failing.insert(match result.serial_number.as_deref().map(str::trim) {
+                        Some(serial) if !serial.is_empty() => serial.to_string(),
+                        _ => format!("{} (unserialized record {})", result.batch, index + 1),
+                    });
+                }
+            }
+
+            CocTestSummary {
+                test_name: test.test_name.clone(),
+                source_type: test.source_type.clone(),
+                units_tested: finals.len(),
+                units_passed: finals.len() - units_failed,
+                units_failed,
+            }
+        })
+        .collect();
+
+    (tests, failing.into_iter().collect())
+}
+
+/// Reasons a CoC cannot be issued without an override
+pub fn blocking_reasons(validation: &ValidationResult, failing_units: &[String]) -> Vec<String> {
+    let mut reasons = Vec::new();
+
+    if !validation.is_complete {
+        let missing: Vec<&str> = validation
+            .test_statuses
+            .iter()
+            .filter(|s| !s.has_data)
+            .map(|s| s.test_name.as_str())
+            .collect();
+        let mut reason = format!(
+            "Report data is incomplete: {} test(s) missing data ({})",
+            validation.missing_test_count,
+            missing.join(", ")
+        );
+        if let Some(matrix) = &validation.matrix {
+            reason.push_str(&format!(
+                ", {} of {} serials complete",
+                matrix.complete_serials, matrix.total_serials
+            ));
+        }
+        reasons.push(reason);
+    }
+
+    if !failing_units.is_empty() {
+        reasons.push(format!(
+            "{} unit(s) have a failing final result: {}",
+            failing_units.len(),
+            failing_units.join(", ")
+        ));
+    }
+
+    reasons
+}
+
+/// Blocking reasons as stored with an override
+fn reasons_key(blocking: &[String]) -> String {
+    serde_json::to_string(blocking).unwrap_or_default()
+}
+
+/// Get the most recent override recorded for a report and scope that was
+/// approved for exactly these blocking reasons
+pub async fn find_override(
+    db: &DbConn,
+    report_id: i32,
+    scope: &str,
+    blocking: &[String],
+) -> Result<Option<CocOverride>, DbErr> {
+    let row = CocOverrideRow::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        r#"
+        SELECT id, report_id, scope, reason, approved_by,
+               CAST(created_at AS TEXT) as created_at, blocking_reasons
+        FROM coc_overrides
+        WHERE report_id = ? AND scope = ? AND blocking_reasons = ?
+        ORDER BY created_at DESC, id DESC
+        LIMIT 1
+        "#,
+        [report_id.into(), scope.into(), reasons_key(blocking).into()],
+    ))
+    .one(db)
+    .await?;
+    Ok(row.map(CocOverride::from))
+}
+
+/// Record an override and return its id
+pub async fn insert_override(
+    db: &DbConn,
+    report_id: i32,
+    scope: &str,
+    reason: &str,
+    blocking: &[String],
+    approved_by: &str,
+) -> Result<i32, DbErr> {
+    let result = db
+        .execute_raw(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            r#"
+            INSERT INTO coc_overrides (report_id, scope, reason, approved_by, blocking_reasons)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+            [
+                report_id.into(),
+                scope.into(),
+                reason.into(),
+                approved_by.into(),
+                reasons_key(blocking).into(),
+            ],
+        ))
+        .await?;
+
+    Ok(result.last_insert_id() as i32)
+}
+
+/// Collect, validate and summarize the data for a CoC
+async fn build_summary(
+    request: &CocRequest,
+    state: &AppState,
+) -> Result<(CocSummary, ReportData), String> {
+    let scope = coc_scope(&request.batch, &request.serial_range)?;
+
+    // Validate exactly the records the CoC is built from
+    let selection = RecordSelection {
+        batch: request.batch.clone(),
+        serial_range: request.serial_range.clone(),
+        selected_dates: request.selected_dates.clone(),
+        sessions: request.sessions.clone(),
+    };
+    let validation = validator::validate_report_data(
+        request.report_id,
+        &selection,
+        &state.core_db,
+        &state.voltech_db,
+        &state.manual_db,
+    )
+    .await
+    .map_err(|e| format!("Failed to validate report: {}", e))?;
+
+    let data = collector::collect_report_data(
+        request.report_id,
+        selection,
+        &state.core_db,
+        &state.voltech_db,
+        &state.manual_db,
+    )
+    .await
+    .map_err(|e| format!("Failed to collect report data: {}", e))?;
+
+    let (tests, failing_units) = summarize_tests(&data);
+    let blocking = blocking_reasons(&validation, &failing_units);
+
+    // An override only covers the reasons it was approved for
+    let override_record = if blocking.is_empty() {
+        None
+    } else {
+        find_override(&state.core_db, request.report_id, &scope, &blocking)
+            .await
+            .map_err(|e| format!("Failed to load CoC override: {}", e))?
+    };
+
+    let quantity = match request.quantity {
+        Some(quantity) => Some(quantity),
+        None => default_quantity(data.is_serialized, request.serial_range.as_deref())?,
+    };
+
+    let summary = CocSummary {
+        customer_pn: get_customer_pn(&state.core_db, data.fg_id).await?,
+        fg_number: data.fg_number.clone(),
+        fg_revision: data.fg_revision.clone(),
+        customer: data.fg_customer.clone(),
+        job_number: request.job_number.trim().to_string(),
+        quantity,
+        scope_label: if request.serial_range.is_some() {
+            "Serial Range"
+        } else {
+            "Batch"
+        }
+        .to_string(),
+        scope,
+        date_code: request.date_code.trim().to_string(),
+        issue_date: chrono::Local::now().format("%Y-%m-%d").to_string(),
+        tests,
+        is_complete: validation.is_complete,
+        all_pass: failing_units.is_empty(),
+        failing_units,
+        can_generate: blocking.is_empty() || override_record.is_some(),
+        blocking_reasons: blocking,
+        override_record,
+    };
+
+    Ok((summary, data))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Check whether a CoC can be issued and show what it will contain
+#[tauri::command]
+pub async fn preview_coc(
+    request: CocRequest,
+    state: State<'_, AppState>,
+) -> Result<CocSummary, String> {
+    build_summary(&request, &state)
+        .await
+        .map(|(summary, _)| summary)
+}
+
+/// Generate a CoC PDF
+/// Refused when data is incomplete or a unit failed, unless an override is recorded
+#[tauri::command]
+pub async fn generate_coc(
+    request: CocRequest,
+    state: State<'_, AppState>,
+) -> Result<Vec<u8>, String> {
+    let (summary, data) = build_summary(&request, &state).await?;
+
+    if !summary.can_generate {
+        return Err(format!(
+            "Cannot issue CoC: {}",
+            summary.blocking_reasons.join("; ")
+        ));
+    }
+
+    let template = template_store::select_template(
+        &state.core_db,
+        request.template_id,
+        Some(data.fg_id),
+        Some(&data.fg_customer),
+    )
+    .await
+    .map_err(|e| format!("Failed to load report template: {}", e))?;
+
+    let buffer = pdf::generate_coc(&summary, &template)?;
+
+    // Keep the issued certificate alongside the data it certified
+    let params = ReportParameters {
+        report_id: request.report_id,
+        batch: request.batch.clone(),
+        serial_range: request.serial_range.clone(),
+        selected_dates: request.selected_dates.clone(),
+        sessions: request.sessions.clone(),
+        template_id: request.template_id,
+        sheets: Default::default(),
+    };
+    let spec_rows = snapshot::load_spec_rows(&state.core_db, request.report_id).await?;
+    snapshot::record_snapshot(
+        &state.core_db,
+        snapshot::SnapshotInput {
+            format: snapshot::FORMAT_COC_PDF,
+            parameters: &params,
+            data: &data,
+            template: &template,
+            spec_rows: &spec_rows,
+            raw: None,
+            bytes: &buffer,
+        },
+    )
+    .await?;
+
+    Ok(buffer)
+}
+
+/// Generate a CoC PDF and save to specified file path
+#[tauri::command]
+pub async fn save_coc(
+    request: CocRequest,
+    file_path: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let buffer = generate_coc(request, state).await?;
+    write_report_file(&file_path, buffer)
+}
+
+/// Record an admin override allowing a CoC for this report and scope despite
+/// its current blocking reasons; the override lapses if the reasons change
+#[tauri::command]
+pub async fn record_coc_override(
+    request: CocRequest,
+    reason: String,
+    state: State<'_, AppState>,
+) -> Result<CocOverride, String> {
+    let username = get_current_username()?;
+    if !check_admin_permission(&state, &username).await? {
+        return Err("Admin permission required to override CoC checks".to_string());
+    }
+
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Err("An override reason is required".to_string());
+    }
+
+    let (summary, _) = build_summary(&request, &state).await?;
+    if summary.blocking_reasons.is_empty() {
+        return Err("Nothing blocks this CoC; no override is needed".to_string());
+    }
+
+    insert_override(
+        &state.core_db,
+        request.report_id,
+        &summary.scope,
+        reason,
+        &summary.blocking_reasons,
+        &username,
+    )
+    .await
+    .map_err(|e| format!("Failed to record CoC override: {}", e))?;
+
+    find_override(
+        &state.core_db,
+        request.report_id,
+        &summary.scope,
+        &summary.blocking_reasons,
+    )
+    .await
+    .map_err(|e| format!("Failed to load CoC override: {}", e))?
+    .ok_or_else(|| "Failed to load CoC override".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reports::collector::{SingleTestResult, TestResultData};
+    use crate::reports::validator::TestStatus;
+
+    fn result(serial: &str, date: &str, verdict: &str) -> SingleTestResult {
+        SingleTestResult {
+            serial_number: Some(serial.to_string()),
+            batch: "B1".to_string(),
+            date: date.to_string(),
+            result: verdict.to_string(),
+            measurements: serde_json::json!({}),
+            retests: 0,
+        }
+    }
+
+    fn report(results: Vec<SingleTestResult>) -> ReportData {
+        ReportData {
+            report_id: 1,
+            fg_id: 1,
+            fg_number: "132520".to_string(),
+            fg_revision: "FTA".to_string(),
+            fg_customer: "ACME".to_string(),
+            is_serialized: true,
+            batch: None,
+            serial_range: Some("1001-1003".to_string()),
+            retest_policy: RetestPolicy::default(),
+            test_results: vec![TestResultData {
+                test_id: 1,
+                test_name: "DCR".to_string(),
+                source_type: "voltech".to_string(),
+                associated_test: None,
+                spec_min: None,
+                spec_max: None,
+                spec_unit: None,
+                results,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_retest_pass_supersedes_failure() {
+        let data = report(vec![
+            result("1001", "2025-11-18", "FAIL"),
+            result("1001", "2025-11-19", "PASS"),
+            result("1002", "2025-11-18", "PASS"),
+            result("1003", "2025-11-18", "PASS"),
+            result("1003", "2025-11-19", "FAIL"),
+        ]);
+
+        let (tests, failing) = summarize_tests(&data);
+        assert_eq!(failing, vec!["1003".to_string()]);
+        assert_eq!(tests[0].units_tested, 3);
+        assert_eq!(tests[0].units_passed, 2);
+        assert_eq!(tests[0].units_failed, 1);
+    }
+
+    #[test]
+    fn test_summary_follows_report_resolution() {
+        // Same-day retests are decided by collection order, not the date
+        let mut data = report(vec![
+            result("1001", "2025-11-19", "PASS"),
+            result("1001", "2025-11-19", "FAIL"),
+        ]);
+        assert_eq!(summarize_tests(&data).1, vec!["1001".to_string()]);
+
+        // Under "latest pass" a passing attempt decides the serial
+        data.retest_policy = RetestPolicy::LatestPass;
+        assert!(summarize_tests(&data).1.is_empty());
+
+        // Unserialized records of one batch are separate units
+        let mut unserialized = vec![
+            result("", "2025-11-19", "PASS"),
+            result("", "2025-11-19", "FAIL"),
+        ];
+        unserialized[0].serial_number = None;
+        let (tests, failing) = summarize_tests(&report(unserialized));
+        assert_eq!(tests[0].units_tested, 2);
+        assert_eq!(tests[0].units_failed, 1);
+        assert_eq!(failing, vec!["B1 (unserialized record 2)".to_string()]);
+    }
+
+    #[test]
+    fn test_blocking_reasons() {
+        let mut validation = ValidationResult {
+            is_complete: true,
+            fg_serialized: true,
+            test_statuses: vec![],
+            missing_test_count: 0,
+            matrix: None,
+        };
+        assert!(blocking_reasons(&validation, &[]).is_empty());
+
+        validation.is_complete = false;
+        validation.missing_test_count = 1;
+        validation.test_statuses.push(TestStatus {
+            test_id: 2,
+            test_name: "Hipot".to_string(),
+            source_type: "manual".to_string(),
+            associated_test: None,
+            has_data: false,
+            record_count: 0,
+            available_sessions: vec![],
+            search_method: "serial_range".to_string(),
+        });
+
+        let reasons = blocking_reasons(&validation, &["1003".to_string()]);
+        assert_eq!(reasons.len(), 2);
+        assert!(reasons[0].contains("Hipot"));
+        assert!(reasons[1].contains("1003"));
+    }
+
+    #[test]
+    fn test_default_quantity_counts_the_range() {
+        // Every serial of the range, however untested serials or zero padding look
+        assert_eq!(
+            default_quantity(true, Some("026-035, 26")).unwrap(),
+            Some(10)
+        );
+        assert_eq!(
+            default_quantity(true, Some("1001-1100")).unwrap(),
+            Some(100)
+        );
+        assert_eq!(default_quantity(false, Some("1001-1100")).unwrap(), None);
+        assert_eq!(default_quantity(true, None).unwrap(), None);
+        assert!(default_quantity(true, Some("10-1")).is_err());
+    }
+
+    #[test]
+    fn test_coc_scope_prefers_serial_range() {
+        let batch = Some("B1".to_string());
+        assert_eq!(
+            coc_scope(&batch, &Some("1-10".to_string())).unwrap(),
+            "1-10"
+        );
+        assert_eq!(coc_scope(&batch, &None).unwrap(), "B1");
+        assert!(coc_scope(&None, &Some("  ".to_string())).is_err());
+
+        // Ranges typed differently are the same run
+        assert_eq!(
+            coc_scope(&None, &Some("01 - 10".to_string())).unwrap(),
+            "1-10"
+        );
+        assert!(coc_scope(&None, &Some("10-1".to_string())).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_override_only_covers_its_reasons() {
+        use migration::{Migrator, MigratorTrait};
+
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        Migrator::up(&db, None).await.unwrap();
+        db.execute_unprepared(
+            "INSERT INTO fg (id, fg, rev, customer) VALUES (1, '132520', 'FTA', 'ACME');
+             INSERT INTO report (id, fg_id) VALUES (1, 1);",
+        )
+        .await
+        .unwrap();
+
+        let failing = vec!["1 unit(s) have a failing final result: 1003".to_string()];
+        insert_override(&db, 1, "1001-1003", "Scrapped", &failing, "admin")
+            .await
+            .unwrap();
+
+        let found = find_override(&db, 1, "1001-1003", &failing).await.unwrap();
+        assert_eq!(found.unwrap().blocking_reasons, failing);
+
+        // Another unit failing since is not covered
+        let changed = vec!["1 unit(s) have a failing final result: 1002".to_string()];
+        assert!(find_override(&db, 1, "1001-1003", &changed)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_recomputed_manual_fail_blocks_the_unit() {
+        use migration::{Migrator, MigratorTrait};
+
+        let core_db = Database::connect("sqlite::memory:").await.unwrap();
+        Migrator::up(&core_db, None).await.unwrap();
+        core_db
+            .execute_unprepared(
+                "INSERT INTO fg (id, fg, rev, customer, serialized) VALUES (1, '132520', 'FTA', 'ACME', 1);
+                 INSERT INTO report (id, fg_id) VALUES (1, 1);
+                 INSERT INTO test (id, report_id, fg_id, test_type, uo_m, \"order\", source_type, associated_test)
+                 VALUES (1, 1, 1, 'DCR1', 'Ohm', 1, 'manual', '132520-LFG-DCR1');",
+            )
+            .await
+            .unwrap();
+        let voltech_db = Database::connect("sqlite::memory:").await.unwrap();
+        migration_voltech::Migrator::up(&voltech_db, None)
+            .await
+            .unwrap();
+        // The file marks 1001 PASS but its reading is outside the limits
+        let manual_db = crate::manual::queries::test_db(&[
+            ("132520", "B1", "AB", "PASS", Some("FAIL")),
+            ("132520", "B1", "AB", "PASS", None),
+        ])
+        .await;
+
+        let selection = RecordSelection {
+            serial_range: Some("1001-1002".to_string()),
+            ..Default::default()
+        };
+        let data = collector::collect_report_data(1, selection, &core_db, &voltech_db, &manual_db)
+            .await
+            .unwrap();
+        assert_eq!(data.test_results[0].results[0].result, "FAIL");
+
+        let (tests, failing) = summarize_tests(&data);
+        assert_eq!(failing, vec!["1001".to_string()]);
+        assert_eq!(tests[0].units_passed, 1);
+    }
+}
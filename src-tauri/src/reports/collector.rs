@@ -1,7 +1,18 @@
+use crate::manual::limits::manual_verdict;
+use crate::part_mapping::{manual_parts, voltech_parts};
+use crate::reports::other_results::{run_results, OtherResult};
+use crate::reports::retest::{
+    manual_attempt_order, report_policy, resolve, voltech_attempt_order, RetestPolicy,
+};
+use crate::reports::sessions::{SessionBlock, SessionSelection};
+use crate::reports::statistics::numeric_value;
+use crate::serials::SerialSpec;
+use crate::units::Unit;
 use crate::AppState;
 use ::entity::fg as fg_entity;
 use ::entity::report as report_entity;
 use ::entity::test as test_entity;
+use chrono::NaiveDate;
 use entity_manual::manual_test_results;
 use entity_voltech::test_results as voltech_test_results;
 use sea_orm::*;
@@ -12,16 +23,18 @@ use tauri::State;
 // Data Structures
 // ============================================================================
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SingleTestResult {
     pub serial_number: Option<String>,
     pub batch: String,
     pub date: String,
     pub result: String,                  // "PASS" or "FAIL"
     pub measurements: serde_json::Value, // JSON object with test-specific measurements
+    #[serde(default)]
+    pub retests: i32, // Attempts on this serial after the first
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TestResultData {
     pub test_id: i32,
     pub test_name: String,
@@ -33,18 +46,46 @@ pub struct TestResultData {
     pub results: Vec<SingleTestResult>,
 }
 
-#[derive(Debug, Serialize)]
+impl TestResultData {
+    /// Whether the test's spec limits describe this measurement column:
+    /// the associated Voltech measurement, the manual "reading", or the
+    /// recorded "value" of an "other" test
+    pub fn is_spec_column(&self, column: &str) -> bool {
+        match self.source_type.as_str() {
+            "manual" => return column == "reading",
+            "other" => return column == "value",
+            _ => {}
+        }
+        self.associated_test
+            .as_deref()
+            .is_some_and(|a| a.trim().eq_ignore_ascii_case(column.trim()))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ReportData {
     pub report_id: i32,
+    pub fg_id: i32,
     pub fg_number: String,
     pub fg_revision: String,
     pub fg_customer: String,
     pub is_serialized: bool,
     pub batch: Option<String>,
     pub serial_range: Option<String>,
+    #[serde(default)]
+    pub retest_policy: RetestPolicy,
     pub test_results: Vec<TestResultData>,
 }
 
+/// Which of the FG's records a report is built from
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecordSelection {
+    pub batch: Option<String>,
+    pub serial_range: Option<String>,        // Serialized mode
+    pub selected_dates: Option<Vec<String>>, // Batch mode: whole days
+    pub sessions: SessionSelection,          // Batch mode: sessions by id
+}
+
 // ============================================================================
 // Data Collection Functions
 // ============================================================================
@@ -52,9 +93,7 @@ pub struct ReportData {
 /// Collect all test data for a report
 pub async fn collect_report_data(
     report_id: i32,
-    batch: Option<String>,
-    serial_range: Option<String>,
-    selected_dates: Option<Vec<String>>,
+    selection: RecordSelection,
     core_db: &DbConn,
     voltech_db: &DbConn,
     manual_db: &DbConn,
@@ -71,6 +110,21 @@ pub async fn collect_report_data(
         .ok_or(DbErr::RecordNotFound("FG not found".to_string()))?;
 
     let is_serialized = fg.serialized;
+    let serials = if is_serialized {
+        serial_spec(selection.serial_range.as_deref())?
+    } else {
+        None
+    };
+    let retest_policy = report_policy(core_db, report_id).await?.unwrap_or_default();
+    let scope = RecordScope::for_fg(
+        &fg,
+        &selection,
+        serials.as_ref(),
+        core_db,
+        voltech_db,
+        manual_db,
+    )
+    .await?;
 
     // Get all tests for this report
     let tests = test_entity::Entity::find()
@@ -78,55 +132,31 @@ pub async fn collect_report_data(
         .all(core_db)
         .await?;
 
+    // One query per source for every test of the report
+    let voltech = scope
+        .voltech_records(&associated_tests(&tests, "voltech"), voltech_db)
+        .await?;
+    let manual = scope
+        .manual_records(&associated_tests(&tests, "manual"), manual_db)
+        .await?;
+    let run = run_scope(
+        selection.batch.as_deref(),
+        selection.serial_range.as_deref(),
+    )
+    .map_err(DbErr::Custom)?;
+    let other = match &run {
+        Some(run) => run_results(core_db, report_id, Some(run)).await?,
+        None => Vec::new(),
+    };
+
     let mut test_results = Vec::new();
 
-    for test in tests {
+    for test in &tests {
         let test_data = match test.source_type.as_str() {
-            "voltech" => {
-                collect_voltech_test(
-                    &test,
-                    is_serialized,
-                    batch.as_deref(),
-                    serial_range.as_deref(),
-                    selected_dates.as_ref(),
-                    voltech_db,
-                )
-                .await?
-            }
-            "manual" => {
-                collect_manual_test(
-                    &test,
-                    is_serialized,
-                    batch.as_deref(),
-                    serial_range.as_deref(),
-                    selected_dates.as_ref(),
-                    manual_db,
-                )
-                .await?
-            }
-            "other" => {
-                // For "other" type, create empty result structure
-                TestResultData {
-                    test_id: test.id,
-                    test_name: test.test_type.clone(),
-                    source_type: test.source_type.clone(),
-                    associated_test: test.associated_test.clone(),
-                    spec_min: test.minimum,
-                    spec_max: test.maximum,
-                    spec_unit: Some(test.uo_m.clone()),
-                    results: Vec::new(),
-                }
-            }
-            _ => TestResultData {
-                test_id: test.id,
-                test_name: test.test_type.clone(),
-                source_type: test.source_type.clone(),
-                associated_test: test.associated_test.clone(),
-                spec_min: test.minimum,
-                spec_max: test.maximum,
-                spec_unit: Some(test.uo_m.clone()),
-                results: Vec::new(),
-            },
+            "voltech" => collect_voltech_test(test, &voltech, serials.as_ref(), retest_policy),
+            "manual" => collect_manual_test(test, &manual, serials.as_ref(), retest_policy),
+            "other" => collect_other_test(test, &other, run.as_deref(), retest_policy),
+            _ => test_result_data(test, Vec::new()),
         };
 
         test_results.push(test_data);
@@ -134,111 +164,215 @@ pub async fn collect_report_data(
 
     Ok(ReportData {
         report_id,
+        fg_id: fg.id,
         fg_number: fg.fg.clone(),
         fg_revision: fg.rev.clone(),
         fg_customer: fg.customer.clone(),
         is_serialized,
-        batch,
-        serial_range,
+        batch: selection.batch.clone(),
+        serial_range: selection.serial_range.clone(),
+        retest_policy,
         test_results,
     })
 }
 
-/// Collect voltech test results
-async fn collect_voltech_test(
-    test: &test_entity::Model,
-    is_serialized: bool,
+/// The report run a batch or serial range identifies: the serial range when
+/// given, else the batch
+/// Serial ranges are keyed by their canonical text, so "1001 - 1050" and
+/// "01001-01050" are the same run as "1001-1050"
+pub(crate) fn run_scope(
     batch: Option<&str>,
     serial_range: Option<&str>,
-    selected_dates: Option<&Vec<String>>,
-    voltech_db: &DbConn,
-) -> Result<TestResultData, DbErr> {
-    let associated_test = test.associated_test.as_deref().unwrap_or("");
-    let mut results = Vec::new();
-
-    if is_serialized {
-        // Serialized mode: filter by serial range
-        if let Some(range) = serial_range {
-            // Parse serial range (e.g., "1001-1010")
-            if let Some((start_str, end_str)) = range.split_once('-') {
-                if let (Ok(start), Ok(end)) = (start_str.parse::<i32>(), end_str.parse::<i32>()) {
-                    // Query using measurements JSON search
-                    let records = voltech_test_results::Entity::find()
-                        .filter(
-                            voltech_test_results::Column::Measurements
-                                .contains(&format!("\"{}\"", associated_test)),
-                        )
-                        .all(voltech_db)
-                        .await?;
-
-                    for record in records {
-                        // Check if serial is within range
-                        if let Ok(serial_num) = record.serial_num.parse::<i32>() {
-                            if serial_num >= start && serial_num <= end {
-                                // Parse measurements JSON
-                                let measurements: serde_json::Value =
-                                    serde_json::from_str(&record.measurements)
-                                        .unwrap_or(serde_json::json!({}));
-
-                                results.push(SingleTestResult {
-                                    serial_number: Some(record.serial_num.clone()),
-                                    batch: record.batch.clone(),
-                                    date: record
-                                        .normalized_date
-                                        .map(|d| d.to_string())
-                                        .unwrap_or_default(),
-                                    result: record.pass_fail.clone(),
-                                    measurements,
-                                });
-                            }
-                        }
-                    }
-                }
+) -> Result<Option<String>, String> {
+    if let Some(serial_range) = serial_range.map(str::trim).filter(|s| !s.is_empty()) {
+        return SerialSpec::parse(serial_range).map(|spec| Some(spec.canonical()));
+    }
+    Ok(batch
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string))
+}
+
+/// Parse the serial specification a serialized report is scoped to
+pub(crate) fn serial_spec(serial_range: Option<&str>) -> Result<Option<SerialSpec>, DbErr> {
+    serial_range
+        .map(SerialSpec::parse)
+        .transpose()
+        .map_err(DbErr::Custom)
+}
+
+/// Records of one serial that count as retests of each other: serials the
+/// report's specification treats as one, or the recorded serial in batch mode
+fn retest_key(serial: &str, serials: Option<&SerialSpec>) -> Option<String> {
+    match serials {
+        Some(serials) => serials.label(serial),
+        None => Some(serial.trim().to_string()).filter(|s| !s.is_empty()),
+    }
+}
+
+/// Which tester records belong to a report: the FG's part numbers, then the
+/// serial specification (serialized FGs) or the batch, session dates and
+/// selected sessions
+struct RecordScope<'a> {
+    voltech_parts: Vec<String>,
+    manual_parts: Vec<String>,
+    is_serialized: bool,
+    selection: &'a RecordSelection,
+    serials: Option<&'a SerialSpec>,
+}
+
+impl<'a> RecordScope<'a> {
+    async fn for_fg(
+        fg: &fg_entity::Model,
+        selection: &'a RecordSelection,
+        serials: Option<&'a SerialSpec>,
+        core_db: &DbConn,
+        voltech_db: &DbConn,
+        manual_db: &DbConn,
+    ) -> Result<Self, DbErr> {
+        Ok(Self {
+            voltech_parts: voltech_parts(core_db, voltech_db, &fg.fg, Some(&fg.rev)).await?,
+            manual_parts: manual_parts(core_db, manual_db, &fg.fg, Some(&fg.rev)).await?,
+            is_serialized: fg.serialized,
+            selection,
+            serials,
+        })
+    }
+
+    /// Selected session dates, as dates; `None` selects every date
+    /// Sessions included by id replace the date selection
+    fn dates(&self) -> Option<Vec<NaiveDate>> {
+        if !self.selection.sessions.include.is_empty() {
+            return None;
+        }
+        self.selection.selected_dates.as_ref().map(|dates| {
+            dates
+                .iter()
+                .filter_map(|d| NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d").ok())
+                .collect()
+        })
+    }
+
+    /// Voltech records in scope that carry any of the measurements, in one query
+    ///
+    /// Pass state is not filtered here: the retest policy decides which
+    /// attempts to keep, and it needs the failures to do so. Session ids are
+    /// not stored, so selected sessions are applied to the query's rows.
+    async fn voltech_records(
+        &self,
+        associated_tests: &[&str],
+        voltech_db: &DbConn,
+    ) -> Result<Vec<voltech_test_results::Model>, DbErr> {
+        if associated_tests.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // The FG's part numbers, then the measurements JSON search
+        let measurements = associated_tests.iter().fold(Condition::any(), |any, test| {
+            any.add(voltech_test_results::Column::Measurements.contains(measurement_key(test)))
+        });
+        let mut query = voltech_test_results::Entity::find()
+            .filter(voltech_test_results::Column::Part.is_in(self.voltech_parts.iter().cloned()))
+            .filter(measurements);
+
+        if self.is_serialized {
+            // Serialized mode: filter by serial specification
+            let Some(serials) = self.serials else {
+                return Ok(Vec::new());
+            };
+            query = query.filter(serials.condition(voltech_test_results::Column::SerialNum));
+        } else {
+            // Batch mode: filter by batch and optionally selected dates
+            if let Some(batch_val) = &self.selection.batch {
+                query = query.filter(voltech_test_results::Column::Batch.eq(batch_val.as_str()));
+            }
+            if let Some(dates) = self.dates() {
+                query = query.filter(voltech_test_results::Column::NormalizedDate.is_in(dates));
             }
         }
-    } else {
-        // Batch mode: filter by batch and optionally selected dates
-        let mut query = voltech_test_results::Entity::find().filter(
-            voltech_test_results::Column::Measurements
-                .contains(&format!("\"{}\"", associated_test)),
-        );
 
-        if let Some(batch_val) = batch {
-            query = query.filter(voltech_test_results::Column::Batch.eq(batch_val));
+        let mut records = query
+            .order_by_asc(voltech_test_results::Column::Id)
+            .all(voltech_db)
+            .await?;
+        if !self.is_serialized && !self.selection.sessions.is_empty() {
+            records.retain(|r| {
+                self.selection
+                    .sessions
+                    .keeps(&SessionBlock::voltech(r).id())
+            });
         }
+        Ok(records)
+    }
 
-        let records = query.all(voltech_db).await?;
+    /// Manual records in scope for any of the tests, in one query
+    async fn manual_records(
+        &self,
+        associated_tests: &[&str],
+        manual_db: &DbConn,
+    ) -> Result<Vec<manual_test_results::Model>, DbErr> {
+        if associated_tests.is_empty() {
+            return Ok(Vec::new());
+        }
 
-        // Filter by selected dates if provided
-        for record in records {
-            let date_str = record
-                .normalized_date
-                .map(|d| d.to_string())
-                .unwrap_or_default();
+        let mut query = manual_test_results::Entity::find()
+            .filter(manual_test_results::Column::Fg.is_in(self.manual_parts.iter().cloned()))
+            .filter(manual_test_results::Column::Test.is_in(associated_tests.iter().copied()));
 
-            let include = if let Some(dates) = selected_dates {
-                dates.contains(&date_str)
-            } else {
-                true
+        if self.is_serialized {
+            // Serialized mode: filter by serial specification
+            let Some(serials) = self.serials else {
+                return Ok(Vec::new());
             };
-
-            if include {
-                // Parse measurements JSON
-                let measurements: serde_json::Value =
-                    serde_json::from_str(&record.measurements).unwrap_or(serde_json::json!({}));
-
-                results.push(SingleTestResult {
-                    serial_number: Some(record.serial_num.clone()),
-                    batch: record.batch.clone(),
-                    date: date_str,
-                    result: record.pass_fail.clone(),
-                    measurements,
-                });
+            query = query.filter(serials.condition(manual_test_results::Column::Sn));
+        } else {
+            // Batch mode: filter by batch and optionally selected dates
+            if let Some(batch_val) = &self.selection.batch {
+                query = query.filter(manual_test_results::Column::Batch.eq(batch_val.as_str()));
+            }
+            if let Some(dates) = self.dates() {
+                query = query.filter(manual_test_results::Column::NormalizedDate.is_in(dates));
             }
         }
+
+        let mut records = query
+            .order_by_asc(manual_test_results::Column::Id)
+            .all(manual_db)
+            .await?;
+        if !self.is_serialized && !self.selection.sessions.is_empty() {
+            records.retain(|r| self.selection.sessions.keeps(&SessionBlock::manual(r).id()));
+        }
+        Ok(records)
     }
+}
+
+/// The quoted JSON key a Voltech measurement is stored under
+fn measurement_key(associated_test: &str) -> String {
+    format!("\"{}\"", associated_test)
+}
 
-    Ok(TestResultData {
+/// Whether a record's measurements JSON mentions the measurement, matching the
+/// SQL search (LIKE ignores ASCII case)
+fn has_measurement(measurements: &str, associated_test: &str) -> bool {
+    measurements
+        .to_ascii_lowercase()
+        .contains(&measurement_key(associated_test).to_ascii_lowercase())
+}
+
+/// The associated tests of a report's tests from one source
+fn associated_tests<'t>(tests: &'t [test_entity::Model], source_type: &str) -> Vec<&'t str> {
+    let mut names: Vec<&str> = tests
+        .iter()
+        .filter(|t| t.source_type == source_type)
+        .map(|t| t.associated_test.as_deref().unwrap_or(""))
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
+fn test_result_data(test: &test_entity::Model, results: Vec<SingleTestResult>) -> TestResultData {
+    let mut data = TestResultData {
         test_id: test.id,
         test_name: test.test_type.clone(),
         source_type: test.source_type.clone(),
@@ -247,108 +381,268 @@ async fn collect_voltech_test(
         spec_max: test.maximum,
         spec_unit: Some(test.uo_m.clone()),
         results,
-    })
+    };
+    scale_to_spec_unit(&mut data);
+    data
 }
 
-/// Collect manual test results
-async fn collect_manual_test(
-    test: &test_entity::Model,
-    is_serialized: bool,
-    batch: Option<&str>,
-    serial_range: Option<&str>,
-    selected_dates: Option<&Vec<String>>,
-    manual_db: &DbConn,
-) -> Result<TestResultData, DbErr> {
-    let associated_test = test.associated_test.as_deref().unwrap_or("");
-    let mut results = Vec::new();
-
-    if is_serialized {
-        // Serialized mode: filter by serial range
-        if let Some(range) = serial_range {
-            // Parse serial range (e.g., "1001-1010")
-            if let Some((start_str, end_str)) = range.split_once('-') {
-                if let (Ok(start), Ok(end)) = (start_str.parse::<i32>(), end_str.parse::<i32>()) {
-                    let records = manual_test_results::Entity::find()
-                        .filter(manual_test_results::Column::Test.eq(associated_test))
-                        .all(manual_db)
-                        .await?;
-
-                    for record in records {
-                        // Check if serial is within range
-                        if let Ok(serial_num) = record.sn.parse::<i32>() {
-                            if serial_num >= start && serial_num <= end {
-                                // Create measurements JSON from manual test data
-                                let measurements = serde_json::json!({
-                                    "minimum": record.minimum,
-                                    "reading": record.reading,
-                                    "maximum": record.maximum,
-                                    "uom": record.uom
-                                });
-
-                                results.push(SingleTestResult {
-                                    serial_number: Some(record.sn.clone()),
-                                    batch: record.batch.clone(),
-                                    date: record.normalized_date.to_string(),
-                                    result: record.passfail.clone(),
-                                    measurements,
-                                });
-                            }
-                        }
-                    }
+/// Express spec readings in the unit declared on the test, so they sit
+/// alongside its limits. Voltech records readings in base units; manual rows
+/// carry their own unit; "other" values are recorded in the spec unit already.
+/// Readings without a recognised unit are left as-is.
+fn scale_to_spec_unit(data: &mut TestResultData) {
+    if data.source_type == "other" {
+        return;
+    }
+    let Some(unit) = data.spec_unit.as_deref().and_then(Unit::parse) else {
+        return;
+    };
+
+    let manual = data.source_type == "manual";
+    let mut results = std::mem::take(&mut data.results);
+
+    for result in &mut results {
+        let Some(obj) = result.measurements.as_object_mut() else {
+            continue;
+        };
+
+        if manual {
+            // Limits recorded with the reading share its unit
+            let Some(from) = obj
+                .get("uom")
+                .and_then(|v| v.as_str())
+                .and_then(Unit::parse)
+            else {
+                continue;
+            };
+            if from.base != unit.base {
+                continue;
+            }
+            for key in ["minimum", "reading", "maximum"] {
+                let scaled = obj
+                    .get(key)
+                    .and_then(numeric_value)
+                    .and_then(|value| from.convert_to(value, &unit));
+                if let Some(scaled) = scaled {
+                    obj.insert(key.to_string(), serde_json::json!(scaled));
+                }
+            }
+            obj.insert("uom".to_string(), serde_json::json!(unit.to_string()));
+        } else {
+            for (key, value) in obj.iter_mut() {
+                if !data.is_spec_column(key) {
+                    continue;
+                }
+                if let Some(number) = numeric_value(value) {
+                    *value = serde_json::json!(unit.scale_from_base(number));
                 }
             }
         }
-    } else {
-        // Batch mode: filter by batch and optionally selected dates
-        let mut query = manual_test_results::Entity::find()
-            .filter(manual_test_results::Column::Test.eq(associated_test));
+    }
 
-        if let Some(batch_val) = batch {
-            query = query.filter(manual_test_results::Column::Batch.eq(batch_val));
-        }
+    data.results = results;
+}
+
+/// Collect voltech test results from the report's Voltech records
+fn collect_voltech_test(
+    test: &test_entity::Model,
+    records: &[voltech_test_results::Model],
+    serials: Option<&SerialSpec>,
+    policy: RetestPolicy,
+) -> TestResultData {
+    let associated_test = test.associated_test.as_deref().unwrap_or("");
+    let records = records
+        .iter()
+        .filter(|r| has_measurement(&r.measurements, associated_test))
+        .cloned()
+        .collect();
+
+    let records = resolve(
+        records,
+        policy,
+        |r| retest_key(&r.serial_num, serials),
+        |r| r.pass_fail.eq_ignore_ascii_case("pass"),
+        voltech_attempt_order,
+    );
+
+    let results = records
+        .into_iter()
+        .map(|resolved| {
+            let retests = resolved.retests();
+            let record = resolved.record;
+            SingleTestResult {
+                // Parse measurements JSON
+                measurements: serde_json::from_str(&record.measurements)
+                    .unwrap_or(serde_json::json!({})),
+                serial_number: Some(record.serial_num),
+                batch: record.batch,
+                date: record
+                    .normalized_date
+                    .map(|d| d.to_string())
+                    .unwrap_or_default(),
+                result: record.pass_fail,
+                retests,
+            }
+        })
+        .collect();
 
-        let records = query.all(manual_db).await?;
+    test_result_data(test, results)
+}
 
-        // Filter by selected dates if provided
-        for record in records {
-            let date_str = record.normalized_date.to_string();
+/// Collect the outcomes recorded by hand for an "other" test in this run
+fn collect_other_test(
+    test: &test_entity::Model,
+    records: &[OtherResult],
+    run: Option<&str>,
+    policy: RetestPolicy,
+) -> TestResultData {
+    let records = records
+        .iter()
+        .filter(|r| r.test_id == test.id)
+        .cloned()
+        .collect();
+
+    let records = resolve(
+        records,
+        policy,
+        |r: &OtherResult| retest_key(r.serial_number.as_deref().unwrap_or(""), None),
+        |r| r.result.eq_ignore_ascii_case("pass"),
+        |r| (r.recorded_at.clone(), r.id),
+    );
+
+    let results = records
+        .into_iter()
+        .map(|resolved| {
+            let retests = resolved.retests();
+            let record = resolved.record;
+            SingleTestResult {
+                measurements: serde_json::json!({
+                    "value": record.value,
+                    "recorded_by": record.recorded_by,
+                    "recorded_at": record.recorded_at,
+                    "attachment": record.attachment,
+                    "notes": record.notes
+                }),
+                serial_number: record.serial_number,
+                batch: run.unwrap_or_default().to_string(),
+                date: record.recorded_at.chars().take(10).collect(),
+                result: record.result,
+                retests,
+            }
+        })
+        .collect();
 
-            let include = if let Some(dates) = selected_dates {
-                dates.contains(&date_str)
-            } else {
-                true
-            };
+    test_result_data(test, results)
+}
 
-            if include {
+/// Collect manual test results from the report's manual records
+fn collect_manual_test(
+    test: &test_entity::Model,
+    records: &[manual_test_results::Model],
+    serials: Option<&SerialSpec>,
+    policy: RetestPolicy,
+) -> TestResultData {
+    let associated_test = test.associated_test.as_deref().unwrap_or("");
+    let records = records
+        .iter()
+        .filter(|r| r.test == associated_test)
+        .cloned()
+        .collect();
+
+    let records = resolve(
+        records,
+        policy,
+        |r| retest_key(&r.sn, serials),
+        |r| manual_verdict(r).eq_ignore_ascii_case("pass"),
+        manual_attempt_order,
+    );
+
+    let results = records
+        .into_iter()
+        .map(|resolved| {
+            let retests = resolved.retests();
+            let record = resolved.record;
+            let result = manual_verdict(&record).to_string();
+            SingleTestResult {
                 // Create measurements JSON from manual test data
-                let measurements = serde_json::json!({
+                measurements: serde_json::json!({
                     "minimum": record.minimum,
                     "reading": record.reading,
                     "maximum": record.maximum,
                     "uom": record.uom
-                });
-
-                results.push(SingleTestResult {
-                    serial_number: Some(record.sn.clone()),
-                    batch: record.batch.clone(),
-                    date: date_str,
-                    result: record.passfail.clone(),
-                    measurements,
-                });
+                }),
+                serial_number: Some(record.sn),
+                batch: record.batch,
+                date: record.normalized_date.to_string(),
+                result,
+                retests,
             }
-        }
-    }
+        })
+        .collect();
 
-    Ok(TestResultData {
-        test_id: test.id,
-        test_name: test.test_type.clone(),
-        source_type: test.source_type.clone(),
-        associated_test: test.associated_test.clone(),
-        spec_min: test.minimum,
-        spec_max: test.maximum,
-        spec_unit: Some(test.uo_m.clone()),
-        results,
-    })
+    test_result_data(test, results)
+}
+
+/// Every underlying source row behind a report, including retests and failures
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RawRecords {
+    pub voltech: Vec<voltech_test_results::Model>,
+    pub manual: Vec<manual_test_results::Model>,
+}
+
+/// Collect the raw source rows for a report, using the same scope as
+/// `collect_report_data`
+/// Rows shared by several tests appear once, ordered by serial and time
+pub async fn collect_raw_records(
+    report_id: i32,
+    selection: &RecordSelection,
+    core_db: &DbConn,
+    voltech_db: &DbConn,
+    manual_db: &DbConn,
+) -> Result<RawRecords, DbErr> {
+    let report = report_entity::Entity::find_by_id(report_id)
+        .one(core_db)
+        .await?
+        .ok_or(DbErr::RecordNotFound("Report not found".to_string()))?;
+
+    let fg = fg_entity::Entity::find_by_id(report.fg_id)
+        .one(core_db)
+        .await?
+        .ok_or(DbErr::RecordNotFound("FG not found".to_string()))?;
+
+    let serials = if fg.serialized {
+        serial_spec(selection.serial_range.as_deref())?
+    } else {
+        None
+    };
+    let scope = RecordScope::for_fg(
+        &fg,
+        selection,
+        serials.as_ref(),
+        core_db,
+        voltech_db,
+        manual_db,
+    )
+    .await?;
+    let tests = test_entity::Entity::find()
+        .filter(test_entity::Column::ReportId.eq(report_id))
+        .all(core_db)
+        .await?;
+
+    let mut voltech = scope
+        .voltech_records(&associated_tests(&tests, "voltech"), voltech_db)
+        .await?;
+    let mut manual = scope
+        .manual_records(&associated_tests(&tests, "manual"), manual_db)
+        .await?;
+
+    voltech.sort_by(|a, b| {
+        (&a.serial_num, voltech_attempt_order(a)).cmp(&(&b.serial_num, voltech_attempt_order(b)))
+    });
+
+    manual.sort_by(|a, b| (&a.sn, manual_attempt_order(a)).cmp(&(&b.sn, manual_attempt_order(b))));
+
+    Ok(RawRecords { voltech, manual })
 }
 
 // ============================================================================
@@ -361,13 +655,18 @@ pub async fn collect_report(
     batch: Option<String>,
     serial_range: Option<String>,
     selected_dates: Option<Vec<String>>,
+    sessions: Option<SessionSelection>,
     state: State<'_, AppState>,
 ) -> Result<ReportData, String> {
-    match collect_report_data(
-        report_id,
+    let selection = RecordSelection {
         batch,
         serial_range,
         selected_dates,
+        sessions: sessions.unwrap_or_default(),
+    };
+    match collect_report_data(
+        report_id,
+        selection,
         &state.core_db,
         &state.voltech_db,
         &state.manual_db,
@@ -378,3 +677,82 @@ pub async fn collect_report(
         Err(e) => Err(format!("Failed to collect report data: {}", e)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_data(
+        source_type: &str,
+        associated: &str,
+        measurements: Vec<serde_json::Value>,
+    ) -> TestResultData {
+        TestResultData {
+            test_id: 1,
+            test_name: "Inductance".to_string(),
+            source_type: source_type.to_string(),
+            associated_test: Some(associated.to_string()),
+            spec_min: Some(11.0),
+            spec_max: Some(13.0),
+            spec_unit: Some("mH".to_string()),
+            results: measurements
+                .into_iter()
+                .map(|measurements| SingleTestResult {
+                    serial_number: Some("1001".to_string()),
+                    batch: "B1".to_string(),
+                    date: "2025-11-19".to_string(),
+                    result: "PASS".to_string(),
+                    measurements,
+                    retests: 0,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_voltech_readings_scale_to_spec_unit() {
+        let mut data = test_data(
+            "voltech",
+            "002 LS Reading",
+            vec![serde_json::json!({"002 LS Reading": "+1.22774E-02", "003 R Reading": 0.5})],
+        );
+        scale_to_spec_unit(&mut data);
+
+        let m = &data.results[0].measurements;
+        assert!((m["002 LS Reading"].as_f64().unwrap() - 12.2774).abs() < 1e-9);
+        // Other measurements have no declared unit
+        assert_eq!(m["003 R Reading"], serde_json::json!(0.5));
+    }
+
+    #[test]
+    fn test_manual_readings_convert_from_recorded_unit() {
+        let mut data = test_data(
+            "manual",
+            "DCR1",
+            vec![
+                serde_json::json!({"minimum": 11000.0, "reading": 12280.0, "maximum": 13000.0, "uom": "uH"}),
+                serde_json::json!({"minimum": 1.0, "reading": 2.0, "maximum": 3.0, "uom": "Ohm"}),
+            ],
+        );
+        scale_to_spec_unit(&mut data);
+
+        let m = &data.results[0].measurements;
+        assert!((m["reading"].as_f64().unwrap() - 12.28).abs() < 1e-9);
+        assert!((m["minimum"].as_f64().unwrap() - 11.0).abs() < 1e-9);
+        assert_eq!(m["uom"], "mH");
+        // A different quantity is left untouched
+        assert_eq!(
+            data.results[1].measurements["reading"],
+            serde_json::json!(2.0)
+        );
+        assert_eq!(data.results[1].measurements["uom"], "Ohm");
+    }
+
+    #[test]
+    fn test_split_matches_measurement_search() {
+        let json = r#"{"002 LS Reading": "+1.2E-02", "003 R Reading": 0.5}"#;
+        assert!(has_measurement(json, "002 LS Reading"));
+        assert!(has_measurement(json, "002 ls reading"));
+        assert!(!has_measurement(json, "002 LS"));
+    }
+}
@@ -1,3 +1,5 @@
+use crate::part_mapping::voltech_parts;
+use crate::serials::SerialSpec;
 use crate::AppState;
 use entity_voltech::test_results as voltech_test_results;
 use sea_orm::*;
@@ -31,15 +33,10 @@ pub async fn debug_voltech_query(
 ) -> Result<DebugQueryResult, String> {
     let voltech_db = &state.voltech_db;
 
-    // Parse serial range
-    let parts: Vec<&str> = serial_range.split('-').collect();
-    if parts.len() != 2 {
-        return Err("Invalid serial range format".to_string());
-    }
-    let (start, end) = match (parts[0].parse::<i32>(), parts[1].parse::<i32>()) {
-        (Ok(s), Ok(e)) => (s.to_string(), e.to_string()),
-        _ => return Err("Invalid serial numbers".to_string()),
-    };
+    let serials = SerialSpec::parse(&serial_range)?;
+    let parts = voltech_parts(&state.core_db, voltech_db, &fg_number, None)
+        .await
+        .map_err(|e| e.to_string())?;
 
     // Total records
     let total_records = voltech_test_results::Entity::find()
@@ -47,25 +44,25 @@ pub async fn debug_voltech_query(
         .await
         .map_err(|e| e.to_string())
         .map(|count| count as i32)?;
-    // Matching FG (using starts_with since part = FG + type + revision)
+    // Matching FG (the FG's part numbers at any revision)
     let matching_fg = voltech_test_results::Entity::find()
-        .filter(voltech_test_results::Column::Part.starts_with(&fg_number))
+        .filter(voltech_test_results::Column::Part.is_in(parts.clone()))
         .count(voltech_db.as_ref())
         .await
         .map_err(|e| e.to_string())
         .map(|count| count as i32)?;
     // Matching FG and serials
     let matching_fg_and_serials = voltech_test_results::Entity::find()
-        .filter(voltech_test_results::Column::Part.starts_with(&fg_number))
-        .filter(voltech_test_results::Column::SerialNum.between(&start, &end))
+        .filter(voltech_test_results::Column::Part.is_in(parts.clone()))
+        .filter(serials.condition(voltech_test_results::Column::SerialNum))
         .count(voltech_db.as_ref())
         .await
         .map_err(|e| e.to_string())
         .map(|count| count as i32)?;
     // Matching pass
     let matching_pass = voltech_test_results::Entity::find()
-        .filter(voltech_test_results::Column::Part.starts_with(&fg_number))
-        .filter(voltech_test_results::Column::SerialNum.between(&start, &end))
+        .filter(voltech_test_results::Column::Part.is_in(parts.clone()))
+        .filter(serials.condition(voltech_test_results::Column::SerialNum))
         .filter(voltech_test_results::Column::PassFail.eq("Pass"))
         .count(voltech_db.as_ref())
         .await
@@ -73,8 +70,8 @@ pub async fn debug_voltech_query(
         .map(|count| count as i32)?;
     // Matching measurement
     let matching_measurement = voltech_test_results::Entity::find()
-        .filter(voltech_test_results::Column::Part.starts_with(&fg_number))
-        .filter(voltech_test_results::Column::SerialNum.between(&start, &end))
+        .filter(voltech_test_results::Column::Part.is_in(parts.clone()))
+        .filter(serials.condition(voltech_test_results::Column::SerialNum))
         .filter(voltech_test_results::Column::PassFail.eq("Pass"))
         .filter(
             voltech_test_results::Column::Measurements
@@ -87,7 +84,7 @@ pub async fn debug_voltech_query(
 
     // Get sample records for inspection
     let sample_records = voltech_test_results::Entity::find()
-        .filter(voltech_test_results::Column::Part.starts_with(&fg_number))
+        .filter(voltech_test_results::Column::Part.is_in(parts.clone()))
         .limit(5)
         .all(voltech_db.as_ref())
         .await
@@ -1,50 +1,217 @@
-use log::debug;
-// use umya_spreadsheet::*;
-use rust_xlsxwriter::*;
-use sea_orm::{Database, DbConn};
-use serde_json::Value;
 use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
 
-use sea_orm::sea_query::ExprTrait;
-use sea_orm::{ColumnTrait, EntityTrait, ModelTrait, QueryFilter};
-
-use crate::reports::serialized::query_manual_serialized;
-use crate::{reports::serialized::query_serialized, test::TestData};
-
-use crate::{voltech, AppState};
-use entity::{fg, report, test};
+use entity::test;
 use entity_manual::manual_test_results;
 use entity_voltech::test_results;
-use tauri::State;
+use log::debug;
+use rust_xlsxwriter::utility::cell_range;
+use rust_xlsxwriter::*;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde::Deserialize;
+use serde_json::Value;
+use tauri::{AppHandle, State};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::fg::get_customer_pn;
+use crate::manual::limits::manual_verdict;
+use crate::part_mapping::{manual_parts, voltech_parts};
+use crate::reports::collector::{run_scope, ReportData, SingleTestResult, TestResultData};
+use crate::reports::jobs::{no_progress, JobPhase, Progress};
+use crate::reports::other_results::{run_results, serial_outcomes, OtherResult};
+use crate::reports::paging::PageGrid;
+use crate::reports::retest::{report_policy, resolve, Resolved, RetestPolicy};
+use crate::reports::serialized::{query_manual_serialized, query_serialized};
+use crate::reports::snapshot::{self, ReportParameters, SnapshotInput, FORMAT_BA_XLSX};
+use crate::reports::statistics::{column_stats, numeric_value, write_stat, STATS};
+use crate::reports::template::{
+    apply_page_setup, create_cell_format, create_label_format, create_title_format, insert_logo,
+    write_number, write_signature_block, PageSetup, TemplateValues,
+};
+use crate::reports::template_store::select_template;
+use crate::serials::SerialSpec;
+use crate::test::TestData;
+use crate::units::{normalize_uom, NumberFormat, Unit};
+use crate::AppState;
 
 // Function to normalize spaces in measurement keys
 fn normalize_spaces(s: &str) -> String {
     s.split_whitespace().collect::<Vec<&str>>().join(" ")
 }
 
-// Validate that for each expected manual test suffix, the manual_map has at least one PASS (case-insensitive)
-fn manual_map_all_pass(
-    manual_map: &HashMap<String, Vec<manual_test_results::Model>>,
-    expected: &[String],
-) -> bool {
-    for suffix in expected {
-        match manual_map.get(suffix) {
-            Some(entries) => {
-                let ok = entries
-                    .iter()
-                    .any(|m| m.passfail.eq_ignore_ascii_case("pass"));
-                if !ok {
-                    println!("Missing PASS for manual suffix: '{}'", suffix);
-                    return false;
-                }
-            }
-            None => {
-                println!("Missing manual suffix: '{}'", suffix);
-                return false;
-            }
+// Write one spec cell of a test column, selected by the template's field name
+fn write_spec_field(
+    worksheet: &mut Worksheet,
+    row: u32,
+    col: u16,
+    test: &TestData,
+    field: &str,
+    numbers: &NumberFormat,
+    format: &Format,
+) -> Result<(), XlsxError> {
+    // Limits are shown with the template's number format
+    let limit = match field {
+        "minimum" => test.minimum,
+        "maximum" => test.maximum,
+        _ => None,
+    };
+    if let Some(value) = limit {
+        return write_number(worksheet, (row, col), value, numbers, format);
+    }
+
+    match field {
+        "test_type" => worksheet.write_with_format(row, col, &test.test_type, format)?,
+        "source_type" => worksheet.write_with_format(row, col, &test.source_type, format)?,
+        "voltage" => worksheet.write_with_format(row, col, test.voltage, format)?,
+        "frequency" => worksheet.write_with_format(row, col, test.frequency, format)?,
+        "minimum" => worksheet.write_with_format(row, col, test.minimum, format)?,
+        "maximum" => worksheet.write_with_format(row, col, test.maximum, format)?,
+        "uo_m" => worksheet.write_with_format(row, col, normalize_uom(&test.uo_m), format)?,
+        "primary_pins" => worksheet.write_with_format(row, col, test.primary_pins.clone(), format)?,
+        "secondary_pins" => {
+            worksheet.write_with_format(row, col, test.secondary_pins.clone(), format)?
+        }
+        "shorted_pins" => worksheet.write_with_format(row, col, test.shorted_pins.clone(), format)?,
+        "description" => worksheet.write_with_format(row, col, test.description.clone(), format)?,
+        "associated_test" => {
+            worksheet.write_with_format(row, col, test.associated_test.clone(), format)?
+        }
+        _ => worksheet.write_with_format(row, col, "", format)?,
+    };
+    Ok(())
+}
+
+/// Why a serial is not a clean pass, for the status column and summary
+#[derive(Debug, Clone, PartialEq)]
+enum PartIssue {
+    Missing(String), // No data recorded for the test
+    Failing(String), // Failing verdict, or a reading outside the test's limits
+}
+
+/// Overall result of one serial across every test
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PartStatus {
+    Pass,
+    Fail,
+    Incomplete,
+}
+
+impl PartStatus {
+    fn from_issues(issues: &[PartIssue]) -> Self {
+        if issues.iter().any(|i| matches!(i, PartIssue::Failing(_))) {
+            PartStatus::Fail
+        } else if !issues.is_empty() {
+            PartStatus::Incomplete
+        } else {
+            PartStatus::Pass
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            PartStatus::Pass => "PASS",
+            PartStatus::Fail => "FAIL",
+            PartStatus::Incomplete => "INCOMPLETE",
         }
     }
-    true
+}
+
+// A reading passes when it is inside every limit the test sets
+fn within_limits(value: f64, minimum: Option<f64>, maximum: Option<f64>) -> bool {
+    minimum.map_or(true, |min| value >= min) && maximum.map_or(true, |max| value <= max)
+}
+
+// Summary text for a serial's issues, e.g. "Missing: LS; Failing: DCR1"
+fn describe_issues(issues: &[PartIssue]) -> String {
+    let names = |missing: bool| -> Vec<&str> {
+        issues
+            .iter()
+            .filter_map(|issue| match issue {
+                PartIssue::Missing(name) if missing => Some(name.as_str()),
+                PartIssue::Failing(name) if !missing => Some(name.as_str()),
+                _ => None,
+            })
+            .collect()
+    };
+
+    let mut parts = Vec::new();
+    for (label, names) in [("Missing", names(true)), ("Failing", names(false))] {
+        if !names.is_empty() {
+            parts.push(format!("{}: {}", label, names.join(", ")));
+        }
+    }
+    parts.join("; ")
+}
+
+// Outcome of an "other" test for one serial: the policy's pick among the outcomes
+// recorded for the serial, or among those recorded for the whole run when none name it
+fn other_outcome<'r>(
+    outcomes: &'r [OtherResult],
+    test_id: i32,
+    sn: &str,
+    serials: &SerialSpec,
+    policy: RetestPolicy,
+) -> Option<Resolved<&'r OtherResult>> {
+    let attempts = serial_outcomes(outcomes, test_id, sn, serials);
+
+    // Outcomes are oldest first; every attempt belongs to this serial
+    resolve(
+        attempts.into_iter().enumerate().collect(),
+        policy,
+        |_| Some(sn.to_string()),
+        |(_, r)| r.result.eq_ignore_ascii_case("pass"),
+        |(index, _)| *index,
+    )
+    .pop()
+    .map(|resolved| Resolved {
+        record: resolved.record.1,
+        attempts: resolved.attempts,
+    })
+}
+
+// Report test each BA column was built from: same source and type, preferring the same order
+fn column_test_ids(tests: &[TestData], report_tests: &[test::Model]) -> Vec<Option<i32>> {
+    tests
+        .iter()
+        .map(|t| {
+            let same_test =
+                |m: &&test::Model| m.source_type == t.source_type && m.test_type == t.test_type;
+            report_tests
+                .iter()
+                .filter(same_test)
+                .find(|m| m.order == t.order)
+                .or_else(|| report_tests.iter().find(same_test))
+                .map(|m| m.id)
+        })
+        .collect()
+}
+
+// Measurement name a BA cell is kept under in the report snapshot
+fn snapshot_column(test: &TestData) -> String {
+    match test.source_type.as_str() {
+        "manual" => "reading".to_string(),
+        "other" => "value".to_string(),
+        _ => test
+            .associated_test
+            .clone()
+            .unwrap_or_else(|| test.test_type.clone()),
+    }
+}
+
+// For each expected manual test suffix, the latest attempt in manual_map needs a PASS (case-insensitive)
+fn manual_issues(
+    manual_map: &HashMap<String, Vec<manual_test_results::Model>>,
+    expected: &[String],
+) -> Vec<PartIssue> {
+    expected
+        .iter()
+        .filter_map(|suffix| match manual_map.get(suffix) {
+            Some(entries) if entries.last().is_some_and(|m| manual_verdict(m).eq_ignore_ascii_case("pass")) => None,
+            Some(_) => Some(PartIssue::Failing(suffix.clone())),
+            None => Some(PartIssue::Missing(suffix.clone())),
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -53,7 +220,7 @@ mod tests {
     use chrono::{NaiveDate, Utc};
 
     #[test]
-    fn test_manual_map_all_pass() {
+    fn test_manual_issues() {
         let mut map: HashMap<String, Vec<manual_test_results::Model>> = HashMap::new();
 
         let m1 = manual_test_results::Model {
@@ -75,12 +242,17 @@ mod tests {
             file_path: "f".to_string(),
             created_at: Utc::now().into(),
             normalized_date: NaiveDate::from_ymd_opt(2025, 12, 12).unwrap(),
+            computed_passfail: None,
+            tested_at: None,
         };
 
         map.entry("DCR1".to_string()).or_default().push(m1);
 
-        assert!(manual_map_all_pass(&map, &vec!["DCR1".to_string()]));
-        assert!(!manual_map_all_pass(&map, &vec!["DCR1".to_string(), "DCR2".to_string()]));
+        assert!(manual_issues(&map, &vec!["DCR1".to_string()]).is_empty());
+        assert_eq!(
+            manual_issues(&map, &vec!["DCR1".to_string(), "DCR2".to_string()]),
+            vec![PartIssue::Missing("DCR2".to_string())]
+        );
 
         let m2 = manual_test_results::Model {
             id: 2,
@@ -101,10 +273,15 @@ mod tests {
             file_path: "f".to_string(),
             created_at: Utc::now().into(),
             normalized_date: NaiveDate::from_ymd_opt(2025, 12, 12).unwrap(),
+            computed_passfail: None,
+            tested_at: None,
         };
 
         map.entry("DCR2".to_string()).or_default().push(m2);
-        assert!(!manual_map_all_pass(&map, &vec!["DCR1".to_string(), "DCR2".to_string()]));
+        assert_eq!(
+            manual_issues(&map, &vec!["DCR1".to_string(), "DCR2".to_string()]),
+            vec![PartIssue::Failing("DCR2".to_string())]
+        );
 
         // Add a PASS for DCR2
         let m3 = manual_test_results::Model {
@@ -126,10 +303,64 @@ mod tests {
             file_path: "f".to_string(),
             created_at: Utc::now().into(),
             normalized_date: NaiveDate::from_ymd_opt(2025, 12, 12).unwrap(),
+            computed_passfail: None,
+            tested_at: None,
         };
 
         map.get_mut("DCR2").unwrap().push(m3);
-        assert!(manual_map_all_pass(&map, &vec!["DCR1".to_string(), "DCR2".to_string()]));
+        assert!(manual_issues(&map, &vec!["DCR1".to_string(), "DCR2".to_string()]).is_empty());
+    }
+
+    #[test]
+    fn test_part_status_and_limits() {
+        assert!(within_limits(1.5, Some(1.0), Some(2.0)));
+        assert!(within_limits(2.0, Some(1.0), Some(2.0)));
+        assert!(!within_limits(0.9, Some(1.0), None));
+        assert!(within_limits(100.0, None, None));
+
+        let issues = vec![
+            PartIssue::Missing("LS".to_string()),
+            PartIssue::Failing("DCR1".to_string()),
+            PartIssue::Missing("DCR2".to_string()),
+        ];
+        assert_eq!(PartStatus::from_issues(&issues), PartStatus::Fail);
+        assert_eq!(PartStatus::from_issues(&issues[..1]), PartStatus::Incomplete);
+        assert_eq!(PartStatus::from_issues(&[]), PartStatus::Pass);
+        assert_eq!(describe_issues(&issues), "Missing: LS, DCR2; Failing: DCR1");
+    }
+
+    #[test]
+    fn test_other_outcome_per_serial() {
+        let outcome = |id: i32, serial: Option<&str>, result: &str| OtherResult {
+            id,
+            report_id: 1,
+            test_id: 7,
+            scope: "1001-1003".to_string(),
+            serial_number: serial.map(str::to_string),
+            value: None,
+            result: result.to_string(),
+            notes: None,
+            attachment: None,
+            recorded_by: "AB".to_string(),
+            recorded_at: format!("2025-11-19 10:0{}:00", id),
+        };
+        // Run-wide pass; 1002 failed then passed; 1003 failed
+        let outcomes = vec![
+            outcome(1, None, "PASS"),
+            outcome(2, Some("1002"), "FAIL"),
+            outcome(3, Some("1002"), "PASS"),
+            outcome(4, Some("1003"), "FAIL"),
+        ];
+        let serials = SerialSpec::parse("1001-1003").unwrap();
+        let pick = |sn: &str, policy| {
+            other_outcome(&outcomes, 7, sn, &serials, policy).map(|r| (r.record.id, r.attempts))
+        };
+
+        assert_eq!(pick("1001", RetestPolicy::LatestPass), Some((1, 1)));
+        assert_eq!(pick("1002", RetestPolicy::LatestPass), Some((3, 2)));
+        assert_eq!(pick("1002", RetestPolicy::LatestAttempt), Some((3, 2)));
+        assert_eq!(pick("1003", RetestPolicy::LatestPass), Some((4, 1)));
+        assert!(other_outcome(&outcomes, 8, "1001", &serials, RetestPolicy::LatestPass).is_none());
     }
 }
 
@@ -141,81 +372,88 @@ fn test_normalize_spaces() {
     assert_eq!(normalize_spaces("no change"), "no change");
 }
 
-// #[tokio::test]
-// async fn test_returned_tests() -> Result<(), Box<dyn std::error::Error>> {
-//     let db: DbConn = Database::connect(
-//         "sqlite://C:/Users/bashleigh/Desktop/ProductionProjects/REMOTE/testing.sqlite",
-//     )
-//     .await?;
-
-//     let report_id = 2;
-
-//     let tests = test::Entity::find()
-//         .filter(test::Column::ReportId.eq(report_id))
-//         .all(&db)
-//         .await
-//         .map_err(|e| format!("Failed to fetch tests: {}", e))?;
-
-//     println!("Tests: {}", tests.len());
-//     assert_eq!(tests.len(), 1);
-//     Ok(())
-// }
+/// Inputs for a customer BA report
+#[derive(Debug, Deserialize)]
+pub struct BaReportRequest {
+    pub fg: String,
+    pub rev: String,
+    pub serials: String, // Serial specification, e.g. "1001-1050, 1060"
+    pub tests: Vec<TestData>,
+    pub job_number: String,
+    pub split: String,
+    pub date_code: String,
+    pub template_id: Option<i32>,
+}
 
+/// Generate the BA report and return the workbook bytes
 #[tauri::command]
 pub async fn create_ba_report(
-    fg: String,
-    rev: String,
-    start_serial: i32,
-    end_serial: i32,
     state: State<'_, AppState>,
-    tests: Vec<TestData>,
-    job_number: String,
-    split: String,
-    date_code: String,
+    request: BaReportRequest,
+) -> Result<Vec<u8>, String> {
+    xlsx(&state, &request, &no_progress)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Generate the BA report and save it to the given path, optionally opening it
+#[tauri::command]
+pub async fn save_ba_report(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    request: BaReportRequest,
+    file_path: String,
+    open_after_save: Option<bool>,
 ) -> Result<(), String> {
-    xlsx(
-        state,
-        &fg,
-        &rev,
-        start_serial,
-        end_serial,
+    let buffer = create_ba_report(state, request).await?;
+
+    let path = Path::new(&file_path);
+
+    // Create parent directories if they don't exist
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directories: {}", e))?;
+    }
+
+    fs::write(path, buffer).map_err(|e| format!("Failed to write file: {}", e))?;
+
+    // Open with the default application for .xlsx files
+    if open_after_save.unwrap_or(false) {
+        app.opener()
+            .open_path(&file_path, None::<&str>)
+            .map_err(|e| format!("Report saved but could not be opened: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Build the BA workbook, reporting the collection and rendering phases
+pub async fn xlsx(
+    state: &AppState,
+    request: &BaReportRequest,
+    progress: Progress<'_>,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let BaReportRequest {
+        fg,
+        rev,
+        serials,
         tests,
         job_number,
         split,
         date_code,
-    )
-    .await
-    .map_err(|e| e.to_string())
-}
+        template_id,
+    } = request;
+    let (fg, rev) = (fg.as_str(), rev.as_str());
+    let serials = SerialSpec::parse(serials)?;
 
-pub async fn xlsx(
-    state: State<'_, AppState>,
-    fg: &str,
-    rev: &str,
-    start_serial: i32,
-    end_serial: i32,
-    tests: Vec<TestData>,
-    job_number: String,
-    split: String,
-    date_code: String,
-) -> Result<(), Box<dyn std::error::Error>> {
-    // Display input parameters
-    println!("=== XLSX Report Generation Parameters ===");
-    println!("FG: {}", fg);
-    println!("Revision: {}", rev);
-    println!("Serial Range: {} - {}", start_serial, end_serial);
-    println!("Number of test types: {}", tests.len());
-    println!("Test types:");
-    for (i, test) in tests.iter().enumerate() {
-        println!(
-            "  {}. {} ({} {})",
-            i + 1,
-            test.test_type,
-            test.source_type,
-            test.primary_pins.as_deref().unwrap_or("N/A")
-        );
-    }
-    println!("==========================================");
+    debug!(
+        "BA report: FG {} rev {}, serials {}, {} tests",
+        fg,
+        rev,
+        serials,
+        tests.len()
+    );
+
+    progress(JobPhase::Collecting)?;
 
     // Create a new Excel file object.
     let mut workbook = Workbook::new();
@@ -223,223 +461,239 @@ pub async fn xlsx(
     let db = &*state.voltech_db;
     let man_db = &*state.manual_db;
 
-    // FIXME : Input validation
+    // Header values come from the FG record
+    let fg_model = ::entity::fg::Entity::find()
+        .filter(::entity::fg::Column::Fg.eq(fg))
+        .one(&*state.core_db)
+        .await?
+        .ok_or_else(|| format!("FG {} not found", fg))?;
+    let customer_pn = get_customer_pn(&state.core_db, fg_model.id).await?;
+
+    // Retests are resolved with the policy of the report the tests belong to
+    let report_id = tests.iter().find_map(|t| t.report_id);
+    let policy = match report_id {
+        Some(report_id) => report_policy(&state.core_db, report_id).await?,
+        None => None,
+    }
+    .unwrap_or(RetestPolicy::SERIALIZED_DEFAULT);
+
+    // Tester part numbers recorded for this FG and revision
+    let fg_voltech_parts = voltech_parts(&state.core_db, db, fg, Some(rev)).await?;
+    let fg_manual_parts = manual_parts(&state.core_db, man_db, fg, Some(rev)).await?;
 
-    let test_data: Vec<test_results::Model> =
-        query_serialized(fg, rev, start_serial, end_serial, db).await?;
+    let test_data = query_serialized(&fg_voltech_parts, &serials, policy, db).await?;
 
-    let manual_data : Vec<entity_manual::manual_test_results::Model> = match query_manual_serialized(fg, rev, start_serial, end_serial, man_db).await
+    let manual_data = match query_manual_serialized(&fg_manual_parts, &serials, policy, man_db).await
     {
         Ok(data) => data,
         Err(e) => {
-            println!("Manual data query failed: {}", e);
+            debug!("Manual data query failed: {}", e);
             return Err(Box::new(e));
         }
     };
 
-    // Use tests parameter to determine columns - one column per test
-    // println!(
-    //     "Using {} tests to determine spreadsheet columns",
-    //     tests.len()
-    // );
+    progress(JobPhase::Rendering)?;
 
-    // Decode json blob from test_data.measurements
+    // Layout comes from the template selected for this FG (or customer)
+    let template = select_template(
+        &state.core_db,
+        *template_id,
+        Some(fg_model.id),
+        Some(&fg_model.customer),
+    )
+    .await?;
+    let ba = &template.ba;
+    let style = &template.style_config;
+
+    let mut values = TemplateValues::new();
+    values
+        .set("fg_number", fg_model.fg.as_str())
+        .set("fg_revision", rev)
+        .set("customer", fg_model.customer.as_str())
+        .set("customer_pn", customer_pn.unwrap_or_default())
+        .set("serial_range", serials.to_string())
+        .set("job_number", job_number.as_str())
+        .set("split", split.as_str())
+        .set("date_code", date_code.as_str())
+        .set("retest_policy", policy.label());
 
     // Add a worksheet to the workbook.
     let worksheet = workbook.add_worksheet();
 
     // Main Title
-    let merge_title_format = Format::new()
-        .set_font_name("Aptos Display")
-        .set_font_size(20)
-        .set_align(FormatAlign::VerticalCenter)
-        .set_align(FormatAlign::Center)
-        .set_border(FormatBorder::Medium)
-        .set_bold();
+    let merge_title_format = create_title_format(style);
 
     // Normal Bold + Border
-    let title_format = Format::new()
-        .set_font_name("Aptos Narrow")
-        .set_align(FormatAlign::VerticalCenter)
-        .set_align(FormatAlign::Center)
-        .set_border(FormatBorder::Medium)
-        .set_font_size(12)
-        .set_bold();
+    let title_format = create_label_format(style);
 
     // Names
-    let bold_format = Format::new()
-        .set_font_name("Aptos Narrow")
-        .set_align(FormatAlign::VerticalCenter)
-        .set_align(FormatAlign::Center)
-        .set_font_size(11)
-        .set_bold()
-        .set_text_wrap()
-        .set_border(FormatBorder::Thin);
+    let bold_format = create_cell_format(style);
+
+    let spec_start_row: u32 = 3;
+    let serial_label_row = spec_start_row + ba.spec_rows.len() as u32;
+    let data_start_row = serial_label_row + 1;
+
+    // Printed pages: each column group holds a row label column followed by
+    // test columns, and serial rows are split into page-height groups.
+    // The status and retest columns follow the last test column.
+    let serial_labels = serials.serials();
+    let status_index = tests.len();
+    let retests_index = status_index + 1;
+    let grid = PageGrid::new(
+        ba.columns_per_page,
+        ba.rows_per_page,
+        tests.len() + 2,
+        data_start_row,
+        serial_labels.len() as u32,
+    );
+    let page_count = grid.column_pages();
+    let test_col = |index: usize| grid.column(index);
+    debug!("BA layout: {} printed pages for the data", grid.page_count());
 
     // Global Formatting
-    worksheet.set_row_height_pixels(0, 35)?;
-    worksheet.set_row_height_pixels(1, 27)?;
+    worksheet.set_row_height_pixels(0, ba.title_row_height)?;
+    worksheet.set_row_height_pixels(1, ba.info_row_height)?;
     worksheet.set_row_height_pixels(2, 10)?;
 
-    worksheet.set_landscape();
-    worksheet.set_print_area(0, 0, 20, 20);
-
-    worksheet.set_margins(0.3, 0.3, 0.3, 0.3, 0.0, 0.0);
+    // The grid sets its own page breaks, which Excel ignores on a sheet scaled
+    // to fit the width, so fit-to-width does not apply to the BA report
+    let page_setup = PageSetup {
+        fit_to_width: false,
+        ..template.page.clone()
+    };
+    apply_page_setup(worksheet, &page_setup);
 
     // -------------------- Start Writing Data
 
-    let mut col_index: u16 = 0;
-    let mut test_index: usize = 0;
-
-    // Write Headers (based on number of tests, not voltech data)
-    while test_index < tests.len() {
-
-        // Write header for every 10 columns (accounting for pages)
-        if col_index % 10 == 0 {
-
-            // Main Title
-            worksheet.merge_range(
-                0,
-                col_index,
-                0,
-                col_index + 9,
-                "VISHAY HIREL TEST REPORT FG XXXXXX",
-                &merge_title_format,
-            )?;
-
-            // Customer PN
-            worksheet.merge_range(
-                1,
-                col_index,
-                1,
-                col_index + 2,
-                "Customer PN XXXXXX-XXX",
-                &title_format,
-            )?;
+    // Write page headers (based on number of tests, not voltech data)
+    for page in 0..page_count {
+        let page_col = grid.label_col(page);
+
+        insert_logo(worksheet, template.logo.as_ref(), page_col)?;
+
+        // Main Title
+        worksheet.merge_range(
+            0,
+            page_col,
+            0,
+            grid.data_cols(page).end - 1,
+            &values.render(&ba.title),
+            &merge_title_format,
+        )?;
+
+        // Info row (Customer PN, Job, Split, Date Code by default)
+        // Spans are clipped to the page so they never overlap the next one
+        let page_end = grid.data_cols(page).end;
+        let mut info_col = page_col;
+        for cell in &ba.info_cells {
+            if info_col >= page_end {
+                break;
+            }
+            let span = cell.span.max(1).min(page_end - info_col);
+            if span > 1 {
+                worksheet.merge_range(
+                    1,
+                    info_col,
+                    1,
+                    info_col + span - 1,
+                    &values.render(&cell.text),
+                    &title_format,
+                )?;
+            } else {
+                worksheet.write_with_format(1, info_col, values.render(&cell.text), &title_format)?;
+            }
+            info_col += span;
+        }
 
-            // Job Number
-            worksheet.write_with_format(1, col_index + 3, "Job", &title_format)?;
-            worksheet.merge_range(
-                1,
-                col_index + 4,
-                1,
-                col_index + 5,
-                &job_number,
+        // Row Headers
+        for (offset, spec_row) in ba.spec_rows.iter().enumerate() {
+            worksheet.write_with_format(
+                spec_start_row + offset as u32,
+                page_col,
+                &spec_row.label,
                 &title_format,
             )?;
-            worksheet.write_with_format(1, col_index + 6, "Split", &title_format)?;
-            worksheet.write_with_format(1, col_index + 7, &split, &title_format)?;
-            worksheet.write_with_format(1, col_index + 8, "Date Code", &title_format)?;
-            worksheet.write_with_format(1, col_index + 9, &date_code, &title_format)?;
-            
-            // Row Headers
-            worksheet.write_with_format(3, col_index, "Test", &title_format)?;
-            worksheet.write_with_format(4, col_index, "Source", &title_format)?;
-            worksheet.write_with_format(5, col_index, "Level", &title_format)?;
-            worksheet.write_with_format(6, col_index, "Frequency", &title_format)?;
-            worksheet.write_with_format(7, col_index, "Minimum", &title_format)?;
-            worksheet.write_with_format(8, col_index, "Maximum", &title_format)?;
-            worksheet.write_with_format(9, col_index, "UoM", &title_format)?;
-            worksheet.write_with_format(10, col_index, "Pins", &title_format)?;
-            worksheet.write_with_format(11, col_index, "Notes", &title_format)?;
-            worksheet.write_with_format(12, col_index, "SN", &title_format)?;
-
-            // Set column width FIX ME
-            worksheet.set_column_width(col_index, 14)?;
-
-            col_index += 1;
-        } else {
-            // FIX ME I don't know how much of this I still need
-            worksheet.set_column_width(col_index, 12)?;
+        }
+        worksheet.write_with_format(serial_label_row, page_col, &ba.serial_label, &title_format)?;
 
-            test_index += 1;
-            col_index += 1;
+        worksheet.set_column_width(page_col, ba.label_column_width)?;
+        for col in grid.data_cols(page) {
+            worksheet.set_column_width(col, ba.data_column_width)?;
         }
     }
 
     // Write test headers
-    let mut test_col_index: u16 = 1;
-    for test in &tests {
-        worksheet.write_with_format(3, test_col_index, &test.test_type, &bold_format)?;
-        worksheet.write_with_format(4, test_col_index, &test.source_type, &bold_format)?;
-        worksheet.write_with_format(5, test_col_index, test.voltage, &bold_format)?;
-        worksheet.write_with_format(6, test_col_index, test.frequency, &bold_format)?;
-        worksheet.write_with_format(7, test_col_index, test.minimum, &bold_format)?;
-        worksheet.write_with_format(8, test_col_index, test.maximum, &bold_format)?;
-        worksheet.write_with_format(9, test_col_index, &test.uo_m, &bold_format)?;
-        worksheet.write_with_format(10, test_col_index, test.primary_pins.clone(), &bold_format)?;
-        worksheet.write_with_format(11, test_col_index, test.description.clone(), &bold_format)?;
-        test_col_index += 1;
+    for (index, test) in tests.iter().enumerate() {
+        let col = test_col(index);
+        for (offset, spec_row) in ba.spec_rows.iter().enumerate() {
+            write_spec_field(
+                worksheet,
+                spec_start_row + offset as u32,
+                col,
+                test,
+                &spec_row.field,
+                &template.numbers,
+                &bold_format,
+            )?;
+        }
     }
+    worksheet.write_with_format(serial_label_row, test_col(status_index), "Status", &title_format)?;
+    worksheet.write_with_format(serial_label_row, test_col(retests_index), "Retests", &title_format)?;
 
     // -------------- TEST RESULTS -----------------------------------------------------------------------------//
 
     // Process actual test data and write to spreadsheet
-    let mut row_index: u32 = 13;
 
     struct Part {
-        sn: i32,
+        sn: String,
         voltech_data: Vec<test_results::Model>,
-        manual_data: Vec<manual_test_results::Model>,
         manual_map: HashMap<String, Vec<manual_test_results::Model>>,
-        valid: bool,
+        issues: Vec<PartIssue>,
+        retests: i32, // Voltech, manual and "other" attempts after each test's first
+        voltech_retests: i32,
     }
 
-    impl Part {
-        // Validate Voltech Data
-        fn is_voltech_valid(&self) -> bool {
-            if !self.voltech_data.is_empty() {
-                for test in &self.voltech_data {
-                    if !test.pass_fail.eq_ignore_ascii_case("pass") {
-                        return false;
-                    }
-                }
-                true
-            } else {
-                false
-            }
-        }
-
-        // Validate Manual Data
-        fn is_manual_valid(&self) -> bool {
-            if !self.manual_data.is_empty() {
-                for test in &self.manual_data {
-                    if !test.passfail.eq_ignore_ascii_case("pass") {
-                        return false;
-                    }
-                }
-                true
-            } else {
-                false
-            }
-        }
-
-        fn test_check(&mut self) {
-            self.valid = self.is_voltech_valid() && self.is_manual_valid();
-        }
-    };
-
     // Data container
     let mut parts: Vec<Part> = Vec::new();
 
     // Create Part per sn
-    for sn in start_serial..=end_serial {
+    for sn in serial_labels {
         parts.push(Part {
             sn,
             voltech_data: Vec::new(),
-            manual_data: Vec::new(),
             manual_map: HashMap::new(),
-            valid: false,
+            issues: Vec::new(),
+            retests: 0,
+            voltech_retests: 0,
         });
     }
 
-    // Convert voltech data into a hashmap, where key = sn
-    let mut voltech_by_part: HashMap<i32, Vec<test_results::Model>> = HashMap::new();
-    for test in test_data {
-        if let Ok(sn) = test.serial_num.parse::<i32>() {
+    // Convert voltech data into a hashmap, where key = sn as the spec writes it
+    // Retests are counted once per serial (or serial and manual test), however many attempts the policy keeps
+    let mut retests_by_part: HashMap<String, HashMap<String, i32>> = HashMap::new();
+    let mut voltech_by_part: HashMap<String, Vec<test_results::Model>> = HashMap::new();
+    for resolved in test_data {
+        let retests = resolved.retests();
+        let test = resolved.record;
+        if let Some(sn) = serials.label(&test.serial_num) {
+            retests_by_part.entry(sn.clone()).or_default().insert("Voltech".to_string(), retests);
             voltech_by_part.entry(sn).or_default().push(test);
         } else {
-            println!("Warning: Could not parse voltech test serial_num '{}' as i32", test.serial_num);
+            debug!("Voltech serial_num '{}' is outside the serial specification", test.serial_num);
+        }
+    }
+
+    // The report's tests, the one each column was built from, and the outcomes
+    // recorded by hand for "other" tests in this run
+    let spec_rows = match report_id {
+        Some(report_id) => snapshot::load_spec_rows(&state.core_db, report_id).await?,
+        None => Vec::new(),
+    };
+    let test_ids = column_test_ids(tests, &spec_rows);
+    let mut other_data: Vec<OtherResult> = Vec::new();
+    if let Some(report_id) = report_id.filter(|_| tests.iter().any(|t| t.source_type == "other")) {
+        if let Some(scope) = run_scope(None, Some(&request.serials))? {
+            other_data = run_results(&state.core_db, report_id, Some(&scope)).await?;
         }
     }
 
@@ -456,14 +710,18 @@ pub async fn xlsx(
             normalize_spaces(&candidate)
         })
         .collect();
+    let has_voltech_tests = tests.iter().any(|t| t.source_type == "voltech");
 
     // Convert manual data into a hashmap, where key = sn -> (suffix -> Vec<manual_test_results::Model>)
-    let mut manual_by_part: HashMap<i32, HashMap<String, Vec<manual_test_results::Model>>> = HashMap::new();
-    for test in manual_data {
+    let mut manual_by_part: HashMap<String, HashMap<String, Vec<manual_test_results::Model>>> = HashMap::new();
+    for resolved in manual_data {
+        let retests = resolved.retests();
+        let test = resolved.record;
         // Get end of test name: eg 132520-LFG-DCR1 = DCR1
         let suffix = test.test.split('-').last().unwrap_or("").to_string();
 
-        if let Ok(sn) = test.sn.parse::<i32>() {
+        if let Some(sn) = serials.label(&test.sn) {
+            retests_by_part.entry(sn.clone()).or_default().insert(test.test.clone(), retests);
             manual_by_part
                 .entry(sn)
                 .or_default()
@@ -471,7 +729,7 @@ pub async fn xlsx(
                 .or_default()
                 .push(test);
         } else {
-            println!("Warning: Could not parse manual test SN '{}' as i32", test.sn);
+            debug!("Manual test SN '{}' is outside the serial specification", test.sn);
         }
     }
 
@@ -484,126 +742,325 @@ pub async fn xlsx(
         // Account for multiple manual tests (grouped by suffix)
         if let Some(m_map) = manual_by_part.remove(&part.sn) {
             part.manual_map = m_map;
-            // also keep a flattened list if needed elsewhere
-            part.manual_data = part
-                .manual_map
-                .values()
-                .flat_map(|v| v.clone())
-                .collect();
+        }
+
+        if let Some(retests) = retests_by_part.remove(&part.sn) {
+            part.voltech_retests = retests.get("Voltech").copied().unwrap_or(0);
+            part.retests = retests.values().sum();
         }
     }
 
-    // Validate Tests using grouped manual slots
+    // Recorded verdicts: the latest kept Voltech run and each manual test's latest kept attempt must pass
     for part in &mut parts {
-        let vol_ok = part.is_voltech_valid();
-        let manual_ok = manual_map_all_pass(&part.manual_map, &expected_manual_suffixes);
-        part.valid = vol_ok && manual_ok;
-        println!(
-            "SNS : {} vol_ok:{} manual_ok:{} overall:{}",
-            part.sn, vol_ok, manual_ok, part.valid
-        );
+        if has_voltech_tests {
+            if part.voltech_data.is_empty() {
+                part.issues.push(PartIssue::Missing("Voltech".to_string()));
+            } else if part
+                .voltech_data
+                .last()
+                .is_some_and(|t| !t.pass_fail.eq_ignore_ascii_case("pass"))
+            {
+                part.issues.push(PartIssue::Failing("Voltech".to_string()));
+            }
+        }
+        part.issues
+            .extend(manual_issues(&part.manual_map, &expected_manual_suffixes));
     }
 
+    // Readings inside the limits are shaded as passes, outside as fails
+    let pass_format = bold_format.clone().set_background_color(style.pass_color);
+    let fail_format = bold_format.clone().set_background_color(style.fail_color);
+
+    // Numeric readings written per test column, for the statistics rows
+    let mut column_values: Vec<Vec<f64>> = vec![Vec::new(); tests.len()];
+
+    // Cells written per test column, kept with the report snapshot
+    let mut column_results: Vec<Vec<SingleTestResult>> = tests.iter().map(|_| Vec::new()).collect();
+
     // Print parts: write serial and test measurement values per row
-    for (index, part) in parts.iter().enumerate() {
-        let row = 13 + index as u32;
-        // Write serial number in column 0
-        worksheet.write_with_format(row, 0, part.sn, &bold_format)?;
-
-        // If we have voltech data for this part, parse the first record's measurements
-        if let Some(record) = part.voltech_data.first() {
-            let measurements_map: serde_json::Map<String, Value> = match serde_json::from_str(&record.measurements) {
-                Ok(m) => m,
-                Err(e) => {
-                    println!("Failed to parse measurements for SN {}: {}", part.sn, e);
-                    // Fill with blanks and continue
-                    let mut col_index: u16 = 1;
-                    for _ in &tests {
-                        worksheet.write_with_format(row, col_index, "", &bold_format)?;
-                        col_index += 1;
-                    }
-                    continue;
-                }
-            };
+    for (index, part) in parts.iter_mut().enumerate() {
+        let row = data_start_row + index as u32;
+        // Write serial number in the label column of every page
+        for page in 0..page_count {
+            worksheet.write_with_format(
+                row,
+                grid.label_col(page),
+                &part.sn,
+                &bold_format,
+            )?;
+        }
 
-            // Normalize keys for lookup
-            let normalized_measurements: HashMap<String, &Value> = measurements_map
-                .iter()
-                .map(|(k, v)| (normalize_spaces(k), v))
-                .collect();
+        // Parse the latest kept Voltech record's measurements; unreadable records count as no data
+        let measurements_map: serde_json::Map<String, Value> = match part.voltech_data.last() {
+            Some(record) => serde_json::from_str(&record.measurements).unwrap_or_else(|e| {
+                debug!("Failed to parse measurements for SN {}: {}", part.sn, e);
+                serde_json::Map::new()
+            }),
+            None => serde_json::Map::new(),
+        };
 
-            // Helper to find a measurement value given candidate keys
-            let find_measurement = |candidates: &Vec<String>| -> Option<&Value> {
-                // Exact match attempts
+        // Run the measurements came from, for the snapshot
+        let (voltech_batch, voltech_date, voltech_verdict) = part
+            .voltech_data
+            .last()
+            .map(|record| (record.batch.clone(), record.date.clone(), record.pass_fail.to_uppercase()))
+            .unwrap_or_default();
+
+        // Normalize keys for lookup
+        let normalized_measurements: HashMap<String, &Value> = measurements_map
+            .iter()
+            .map(|(k, v)| (normalize_spaces(k), v))
+            .collect();
+
+        // Helper to find a measurement value given candidate keys
+        let find_measurement = |candidates: &Vec<String>| -> Option<&Value> {
+            // Exact match attempts
+            for c in candidates {
+                if let Some(v) = normalized_measurements.get(c) {
+                    return Some(*v);
+                }
+            }
+            // Substring match attempts (more permissive)
+            for (k, v) in &normalized_measurements {
                 for c in candidates {
-                    if let Some(v) = normalized_measurements.get(c) {
+                    if k.contains(c) || c.contains(k) {
                         return Some(*v);
                     }
                 }
-                // Substring match attempts (more permissive)
-                for (k, v) in &normalized_measurements {
-                    for c in candidates {
-                        if k.contains(c) || c.contains(k) {
-                            return Some(*v);
-                        }
+            }
+            None
+        };
+
+        // Write each test's value into its column
+        for (index, test) in tests.iter().enumerate() {
+            let col_index = test_col(index);
+
+            // "Other" tests show the outcome recorded for the serial, or for the whole run
+            if test.source_type == "other" {
+                let outcome = test_ids[index]
+                    .and_then(|test_id| other_outcome(&other_data, test_id, &part.sn, &serials, policy));
+                let Some(outcome) = outcome else {
+                    part.issues.push(PartIssue::Missing(test.test_type.clone()));
+                    worksheet.write_with_format(row, col_index, "", &bold_format)?;
+                    continue;
+                };
+                let retests = outcome.retests();
+                part.retests += retests;
+                let record = outcome.record;
+                let format = if record.result.eq_ignore_ascii_case("pass") {
+                    &pass_format
+                } else {
+                    part.issues.push(PartIssue::Failing(test.test_type.clone()));
+                    &fail_format
+                };
+                match record.value {
+                    Some(value) => {
+                        write_number(worksheet, (row, col_index), value, &template.numbers, format)?;
+                        column_values[index].push(value);
                     }
-                }
-                None
-            };
-
-            // Write each test's value into its column (columns start at 1)
-            let mut col_index: u16 = 1;
-            for test in &tests {
-                // Build candidate keys: associated_test first, fallback to test_type
-                let mut candidates: Vec<String> = Vec::new();
-                if let Some(associated_test) = &test.associated_test {
-                    candidates.push(normalize_spaces(associated_test));
-                }
-                candidates.push(normalize_spaces(&test.test_type));
-
-                if let Some(value) = find_measurement(&candidates) {
-                    match value {
-                        Value::Number(num) => {
-                            if let Some(float_val) = num.as_f64() {
-                                worksheet.write_with_format(row, col_index, float_val, &bold_format)?;
-                            } else {
-                                worksheet.write_with_format(row, col_index, value.to_string(), &bold_format)?;
-                            }
-                        }
-                        Value::String(s) => {
-                            worksheet.write_with_format(row, col_index, s, &bold_format)?;
-                        }
-                        _ => {
-                            worksheet.write_with_format(row, col_index, value.to_string(), &bold_format)?;
-                        }
+                    None => {
+                        worksheet.write_with_format(row, col_index, &record.result, format)?;
                     }
+                }
+                column_results[index].push(SingleTestResult {
+                    serial_number: Some(part.sn.clone()),
+                    batch: String::new(),
+                    date: record.recorded_at.clone(),
+                    result: record.result.to_uppercase(),
+                    measurements: serde_json::json!({ "value": record.value }),
+                    retests,
+                });
+                continue;
+            }
+
+            // Build candidate keys: associated_test first, fallback to test_type
+            let mut candidates: Vec<String> = Vec::new();
+            if let Some(associated_test) = &test.associated_test {
+                candidates.push(normalize_spaces(associated_test));
+            }
+            candidates.push(normalize_spaces(&test.test_type));
+
+            if let Some(value) = find_measurement(&candidates) {
+                // Readings are judged against the limits; anything else keeps the run's verdict
+                let mut verdict = voltech_verdict.clone();
+                let mut recorded = value.clone();
+                if let Some(reading) = numeric_value(value) {
+                    // Voltech records base units; show the reading in the spec's unit
+                    let reading = Unit::parse(&test.uo_m)
+                        .map_or(reading, |unit| unit.scale_from_base(reading));
+
+                    let format = if test.minimum.is_none() && test.maximum.is_none() {
+                        &bold_format
+                    } else if within_limits(reading, test.minimum, test.maximum) {
+                        verdict = "PASS".to_string();
+                        &pass_format
+                    } else {
+                        part.issues.push(PartIssue::Failing(test.test_type.clone()));
+                        verdict = "FAIL".to_string();
+                        &fail_format
+                    };
+                    write_number(worksheet, (row, col_index), reading, &template.numbers, format)?;
+                    column_values[index].push(reading);
+                    recorded = serde_json::json!(reading);
+                } else if let Value::String(s) = value {
+                    worksheet.write_with_format(row, col_index, s, &bold_format)?;
                 } else {
-                    // No measurement for this test - leave cell empty and log for debugging
-                    println!("No measurement for SN {} candidates: {:?}", part.sn, candidates);
-                    worksheet.write_with_format(row, col_index, "", &bold_format)?;
+                    worksheet.write_with_format(row, col_index, value.to_string(), &bold_format)?;
+                }
+                let mut measurements = serde_json::Map::new();
+                measurements.insert(snapshot_column(test), recorded);
+                column_results[index].push(SingleTestResult {
+                    serial_number: Some(part.sn.clone()),
+                    batch: voltech_batch.clone(),
+                    date: voltech_date.clone(),
+                    result: verdict,
+                    measurements: Value::Object(measurements),
+                    retests: part.voltech_retests,
+                });
+            } else {
+                // No measurement for this test - leave cell empty
+                // Manual tests are checked against their own records above
+                if test.source_type == "voltech" && !part.voltech_data.is_empty() {
+                    part.issues.push(PartIssue::Missing(test.test_type.clone()));
                 }
+                worksheet.write_with_format(row, col_index, "", &bold_format)?;
+            }
+        }
+
+        // Overall status for the serial
+        let status = PartStatus::from_issues(&part.issues);
+        let status_format = match status {
+            PartStatus::Pass => &pass_format,
+            PartStatus::Fail => &fail_format,
+            PartStatus::Incomplete => &bold_format,
+        };
+        worksheet.write_with_format(row, test_col(status_index), status.label(), status_format)?;
+        worksheet.write_with_format(row, test_col(retests_index), part.retests, &bold_format)?;
+    }
+
+    let mut row = grid.end_row() + 1;
 
-                col_index += 1;
+    // Statistics per test column against its spec limits
+    if template.statistics.enabled && !parts.is_empty() {
+        let last_data_row = data_start_row + parts.len() as u32 - 1;
+
+        for stat in STATS {
+            for page in 0..page_count {
+                worksheet.write_with_format(row, grid.label_col(page), stat.label(), &title_format)?;
             }
-        } else {
-            // No voltech data - fill test columns with empty cells
-            let mut col_index: u16 = 1;
-            for _ in &tests {
-                worksheet.write_with_format(13 + index as u32, col_index, "", &bold_format)?;
-                col_index += 1;
+            for (index, test) in tests.iter().enumerate() {
+                let col = test_col(index);
+                let stats = column_stats(&test.test_type, &column_values[index], test.minimum, test.maximum);
+                let range = template
+                    .statistics
+                    .live_formulas
+                    .then(|| cell_range(data_start_row, col, last_data_row, col));
+                write_stat(worksheet, (row, col), stat, &stats, range.as_deref(), &bold_format)?;
             }
+            row += 1;
         }
+        row += 1;
     }
 
+    // Unit counts by overall status
+    for status in [PartStatus::Pass, PartStatus::Fail, PartStatus::Incomplete] {
+        let count = parts
+            .iter()
+            .filter(|p| PartStatus::from_issues(&p.issues) == status)
+            .count();
+        let label = match status {
+            PartStatus::Pass => "Units Pass",
+            PartStatus::Fail => "Units Fail",
+            PartStatus::Incomplete => "Units Incomplete",
+        };
+        worksheet.write_with_format(row, 0, label, &title_format)?;
+        worksheet.write_with_format(row, 1, count as f64, &bold_format)?;
+        row += 1;
+    }
+
+    // Serials with missing or failing data
+    let flagged: Vec<&Part> = parts.iter().filter(|p| !p.issues.is_empty()).collect();
+    if !flagged.is_empty() {
+        row += 1;
+        worksheet.write_with_format(row, 0, "Missing or failing data", &title_format)?;
+        row += 1;
+        for part in flagged {
+            let status = PartStatus::from_issues(&part.issues);
+            worksheet.write_with_format(row, 0, &part.sn, &bold_format)?;
+            worksheet.write_with_format(row, 1, status.label(), &bold_format)?;
+            worksheet.write(row, 2, describe_issues(&part.issues))?;
+            row += 1;
+        }
+    }
 
-    // Save the file to disk.
-    let path = "C:\\Users\\bashleigh\\Desktop\\ProductionProjects\\excel_writing\\custom_form.xlsx";
-    workbook.save(path)?;
+    // Print everything up to the end of the summary, with the title block on every page
+    grid.apply(worksheet, row)?;
 
-    // Try to open the file with the default application (Windows, macOS, Linux); ignore errors.
-    let _ = std::process::Command::new("cmd")
-        .args(&["/C", "start", "", path])
-        .spawn();
+    // Sign-off below the data
+    write_signature_block(
+        worksheet,
+        &template.signature_block,
+        style,
+        row + 1,
+        0,
+    )?;
 
-    Ok(())
+    let buffer = workbook.save_to_buffer()?;
+
+    // Keep exactly what was generated, with the cells it was built from
+    let Some(report_id) = report_id else {
+        debug!("BA report has no report; not recording a snapshot");
+        return Ok(buffer);
+    };
+    progress(JobPhase::Recording)?;
+    let data = ReportData {
+        report_id,
+        fg_id: fg_model.id,
+        fg_number: fg_model.fg.clone(),
+        fg_revision: rev.to_string(),
+        fg_customer: fg_model.customer.clone(),
+        is_serialized: true,
+        batch: None,
+        serial_range: Some(serials.to_string()),
+        retest_policy: policy,
+        test_results: tests
+            .iter()
+            .zip(test_ids)
+            .zip(column_results)
+            .map(|((test, test_id), results)| TestResultData {
+                test_id: test_id.unwrap_or_default(),
+                test_name: test.test_type.clone(),
+                source_type: test.source_type.clone(),
+                associated_test: test.associated_test.clone(),
+                spec_min: test.minimum,
+                spec_max: test.maximum,
+                spec_unit: Some(test.uo_m.clone()),
+                results,
+            })
+            .collect(),
+    };
+    let params = ReportParameters {
+        report_id,
+        batch: None,
+        serial_range: Some(request.serials.clone()),
+        selected_dates: None,
+        sessions: Default::default(),
+        template_id: *template_id,
+        sheets: Default::default(),
+    };
+    snapshot::record_snapshot(
+        &state.core_db,
+        SnapshotInput {
+            format: FORMAT_BA_XLSX,
+            parameters: &params,
+            data: &data,
+            template: &template,
+            spec_rows: &spec_rows,
+            raw: None,
+            bytes: &buffer,
+        },
+    )
+    .await?;
+
+    Ok(buffer)
 }
@@ -1,18 +1,128 @@
-use crate::reports::collector::ReportData;
+use crate::reports::collector::{ReportData, TestResultData};
+use crate::reports::statistics::{
+    numeric_value, test_statistics, write_stat, write_verdict_count, STATS,
+};
 use crate::reports::template::{
-    create_fail_format, create_header_format, create_no_data_format, create_pass_format,
-    create_regular_format, create_result_header_format, create_spec_header_format,
-    create_test_header_format, default_template,
+    apply_page_setup, create_fail_format, create_header_format, create_no_data_format,
+    create_pass_format, create_regular_format, create_result_header_format,
+    create_spec_header_format, create_test_header_format, insert_logo, write_number,
+    write_signature_block, ReportTemplate, TemplateValues,
 };
+use rust_xlsxwriter::utility::cell_range;
 use rust_xlsxwriter::*;
-use sea_orm::*;
 
-/// Generate Excel report from collected data
-pub fn generate_report(data: &ReportData) -> Result<Vec<u8>, XlsxError> {
-    let mut workbook = Workbook::new();
-    let worksheet = workbook.add_worksheet();
-    let template = default_template();
+/// Placeholder values available to the report header
+pub(crate) fn template_values(data: &ReportData) -> TemplateValues {
+    let mut values = TemplateValues::new();
+    values
+        .set("fg_number", data.fg_number.as_str())
+        .set("fg_revision", data.fg_revision.as_str())
+        .set("customer", data.fg_customer.as_str())
+        .set("batch", data.batch.as_deref().unwrap_or("N/A"))
+        .set(
+            "serial_range",
+            data.serial_range.as_deref().unwrap_or("N/A"),
+        )
+        .set("retest_policy", data.retest_policy.label());
+
+    if data.is_serialized {
+        values
+            .set("lot_label", "Serial Range:")
+            .set("lot", data.serial_range.as_deref().unwrap_or("N/A"));
+    } else {
+        values
+            .set("lot_label", "Batch:")
+            .set("lot", data.batch.as_deref().unwrap_or("N/A"));
+    }
+
+    values
+}
+
+/// Write the statistics block for one test under its results
+/// Values sit under the measurement columns they summarize; returns the next free row
+fn write_statistics(
+    worksheet: &mut Worksheet,
+    test_data: &TestResultData,
+    template: &ReportTemplate,
+    (start_row, col): (u32, u16),
+    (first_data_row, last_data_row): (u32, u32),
+    verdict_col: Option<u16>,
+    measurement_cols: &[(String, u16)],
+) -> Result<u32, XlsxError> {
+    let style = &template.style_config;
+    let header_fmt = create_spec_header_format(style);
+    let label_fmt = create_regular_format(style).set_bold();
+    let value_fmt = create_regular_format(style);
+
+    let stats = test_statistics(test_data);
+    let live = template.statistics.live_formulas;
+    let range = |c: u16| live.then(|| cell_range(first_data_row, c, last_data_row, c));
+
+    // Only columns that made it onto the sheet
+    let columns: Vec<_> = stats
+        .columns
+        .iter()
+        .filter_map(|column| {
+            measurement_cols
+                .iter()
+                .find(|(key, _)| *key == column.column)
+                .map(|(_, c)| (column, *c))
+        })
+        .collect();
+
+    let mut row = start_row;
+    worksheet.write_with_format(row, col, "Statistics", &header_fmt)?;
+    for (column, c) in &columns {
+        worksheet.write_with_format(row, *c, &column.column, &header_fmt)?;
+    }
+    row += 1;
+
+    if !columns.is_empty() {
+        for stat in STATS {
+            worksheet.write_with_format(row, col, stat.label(), &label_fmt)?;
+            for (column, c) in &columns {
+                write_stat(
+                    worksheet,
+                    (row, *c),
+                    stat,
+                    column,
+                    range(*c).as_deref(),
+                    &value_fmt,
+                )?;
+            }
+            row += 1;
+        }
+    }
+
+    let verdict_range = verdict_col.and_then(range);
+    for (label, verdict, count) in [
+        ("Pass", "PASS", stats.pass_count),
+        ("Fail", "FAIL", stats.fail_count),
+    ] {
+        worksheet.write_with_format(row, col, label, &label_fmt)?;
+        write_verdict_count(
+            worksheet,
+            (row, col + 1),
+            verdict,
+            count,
+            verdict_range.as_deref(),
+            &value_fmt,
+        )?;
+        row += 1;
+    }
+
+    Ok(row)
+}
+
+/// Write the report itself to a worksheet
+pub(crate) fn write_report_sheet(
+    worksheet: &mut Worksheet,
+    data: &ReportData,
+    template: &ReportTemplate,
+) -> Result<(), XlsxError> {
+    worksheet.set_name("Report")?;
     let style = &template.style_config;
+    let values = template_values(data);
 
     // Create formats
     let header_fmt = create_header_format(style);
@@ -21,49 +131,38 @@ pub fn generate_report(data: &ReportData) -> Result<Vec<u8>, XlsxError> {
     let result_header_fmt = create_result_header_format(style);
     let pass_fmt = create_pass_format(style);
     let fail_fmt = create_fail_format(style);
-    let regular_fmt = create_regular_format();
-    let no_data_fmt = create_no_data_format();
+    let regular_fmt = create_regular_format(style);
+    let no_data_fmt = create_no_data_format(style);
+
+    apply_page_setup(worksheet, &template.page);
+    insert_logo(worksheet, template.logo.as_ref(), 0)?;
 
     // ========================================================================
     // FG Header Section
     // ========================================================================
-    let mut current_row = template.fg_label_row;
-    let col = template.fg_label_col;
-
-    worksheet.write_with_format(current_row, col, "FG Number:", &header_fmt)?;
-    worksheet.write_with_format(current_row, col + 1, &data.fg_number, &header_fmt)?;
-
-    current_row = template.revision_label_row;
-    worksheet.write_with_format(current_row, col, "Revision:", &regular_fmt)?;
-    worksheet.write_with_format(current_row, col + 1, &data.fg_revision, &regular_fmt)?;
+    let mut current_row = template.header_start_row;
+    let col = template.header_col;
 
-    current_row = template.customer_label_row;
-    worksheet.write_with_format(current_row, col, "Customer:", &regular_fmt)?;
-    worksheet.write_with_format(current_row, col + 1, &data.fg_customer, &regular_fmt)?;
-
-    current_row = template.batch_serial_label_row;
-    if data.is_serialized {
-        worksheet.write_with_format(current_row, col, "Serial Range:", &regular_fmt)?;
-        worksheet.write_with_format(
-            current_row,
-            col + 1,
-            data.serial_range.as_deref().unwrap_or("N/A"),
-            &regular_fmt,
-        )?;
-    } else {
-        worksheet.write_with_format(current_row, col, "Batch:", &regular_fmt)?;
-        worksheet.write_with_format(
-            current_row,
-            col + 1,
-            data.batch.as_deref().unwrap_or("N/A"),
-            &regular_fmt,
-        )?;
+    for (idx, field) in template.header_fields.iter().enumerate() {
+        // The first header line is highlighted
+        let fmt = if idx == 0 { &header_fmt } else { &regular_fmt };
+        worksheet.write_with_format(current_row, col, values.render(&field.label), fmt)?;
+        worksheet.write_with_format(current_row, col + 1, values.render(&field.value), fmt)?;
+        current_row += 1;
     }
 
+    // Serial numbers and retests only apply to serialized FGs
+    let result_columns: Vec<&str> = template
+        .result_columns
+        .iter()
+        .map(|c| c.as_str())
+        .filter(|c| data.is_serialized || !matches!(*c, "serial" | "retests"))
+        .collect();
+
     // ========================================================================
     // Test Sections
     // ========================================================================
-    current_row = template.test_section_start_row;
+    current_row = template.test_section_start_row.max(current_row + 1);
 
     for test_data in &data.test_results {
         // Test name header
@@ -95,13 +194,25 @@ pub fn generate_report(data: &ReportData) -> Result<Vec<u8>, XlsxError> {
         worksheet.write_with_format(current_row, col, "Limits", &regular_fmt)?;
 
         if let Some(min) = test_data.spec_min {
-            worksheet.write_with_format(current_row, col + 1, min, &regular_fmt)?;
+            write_number(
+                worksheet,
+                (current_row, col + 1),
+                min,
+                &template.numbers,
+                &regular_fmt,
+            )?;
         } else {
             worksheet.write_with_format(current_row, col + 1, "N/A", &regular_fmt)?;
         }
 
         if let Some(max) = test_data.spec_max {
-            worksheet.write_with_format(current_row, col + 2, max, &regular_fmt)?;
+            write_number(
+                worksheet,
+                (current_row, col + 2),
+                max,
+                &template.numbers,
+                &regular_fmt,
+            )?;
         } else {
             worksheet.write_with_format(current_row, col + 2, "N/A", &regular_fmt)?;
         }
@@ -120,31 +231,83 @@ pub fn generate_report(data: &ReportData) -> Result<Vec<u8>, XlsxError> {
             current_row += 1;
         } else {
             // Results header
-            if data.is_serialized {
-                worksheet.write_with_format(current_row, col, "Serial #", &result_header_fmt)?;
-            }
-            worksheet.write_with_format(current_row, col + 1, "Batch", &result_header_fmt)?;
-            worksheet.write_with_format(current_row, col + 2, "Date", &result_header_fmt)?;
-            worksheet.write_with_format(current_row, col + 3, "Result", &result_header_fmt)?;
-
-            // Add measurement column headers dynamically
             let mut measurement_keys: Vec<String> = Vec::new();
             if let Some(first_result) = test_data.results.first() {
                 if let Some(obj) = first_result.measurements.as_object() {
                     measurement_keys = obj.keys().cloned().collect();
-                    for (idx, key) in measurement_keys.iter().enumerate() {
+                }
+            }
+
+            // Where the verdicts and each measurement land, for the statistics block
+            let mut verdict_col = None;
+            let mut measurement_cols: Vec<(String, u16)> = Vec::new();
+
+            let mut current_col = col;
+            for column in &result_columns {
+                match *column {
+                    "serial" => {
                         worksheet.write_with_format(
                             current_row,
-                            col + 4 + idx as u16,
-                            key,
+                            current_col,
+                            "Serial #",
                             &result_header_fmt,
                         )?;
                     }
+                    "batch" => {
+                        worksheet.write_with_format(
+                            current_row,
+                            current_col,
+                            "Batch",
+                            &result_header_fmt,
+                        )?;
+                    }
+                    "date" => {
+                        worksheet.write_with_format(
+                            current_row,
+                            current_col,
+                            "Date",
+                            &result_header_fmt,
+                        )?;
+                    }
+                    "result" => {
+                        worksheet.write_with_format(
+                            current_row,
+                            current_col,
+                            "Result",
+                            &result_header_fmt,
+                        )?;
+                        verdict_col = Some(current_col);
+                    }
+                    "retests" => {
+                        worksheet.write_with_format(
+                            current_row,
+                            current_col,
+                            "Retests",
+                            &result_header_fmt,
+                        )?;
+                    }
+                    "measurements" => {
+                        // Add measurement column headers dynamically
+                        for key in &measurement_keys {
+                            worksheet.write_with_format(
+                                current_row,
+                                current_col,
+                                key,
+                                &result_header_fmt,
+                            )?;
+                            measurement_cols.push((key.clone(), current_col));
+                            current_col += 1;
+                        }
+                        continue;
+                    }
+                    _ => continue,
                 }
+                current_col += 1;
             }
             current_row += 1;
 
             // Results data
+            let first_data_row = current_row;
             for result in &test_data.results {
                 let result_fmt = if result.result.to_uppercase().contains("PASS") {
                     &pass_fmt
@@ -156,73 +319,129 @@ pub fn generate_report(data: &ReportData) -> Result<Vec<u8>, XlsxError> {
 
                 let mut current_col = col;
 
-                if data.is_serialized {
-                    worksheet.write_with_format(
-                        current_row,
-                        current_col,
-                        result.serial_number.as_deref().unwrap_or(""),
-                        result_fmt,
-                    )?;
-                    current_col += 1;
-                }
-
-                worksheet.write_with_format(current_row, current_col, &result.batch, result_fmt)?;
-                current_col += 1;
-
-                worksheet.write_with_format(current_row, current_col, &result.date, result_fmt)?;
-                current_col += 1;
-
-                worksheet.write_with_format(
-                    current_row,
-                    current_col,
-                    &result.result,
-                    result_fmt,
-                )?;
-                current_col += 1;
-
-                // Write measurement values
-                if let Some(obj) = result.measurements.as_object() {
-                    for key in &measurement_keys {
-                        if let Some(value) = obj.get(key) {
-                            let value_str = match value {
-                                serde_json::Value::Number(n) => n.to_string(),
-                                serde_json::Value::String(s) => s.clone(),
-                                serde_json::Value::Bool(b) => b.to_string(),
-                                _ => value.to_string(),
-                            };
+                for column in &result_columns {
+                    match *column {
+                        "serial" => {
+                            worksheet.write_with_format(
+                                current_row,
+                                current_col,
+                                result.serial_number.as_deref().unwrap_or(""),
+                                result_fmt,
+                            )?;
+                        }
+                        "batch" => {
                             worksheet.write_with_format(
                                 current_row,
                                 current_col,
-                                &value_str,
+                                &result.batch,
                                 result_fmt,
                             )?;
-                        } else {
+                        }
+                        "date" => {
+                            worksheet.write_with_format(
+                                current_row,
+                                current_col,
+                                &result.date,
+                                result_fmt,
+                            )?;
+                        }
+                        "result" => {
                             worksheet.write_with_format(
                                 current_row,
                                 current_col,
-                                "",
+                                &result.result,
                                 result_fmt,
                             )?;
                         }
-                        current_col += 1;
+                        "retests" => {
+                            worksheet.write_with_format(
+                                current_row,
+                                current_col,
+                                result.retests,
+                                result_fmt,
+                            )?;
+                        }
+                        "measurements" => {
+                            // Write measurement values
+                            let obj = result.measurements.as_object();
+                            for key in &measurement_keys {
+                                if let Some(value) = obj.and_then(|o| o.get(key)) {
+                                    // Readings stay numeric so they can be audited
+                                    if let Some(number) = numeric_value(value) {
+                                        write_number(
+                                            worksheet,
+                                            (current_row, current_col),
+                                            number,
+                                            &template.numbers,
+                                            result_fmt,
+                                        )?;
+                                    } else if let serde_json::Value::String(s) = value {
+                                        worksheet.write_with_format(
+                                            current_row,
+                                            current_col,
+                                            s,
+                                            result_fmt,
+                                        )?;
+                                    } else {
+                                        worksheet.write_with_format(
+                                            current_row,
+                                            current_col,
+                                            value.to_string(),
+                                            result_fmt,
+                                        )?;
+                                    }
+                                } else {
+                                    worksheet.write_with_format(
+                                        current_row,
+                                        current_col,
+                                        "",
+                                        result_fmt,
+                                    )?;
+                                }
+                                current_col += 1;
+                            }
+                            continue;
+                        }
+                        _ => continue,
                     }
+                    current_col += 1;
                 }
 
                 current_row += 1;
             }
+
+            // Statistics block under the results
+            if template.statistics.enabled {
+                let data_rows = (first_data_row, current_row - 1);
+                current_row = write_statistics(
+                    worksheet,
+                    test_data,
+                    template,
+                    (current_row + 1, col),
+                    data_rows,
+                    verdict_col,
+                    &measurement_cols,
+                )?;
+            }
         }
 
         // Spacing between tests
         current_row += template.rows_between_tests;
     }
 
-    // Auto-fit columns
-    worksheet.set_column_width(col, 15)?;
-    worksheet.set_column_width(col + 1, 12)?;
-    worksheet.set_column_width(col + 2, 12)?;
-    worksheet.set_column_width(col + 3, 12)?;
+    // Sign-off
+    write_signature_block(
+        worksheet,
+        &template.signature_block,
+        style,
+        current_row,
+        col,
+    )?;
+
+    // Column widths
+    for (idx, width) in template.column_widths.iter().enumerate() {
+        worksheet.set_column_width(col + idx as u16, *width)?;
+    }
 
-    // Save to buffer
-    let buffer = workbook.save_to_buffer()?;
-    Ok(buffer)
+    Ok(())
 }
@@ -0,0 +1,511 @@
+use crate::reports::collector::run_scope;
+use crate::reports::excel::{self, BaReportRequest};
+use crate::reports::snapshot::ReportParameters;
+use crate::reports::{excel_report, pdf_report, write_report_file};
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+
+// ============================================================================
+// Report Jobs
+// ============================================================================
+//
+// Reports can be generated in the background instead of blocking the command
+// that asked for them. Submitted requests are queued and run at most
+// MAX_RUNNING_JOBS at a time; every change of phase or status is emitted as a
+// JOB_EVENT carrying the job's info. Finished reports stay in memory until
+// the job is removed, and can be fetched or saved by job id. Only the
+// MAX_KEPT_REPORTS most recently completed reports are kept; older ones are
+// discarded and have to be generated again.
+//
+// Cancelling a queued job drops it before it starts. A running job stops at
+// its next checkpoint (the start of a phase); a job past its last checkpoint
+// completes.
+// ============================================================================
+
+/// Event emitted with a job's `ReportJobInfo` whenever it changes
+pub const JOB_EVENT: &str = "report-job-progress";
+
+/// Jobs generating at the same time; the rest wait in the queue
+pub const MAX_RUNNING_JOBS: usize = 2;
+
+/// Completed reports kept in memory; older ones are discarded
+pub const MAX_KEPT_REPORTS: usize = 10;
+
+/// Phase of a running job
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobPhase {
+    Collecting, // Loading test data and the template
+    Rendering,  // Building the workbook or PDF
+    Recording,  // Storing the report snapshot
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn is_finished(self) -> bool {
+        matches!(
+            self,
+            JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled
+        )
+    }
+}
+
+/// Called by report generation as each phase starts
+/// Returning an error stops generation
+pub type Progress<'a> = &'a (dyn Fn(JobPhase) -> Result<(), String> + Send + Sync);
+
+/// Progress for reports generated directly by a command
+pub fn no_progress(_phase: JobPhase) -> Result<(), String> {
+    Ok(())
+}
+
+/// A report to generate in the background
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", content = "request", rename_all = "snake_case")]
+pub enum ReportJobRequest {
+    Excel(ReportParameters),
+    Pdf(ReportParameters),
+    Ba(BaReportRequest),
+}
+
+impl ReportJobRequest {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ReportJobRequest::Excel(_) => "excel",
+            ReportJobRequest::Pdf(_) => "pdf",
+            ReportJobRequest::Ba(_) => "ba",
+        }
+    }
+
+    /// File extension of the generated report
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportJobRequest::Pdf(_) => "pdf",
+            _ => "xlsx",
+        }
+    }
+
+    /// Short description for job lists
+    pub fn label(&self) -> String {
+        match self {
+            ReportJobRequest::Excel(params) | ReportJobRequest::Pdf(params) => {
+                match run_scope(params.batch.as_deref(), params.serial_range.as_deref())
+                    .ok()
+                    .flatten()
+                {
+                    Some(scope) => format!("Report {} ({})", params.report_id, scope),
+                    None => format!("Report {}", params.report_id),
+                }
+            }
+            ReportJobRequest::Ba(request) => {
+                format!(
+                    "BA {} rev {} ({})",
+                    request.fg, request.rev, request.serials
+                )
+            }
+        }
+    }
+}
+
+/// Job state as listed and emitted
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportJobInfo {
+    pub id: String,
+    pub kind: String,
+    pub label: String,
+    pub extension: String,
+    pub status: JobStatus,
+    pub phase: Option<JobPhase>, // Set while running
+    pub error: Option<String>,
+    pub size: Option<usize>, // Bytes of the finished report
+    pub submitted_at: String,
+    pub finished_at: Option<String>,
+}
+
+struct Job {
+    info: ReportJobInfo,
+    cancelled: Arc<AtomicBool>,
+    artifact: Option<Vec<u8>>,
+    completed: u64, // Order of completion, for discarding the oldest reports
+}
+
+/// Submitted jobs, oldest first, and the slots they run in
+#[derive(Clone)]
+pub struct ReportJobs {
+    jobs: Arc<Mutex<Vec<Job>>>,
+    slots: Arc<Semaphore>,
+}
+
+impl Default for ReportJobs {
+    fn default() -> Self {
+        ReportJobs {
+            jobs: Arc::new(Mutex::new(Vec::new())),
+            slots: Arc::new(Semaphore::new(MAX_RUNNING_JOBS)),
+        }
+    }
+}
+
+fn now() -> String {
+    chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+impl ReportJobs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Job>> {
+        // A panic while holding the lock leaves the list itself intact
+        self.jobs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Queue a job and return its id and cancellation flag
+    fn queue(&self, request: &ReportJobRequest) -> (String, Arc<AtomicBool>) {
+        let id = uuid::Uuid::new_v4().to_string();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.lock().push(Job {
+            info: ReportJobInfo {
+                id: id.clone(),
+                kind: request.kind().to_string(),
+                label: request.label(),
+                extension: request.extension().to_string(),
+                status: JobStatus::Queued,
+                phase: None,
+                error: None,
+                size: None,
+                submitted_at: now(),
+                finished_at: None,
+            },
+            cancelled: cancelled.clone(),
+            artifact: None,
+            completed: 0,
+        });
+        (id, cancelled)
+    }
+
+    /// Apply a change to a job and return its updated info
+    fn update(&self, id: &str, change: impl FnOnce(&mut Job)) -> Option<ReportJobInfo> {
+        let mut jobs = self.lock();
+        let job = jobs.iter_mut().find(|job| job.info.id == id)?;
+        change(job);
+        Some(job.info.clone())
+    }
+
+    pub fn list(&self) -> Vec<ReportJobInfo> {
+        self.lock().iter().map(|job| job.info.clone()).collect()
+    }
+
+    pub fn info(&self, id: &str) -> Option<ReportJobInfo> {
+        self.lock()
+            .iter()
+            .find(|job| job.info.id == id)
+            .map(|job| job.info.clone())
+    }
+
+    /// Bytes of a completed job
+    pub fn artifact(&self, id: &str) -> Result<Vec<u8>, String> {
+        let jobs = self.lock();
+        let job = jobs
+            .iter()
+            .find(|job| job.info.id == id)
+            .ok_or_else(|| "Report job not found".to_string())?;
+        job.artifact.clone().ok_or_else(|| match job.info.status {
+            JobStatus::Failed => format!(
+                "Report job failed: {}",
+                job.info.error.as_deref().unwrap_or("unknown error")
+            ),
+            JobStatus::Cancelled => "Report job was cancelled".to_string(),
+            JobStatus::Completed => {
+                "The report was discarded to free memory; generate it again".to_string()
+            }
+            _ => "Report job has not finished".to_string(),
+        })
+    }
+
+    /// Flag a job for cancellation; a queued job is cancelled at once
+    pub fn cancel(&self, id: &str) -> Result<ReportJobInfo, String> {
+        self.update(id, |job| {
+            if job.info.status.is_finished() {
+                return;
+            }
+            job.cancelled.store(true, Ordering::SeqCst);
+            if job.info.status == JobStatus::Queued {
+                job.info.status = JobStatus::Cancelled;
+                job.info.finished_at = Some(now());
+            }
+        })
+        .ok_or_else(|| "Report job not found".to_string())
+    }
+
+    /// Forget a finished job and its report
+    pub fn remove(&self, id: &str) -> Result<(), String> {
+        let mut jobs = self.lock();
+        let index = jobs
+            .iter()
+            .position(|job| job.info.id == id)
+            .ok_or_else(|| "Report job not found".to_string())?;
+        if !jobs[index].info.status.is_finished() {
+            return Err("Cancel the report job before removing it".to_string());
+        }
+        jobs.remove(index);
+        Ok(())
+    }
+
+    /// Record the outcome of a job that was started
+    fn finish(
+        &self,
+        id: &str,
+        result: Result<Vec<u8>, String>,
+        cancelled: bool,
+    ) -> Option<ReportJobInfo> {
+        let mut jobs = self.lock();
+        let order = jobs.iter().map(|job| job.completed).max().unwrap_or(0) + 1;
+        let job = jobs.iter_mut().find(|job| job.info.id == id)?;
+        job.info.phase = None;
+        job.info.finished_at = Some(now());
+        match result {
+            Ok(bytes) => {
+                job.info.status = JobStatus::Completed;
+                job.info.size = Some(bytes.len());
+                job.artifact = Some(bytes);
+                job.completed = order;
+            }
+            Err(_) if cancelled => job.info.status = JobStatus::Cancelled,
+            Err(e) => {
+                job.info.status = JobStatus::Failed;
+                job.info.error = Some(e);
+            }
+        }
+        let info = job.info.clone();
+
+        discard_old_reports(&mut jobs);
+        Some(info)
+    }
+}
+
+/// Drop the reports of all but the most recently completed jobs
+fn discard_old_reports(jobs: &mut [Job]) {
+    let mut kept: Vec<&mut Job> = jobs
+        .iter_mut()
+        .filter(|job| job.artifact.is_some())
+        .collect();
+    if kept.len() <= MAX_KEPT_REPORTS {
+        return;
+    }
+    kept.sort_by_key(|job| job.completed);
+    let excess = kept.len() - MAX_KEPT_REPORTS;
+    for job in kept.into_iter().take(excess) {
+        job.artifact = None;
+    }
+}
+
+fn emit_info(app: &AppHandle, info: Option<ReportJobInfo>) {
+    if let Some(info) = info {
+        let _ = app.emit(JOB_EVENT, info);
+    }
+}
+
+/// Generate a report with the given progress callback
+pub(crate) async fn generate(
+    request: &ReportJobRequest,
+    state: &AppState,
+    progress: Progress<'_>,
+) -> Result<Vec<u8>, String> {
+    match request {
+        ReportJobRequest::Excel(params) => excel_report(params, state, progress).await,
+        ReportJobRequest::Pdf(params) => pdf_report(params, state, progress).await,
+        ReportJobRequest::Ba(request) => excel::xlsx(state, request, progress)
+            .await
+            .map_err(|e| e.to_string()),
+    }
+}
+
+/// Wait for a slot, then generate the report and store the outcome
+async fn run_job(
+    app: AppHandle,
+    id: String,
+    cancelled: Arc<AtomicBool>,
+    request: ReportJobRequest,
+) {
+    let state = app.state::<AppState>();
+    let jobs = &state.report_jobs;
+
+    let Ok(_permit) = jobs.slots.clone().acquire_owned().await else {
+        return;
+    };
+    // Cancelled while queued
+    if cancelled.load(Ordering::SeqCst) {
+        return;
+    }
+
+    let progress = |phase: JobPhase| {
+        if cancelled.load(Ordering::SeqCst) {
+            return Err("Report job cancelled".to_string());
+        }
+        emit_info(
+            &app,
+            jobs.update(&id, |job| {
+                job.info.status = JobStatus::Running;
+                job.info.phase = Some(phase);
+            }),
+        );
+        Ok(())
+    };
+
+    let result = generate(&request, &state, &progress).await;
+    let info = jobs.finish(&id, result, cancelled.load(Ordering::SeqCst));
+    emit_info(&app, info);
+}
+
+/// Queue a report and start its job; the handle resolves once the job has finished
+pub(crate) fn submit(
+    app: &AppHandle,
+    jobs: &ReportJobs,
+    request: ReportJobRequest,
+) -> (String, JoinHandle<()>) {
+    let (id, cancelled) = jobs.queue(&request);
+    emit_info(app, jobs.info(&id));
+    let handle = tokio::spawn(run_job(app.clone(), id.clone(), cancelled, request));
+    (id, handle)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Queue reports for background generation and return their job ids
+/// Progress is emitted as `report-job-progress` events
+#[tauri::command]
+pub async fn submit_report_jobs(
+    app: AppHandle,
+    requests: Vec<ReportJobRequest>,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    if requests.is_empty() {
+        return Err("No reports to generate".to_string());
+    }
+
+    Ok(requests
+        .into_iter()
+        .map(|request| submit(&app, &state.report_jobs, request).0)
+        .collect())
+}
+
+/// List report jobs, oldest first
+#[tauri::command]
+pub async fn list_report_jobs(state: State<'_, AppState>) -> Result<Vec<ReportJobInfo>, String> {
+    Ok(state.report_jobs.list())
+}
+
+/// Cancel a queued or running report job
+#[tauri::command]
+pub async fn cancel_report_job(
+    app: AppHandle,
+    job_id: String,
+    state: State<'_, AppState>,
+) -> Result<ReportJobInfo, String> {
+    let info = state.report_jobs.cancel(&job_id)?;
+    emit_info(&app, Some(info.clone()));
+    Ok(info)
+}
+
+/// Get the report generated by a completed job
+#[tauri::command]
+pub async fn get_report_job_artifact(
+    job_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<u8>, String> {
+    state.report_jobs.artifact(&job_id)
+}
+
+/// Save the report generated by a completed job to a file path
+#[tauri::command]
+pub async fn save_report_job_artifact(
+    job_id: String,
+    file_path: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let buffer = state.report_jobs.artifact(&job_id)?;
+    write_report_file(&file_path, buffer)
+}
+
+/// Remove a finished job and free its report
+#[tauri::command]
+pub async fn remove_report_job(job_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.report_jobs.remove(&job_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn excel_request() -> ReportJobRequest {
+        serde_json::from_str(r#"{"kind":"excel","request":{"report_id":3,"batch":"B7","serial_range":null,"selected_dates":null,"template_id":null}}"#)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_queued_job_cancels_and_removes() {
+        let jobs = ReportJobs::new();
+        let request = excel_request();
+        assert_eq!(request.label(), "Report 3 (B7)");
+
+        let (id, cancelled) = jobs.queue(&request);
+        assert_eq!(jobs.info(&id).unwrap().status, JobStatus::Queued);
+        assert!(jobs.remove(&id).is_err());
+        assert!(jobs.artifact(&id).is_err());
+
+        assert_eq!(jobs.cancel(&id).unwrap().status, JobStatus::Cancelled);
+        assert!(cancelled.load(Ordering::SeqCst));
+        jobs.remove(&id).unwrap();
+        assert!(jobs.list().is_empty());
+    }
+
+    #[test]
+    fn test_finished_job_keeps_artifact() {
+        let jobs = ReportJobs::new();
+        let (done, _) = jobs.queue(&excel_request());
+        let (failed, _) = jobs.queue(&excel_request());
+
+        jobs.finish(&done, Ok(vec![1, 2, 3]), false);
+        jobs.finish(&failed, Err("No data".to_string()), false);
+
+        assert_eq!(jobs.artifact(&done).unwrap(), vec![1, 2, 3]);
+        assert_eq!(jobs.info(&done).unwrap().size, Some(3));
+        assert!(jobs.artifact(&failed).unwrap_err().contains("No data"));
+        // Cancelling a finished job changes nothing
+        assert_eq!(jobs.cancel(&done).unwrap().status, JobStatus::Completed);
+    }
+
+    #[test]
+    fn test_only_recent_reports_are_kept() {
+        let jobs = ReportJobs::new();
+        let ids: Vec<String> = (0..MAX_KEPT_REPORTS + 2)
+            .map(|_| jobs.queue(&excel_request()).0)
+            .collect();
+        // The second job finishes last, so it is kept over the first two to complete
+        for id in ids.iter().skip(2).chain(&ids[..2]) {
+            jobs.finish(id, Ok(vec![1]), false);
+        }
+
+        assert!(jobs.artifact(&ids[2]).unwrap_err().contains("discarded"));
+        assert!(jobs.artifact(&ids[3]).is_err());
+        assert!(jobs.artifact(&ids[4]).is_ok());
+        assert!(jobs.artifact(&ids[1]).is_ok());
+        assert_eq!(jobs.info(&ids[2]).unwrap().status, JobStatus::Completed);
+    }
+}
@@ -1,11 +1,25 @@
+pub mod bulk;
+pub mod coc;
 pub mod collector;
 pub mod debug;
 pub mod excel_generator;
 pub mod excel;
+pub mod jobs;
+pub mod other_results;
+pub mod paging;
+pub mod pdf;
+pub mod reconcile;
+pub mod retest;
 pub mod template;
+pub mod template_store;
 pub mod validator;
+pub mod workbook;
 pub mod serialized;
+pub mod sessions;
+pub mod snapshot;
+pub mod statistics;
 
+use crate::reports::jobs::{no_progress, JobPhase, Progress};
 use crate::AppState;
 use std::fs;
 use std::path::Path;
@@ -16,21 +30,15 @@ pub use collector::collect_report;
 pub use debug::debug_voltech_query;
 pub use validator::validate_report;
 
-/// Generate Excel report from collected data
-#[tauri::command]
-pub async fn generate_excel_report(
-    report_id: i32,
-    batch: Option<String>,
-    serial_range: Option<String>,
-    selected_dates: Option<Vec<String>>,
-    state: State<'_, AppState>,
-) -> Result<Vec<u8>, String> {
+/// Collect report data and load the template to render it with
+async fn collect_with_template(
+    params: &snapshot::ReportParameters,
+    state: &AppState,
+) -> Result<(collector::ReportData, template::ReportTemplate), String> {
     // Collect report data
     let report_data = match collector::collect_report_data(
-        report_id,
-        batch,
-        serial_range,
-        selected_dates,
+        params.report_id,
+        params.selection(),
         &state.core_db,
         &state.voltech_db,
         &state.manual_db,
@@ -41,38 +49,171 @@ pub async fn generate_excel_report(
         Err(e) => return Err(format!("Failed to collect report data: {}", e)),
     };
 
-    // Generate Excel file
-    match excel_generator::generate_report(&report_data) {
-        Ok(buffer) => Ok(buffer),
-        Err(e) => Err(format!("Failed to generate Excel report: {}", e)),
+    // Use the selected template, or the one configured for this FG/customer
+    let template = template_store::select_template(
+        &state.core_db,
+        params.template_id,
+        Some(report_data.fg_id),
+        Some(&report_data.fg_customer),
+    )
+    .await
+    .map_err(|e| format!("Failed to load report template: {}", e))?;
+
+    Ok((report_data, template))
+}
+
+/// Write a generated report to a file path
+pub(crate) fn write_report_file(file_path: &str, buffer: Vec<u8>) -> Result<(), String> {
+    // Write to file using Rust std::fs
+    let path = Path::new(file_path);
+
+    // Create parent directories if they don't exist
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directories: {}", e))?;
     }
+
+    // Write the file
+    fs::write(path, buffer)
+        .map_err(|e| format!("Failed to write file: {}", e))
+}
+
+/// Build an Excel report, reporting each phase as it starts
+pub(crate) async fn excel_report(
+    request: &snapshot::ReportParameters,
+    state: &AppState,
+    progress: Progress<'_>,
+) -> Result<Vec<u8>, String> {
+    progress(JobPhase::Collecting)?;
+    let (report_data, template) = collect_with_template(request, state).await?;
+    let spec_rows = snapshot::load_spec_rows(&state.core_db, request.report_id).await?;
+
+    let raw = if request.sheets.needs_raw_records() {
+        Some(
+            collector::collect_raw_records(
+                request.report_id,
+                &request.selection(),
+                &state.core_db,
+                &state.voltech_db,
+                &state.manual_db,
+            )
+            .await
+            .map_err(|e| format!("Failed to collect raw test data: {}", e))?,
+        )
+    } else {
+        None
+    };
+    let empty = collector::RawRecords::default();
+
+    // Generate Excel file
+    progress(JobPhase::Rendering)?;
+    let extras = workbook::WorkbookExtras {
+        sheets: &request.sheets,
+        raw: raw.as_ref().unwrap_or(&empty),
+        spec_rows: &spec_rows,
+    };
+    let buffer = match workbook::generate_workbook(&report_data, &template, &extras) {
+        Ok(buffer) => buffer,
+        Err(e) => return Err(format!("Failed to generate Excel report: {}", e)),
+    };
+
+    // Keep exactly what was generated
+    progress(JobPhase::Recording)?;
+    snapshot::record_snapshot(
+        &state.core_db,
+        snapshot::SnapshotInput {
+            format: snapshot::FORMAT_XLSX,
+            parameters: request,
+            data: &report_data,
+            template: &template,
+            spec_rows: &spec_rows,
+            raw: raw.as_ref(),
+            bytes: &buffer,
+        },
+    )
+    .await?;
+
+    Ok(buffer)
+}
+
+/// Generate Excel report from collected data
+/// `request.sheets` adds raw data, traceability and spec sheets to the workbook
+#[tauri::command]
+pub async fn generate_excel_report(
+    request: snapshot::ReportParameters,
+    state: State<'_, AppState>,
+) -> Result<Vec<u8>, String> {
+    excel_report(&request, &state, &no_progress).await
 }
 
 /// Generate Excel report and save to specified file path
 #[tauri::command]
 pub async fn save_excel_report(
-    report_id: i32,
+    request: snapshot::ReportParameters,
     file_path: String,
-    batch: Option<String>,
-    serial_range: Option<String>,
-    selected_dates: Option<Vec<String>>,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
     // Generate the Excel file buffer
-    let buffer = generate_excel_report(report_id, batch, serial_range, selected_dates, state).await?;
+    let buffer = generate_excel_report(request, state).await?;
 
-    // Write to file using Rust std::fs
-    let path = Path::new(&file_path);
-    
-    // Create parent directories if they don't exist
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create directories: {}", e))?;
-    }
-    
-    // Write the file
-    fs::write(path, buffer)
-        .map_err(|e| format!("Failed to write file: {}", e))?;
+    write_report_file(&file_path, buffer)
+}
+
+/// Build a PDF report, reporting each phase as it starts
+/// `request.sheets` does not apply to PDFs
+pub(crate) async fn pdf_report(
+    request: &snapshot::ReportParameters,
+    state: &AppState,
+    progress: Progress<'_>,
+) -> Result<Vec<u8>, String> {
+    let params = snapshot::ReportParameters {
+        sheets: Default::default(),
+        ..request.clone()
+    };
+    progress(JobPhase::Collecting)?;
+    let (report_data, template) = collect_with_template(&params, state).await?;
+    let spec_rows = snapshot::load_spec_rows(&state.core_db, params.report_id).await?;
+
+    progress(JobPhase::Rendering)?;
+    let buffer = pdf::generate_report(&report_data, &template)
+        .map_err(|e| format!("Failed to generate PDF report: {}", e))?;
+
+    progress(JobPhase::Recording)?;
+    snapshot::record_snapshot(
+        &state.core_db,
+        snapshot::SnapshotInput {
+            format: snapshot::FORMAT_PDF,
+            parameters: &params,
+            data: &report_data,
+            template: &template,
+            spec_rows: &spec_rows,
+            raw: None,
+            bytes: &buffer,
+        },
+    )
+    .await?;
+
+    Ok(buffer)
+}
+
+/// Generate PDF report from collected data
+/// `request.sheets` does not apply to PDFs
+#[tauri::command]
+pub async fn generate_pdf_report(
+    request: snapshot::ReportParameters,
+    state: State<'_, AppState>,
+) -> Result<Vec<u8>, String> {
+    pdf_report(&request, &state, &no_progress).await
+}
+
+/// Generate PDF report and save to specified file path
+#[tauri::command]
+pub async fn save_pdf_report(
+    request: snapshot::ReportParameters,
+    file_path: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let buffer = generate_pdf_report(request, state).await?;
 
-    Ok(())
+    write_report_file(&file_path, buffer)
 }
@@ -0,0 +1,285 @@
+use crate::reports::collector::run_scope;
+use crate::serials::SerialSpec;
+use crate::voltech::commands::{check_admin_permission, get_current_username};
+use crate::AppState;
+use ::entity::test as test_entity;
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+// ============================================================================
+// "Other" Test Outcomes
+// ============================================================================
+//
+// Tests with source type "other" have no tester data. Their outcomes (a value
+// or a pass/fail attestation, who recorded it and when, and an optional
+// reference to a supporting document) are recorded by hand per report run,
+// keyed by the run's serial range or batch like CoC overrides. Validation
+// and report generation read them for that run.
+//
+// `other_test_results` is not part of the generated entities, so it is read
+// and written with SQL.
+// ============================================================================
+
+/// A recorded outcome
+#[derive(Debug, Clone, Serialize, Deserialize, FromQueryResult)]
+pub struct OtherResult {
+    pub id: i32,
+    pub report_id: i32,
+    pub test_id: i32,
+    pub scope: String,                 // Serial range or batch of the run
+    pub serial_number: Option<String>, // None when the outcome covers the whole run
+    pub value: Option<f64>,            // In the test's unit
+    pub result: String,                // "PASS" or "FAIL"
+    pub notes: Option<String>,
+    pub attachment: Option<String>, // Path or document reference
+    pub recorded_by: String,
+    pub recorded_at: String,
+}
+
+/// An outcome to record
+#[derive(Debug, Clone, Deserialize)]
+pub struct OtherResultData {
+    pub test_id: i32,
+    pub batch: Option<String>,
+    pub serial_range: Option<String>,
+    pub serial_number: Option<String>,
+    pub value: Option<f64>,
+    /// "PASS" or "FAIL"; judged from the value and the test's limits when omitted
+    pub result: Option<String>,
+    pub notes: Option<String>,
+    pub attachment: Option<String>,
+}
+
+/// Decide the stored verdict from an explicit result, or the value against limits
+fn verdict(
+    value: Option<f64>,
+    result: Option<&str>,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+) -> Result<String, String> {
+    if let Some(result) = result.map(str::trim).filter(|r| !r.is_empty()) {
+        return match result.to_ascii_uppercase().as_str() {
+            "PASS" => Ok("PASS".to_string()),
+            "FAIL" => Ok("FAIL".to_string()),
+            _ => Err(format!("Unknown result '{}': use PASS or FAIL", result)),
+        };
+    }
+
+    let Some(value) = value else {
+        return Err("A value or a pass/fail result is required".to_string());
+    };
+    if minimum.is_none() && maximum.is_none() {
+        return Err("The test has no limits: a pass/fail result is required".to_string());
+    }
+
+    let in_spec =
+        !minimum.is_some_and(|min| value < min) && !maximum.is_some_and(|max| value > max);
+    Ok(if in_spec { "PASS" } else { "FAIL" }.to_string())
+}
+
+fn trimmed(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+const SELECT_RESULTS: &str = r#"
+    SELECT id, report_id, test_id, scope, serial_number, value, result, notes,
+           attachment, recorded_by, CAST(recorded_at AS TEXT) as recorded_at
+    FROM other_test_results
+"#;
+
+/// Outcomes recorded for a report run, oldest first
+/// A `scope` of `None` returns the outcomes of every run
+pub async fn run_results(
+    db: &DbConn,
+    report_id: i32,
+    scope: Option<&str>,
+) -> Result<Vec<OtherResult>, DbErr> {
+    let statement = match scope {
+        Some(scope) => Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            format!(
+                "{} WHERE report_id = ? AND scope = ? ORDER BY recorded_at, id",
+                SELECT_RESULTS
+            ),
+            [report_id.into(), scope.into()],
+        ),
+        None => Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            format!(
+                "{} WHERE report_id = ? ORDER BY recorded_at, id",
+                SELECT_RESULTS
+            ),
+            [report_id.into()],
+        ),
+    };
+    OtherResult::find_by_statement(statement).all(db).await
+}
+
+/// Outcomes of a test that decide one serial's result, oldest first: those
+/// recorded for the serial, or those recorded for the whole run when none
+/// name it
+pub fn serial_outcomes<'r>(
+    outcomes: &'r [OtherResult],
+    test_id: i32,
+    sn: &str,
+    serials: &SerialSpec,
+) -> Vec<&'r OtherResult> {
+    let recorded = |serial: Option<&str>| -> Vec<&'r OtherResult> {
+        outcomes
+            .iter()
+            .filter(|r| r.test_id == test_id)
+            .filter(|r| {
+                let named = r.serial_number.as_deref().map(str::trim);
+                match named.filter(|s| !s.is_empty()) {
+                    Some(named) => {
+                        serial.is_some_and(|sn| serials.label(named).as_deref() == Some(sn))
+                    }
+                    None => serial.is_none(),
+                }
+            })
+            .collect()
+    };
+    let attempts = recorded(Some(sn));
+    if attempts.is_empty() {
+        recorded(None)
+    } else {
+        attempts
+    }
+}
+
+async fn find_result(db: &DbConn, id: i32) -> Result<Option<OtherResult>, DbErr> {
+    OtherResult::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        format!("{} WHERE id = ?", SELECT_RESULTS),
+        [id.into()],
+    ))
+    .one(db)
+    .await
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Get the outcomes recorded for a report run
+#[tauri::command]
+pub async fn get_other_results(
+    report_id: i32,
+    batch: Option<String>,
+    serial_range: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<OtherResult>, String> {
+    let scope = run_scope(batch.as_deref(), serial_range.as_deref())?;
+    run_results(&state.core_db, report_id, scope.as_deref())
+        .await
+        .map_err(|e| format!("Failed to get other test results: {}", e))
+}
+
+/// Record the outcome of an "other" test for a report run
+#[tauri::command]
+pub async fn record_other_result(
+    result: OtherResultData,
+    state: State<'_, AppState>,
+) -> Result<OtherResult, String> {
+    let username = get_current_username()?;
+    let db = &*state.core_db;
+
+    let test = test_entity::Entity::find_by_id(result.test_id)
+        .one(db)
+        .await
+        .map_err(|e| format!("Failed to load test: {}", e))?
+        .ok_or_else(|| "Test not found".to_string())?;
+    if test.source_type != "other" {
+        return Err("Outcomes can only be recorded for tests with source type 'other'".to_string());
+    }
+    let report_id = test
+        .report_id
+        .ok_or_else(|| "The test is not part of a report".to_string())?;
+
+    let scope = run_scope(result.batch.as_deref(), result.serial_range.as_deref())?
+        .ok_or_else(|| "A serial range or batch is required".to_string())?;
+    let verdict = verdict(
+        result.value,
+        result.result.as_deref(),
+        test.minimum,
+        test.maximum,
+    )?;
+
+    let inserted = db
+        .execute_raw(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            r#"
+            INSERT INTO other_test_results
+                (report_id, test_id, scope, serial_number, value, result, notes, attachment, recorded_by)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            [
+                report_id.into(),
+                test.id.into(),
+                scope.into(),
+                trimmed(result.serial_number).into(),
+                result.value.into(),
+                verdict.into(),
+                trimmed(result.notes).into(),
+                trimmed(result.attachment).into(),
+                username.into(),
+            ],
+        ))
+        .await
+        .map_err(|e| format!("Failed to record other test result: {}", e))?;
+
+    find_result(db, inserted.last_insert_id() as i32)
+        .await
+        .map_err(|e| format!("Failed to load other test result: {}", e))?
+        .ok_or_else(|| "Failed to load other test result".to_string())
+}
+
+/// Delete a recorded outcome; allowed for whoever recorded it and for admins
+#[tauri::command]
+pub async fn delete_other_result(id: i32, state: State<'_, AppState>) -> Result<String, String> {
+    let username = get_current_username()?;
+    let db = &*state.core_db;
+
+    let existing = find_result(db, id)
+        .await
+        .map_err(|e| format!("Failed to load other test result: {}", e))?
+        .ok_or_else(|| "Other test result not found".to_string())?;
+    if existing.recorded_by != username && !check_admin_permission(&state, &username).await? {
+        return Err("Only the person who recorded a result or an admin can delete it".to_string());
+    }
+
+    db.execute_raw(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "DELETE FROM other_test_results WHERE id = ?",
+        [id.into()],
+    ))
+    .await
+    .map_err(|e| format!("Failed to delete other test result: {}", e))?;
+
+    Ok("Other test result deleted".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verdict_from_result_or_limits() {
+        assert_eq!(verdict(None, Some("pass"), None, None).unwrap(), "PASS");
+        assert_eq!(
+            verdict(Some(99.0), Some(" Fail "), Some(1.0), None).unwrap(),
+            "FAIL"
+        );
+        assert_eq!(
+            verdict(Some(5.0), None, Some(1.0), Some(10.0)).unwrap(),
+            "PASS"
+        );
+        assert_eq!(verdict(Some(0.5), None, Some(1.0), None).unwrap(), "FAIL");
+        assert!(verdict(Some(5.0), None, None, None).is_err());
+        assert!(verdict(None, None, Some(1.0), Some(10.0)).is_err());
+        assert!(verdict(None, Some("maybe"), None, None).is_err());
+    }
+}
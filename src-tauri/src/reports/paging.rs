@@ -0,0 +1,152 @@
+use rust_xlsxwriter::{Worksheet, XlsxError};
+
+// ============================================================================
+// Printed Page Layout
+// ============================================================================
+//
+// Wide serialized reports are laid out as a grid of printed pages. Test
+// columns are split into page-width column groups, each with its own row label
+// column, placed side by side on the sheet. Serial rows are split into
+// page-height row groups. Page breaks fall on the group boundaries, and the
+// title block above the data is repeated on every printed page.
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageGrid {
+    columns_per_page: u16, // Includes the row label column
+    rows_per_page: u32,
+    column_pages: usize,
+    data_start_row: u32,
+    data_rows: u32,
+}
+
+impl PageGrid {
+    /// `data_columns` test columns and `data_rows` serial rows starting at
+    /// `data_start_row`; rows above it form the repeated title block
+    pub fn new(
+        columns_per_page: u16,
+        rows_per_page: u32,
+        data_columns: usize,
+        data_start_row: u32,
+        data_rows: u32,
+    ) -> Self {
+        let columns_per_page = columns_per_page.max(2);
+        let per_page = (columns_per_page - 1) as usize;
+        Self {
+            columns_per_page,
+            rows_per_page: rows_per_page.max(1),
+            column_pages: data_columns.div_ceil(per_page).max(1),
+            data_start_row,
+            data_rows,
+        }
+    }
+
+    fn columns_per_group(&self) -> usize {
+        (self.columns_per_page - 1) as usize
+    }
+
+    /// Number of side-by-side column groups
+    pub fn column_pages(&self) -> usize {
+        self.column_pages
+    }
+
+    /// Number of page-height row groups
+    pub fn row_pages(&self) -> usize {
+        (self.data_rows.div_ceil(self.rows_per_page) as usize).max(1)
+    }
+
+    /// Total printed pages for the data area
+    pub fn page_count(&self) -> usize {
+        self.column_pages * self.row_pages()
+    }
+
+    /// Row label column of a column group
+    pub fn label_col(&self, page: usize) -> u16 {
+        page as u16 * self.columns_per_page
+    }
+
+    /// Columns of a column group, excluding its label column
+    pub fn data_cols(&self, page: usize) -> std::ops::Range<u16> {
+        let label = self.label_col(page);
+        label + 1..label + self.columns_per_page
+    }
+
+    /// Sheet column of the data column at `index`
+    pub fn column(&self, index: usize) -> u16 {
+        let per_group = self.columns_per_group();
+        self.label_col(index / per_group) + 1 + (index % per_group) as u16
+    }
+
+    /// Last sheet column used by the grid
+    pub fn last_col(&self) -> u16 {
+        self.label_col(self.column_pages) - 1
+    }
+
+    /// First sheet row after the data rows
+    pub fn end_row(&self) -> u32 {
+        self.data_start_row + self.data_rows
+    }
+
+    /// Rows that start a new printed page
+    pub fn row_breaks(&self) -> Vec<u32> {
+        (1..self.row_pages() as u32)
+            .map(|page| self.data_start_row + page * self.rows_per_page)
+            .collect()
+    }
+
+    /// Columns that start a new printed page
+    pub fn column_breaks(&self) -> Vec<u32> {
+        (1..self.column_pages)
+            .map(|page| self.label_col(page) as u32)
+            .collect()
+    }
+
+    /// Print area, title rows and page breaks
+    /// `last_row` is the last row printed, including anything after the data
+    pub fn apply(&self, worksheet: &mut Worksheet, last_row: u32) -> Result<(), XlsxError> {
+        worksheet.set_print_area(0, 0, last_row, self.last_col())?;
+        if self.data_start_row > 0 {
+            worksheet.set_repeat_rows(0, self.data_start_row - 1)?;
+        }
+        worksheet.set_page_breaks(&self.row_breaks())?;
+        worksheet.set_vertical_page_breaks(&self.column_breaks())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_columns_split_into_groups() {
+        // 10 columns per page: a label column and 9 data columns
+        let grid = PageGrid::new(10, 40, 20, 13, 5);
+        assert_eq!(grid.column_pages(), 3);
+        assert_eq!(grid.column(0), 1);
+        assert_eq!(grid.column(8), 9);
+        assert_eq!(grid.column(9), 11);
+        assert_eq!(grid.column(19), 22);
+        assert_eq!(grid.label_col(2), 20);
+        assert_eq!(grid.data_cols(1), 11..20);
+        assert_eq!(grid.last_col(), 29);
+        assert_eq!(grid.column_breaks(), vec![10, 20]);
+    }
+
+    #[test]
+    fn test_rows_split_into_pages() {
+        let grid = PageGrid::new(10, 40, 3, 13, 250);
+        assert_eq!(grid.row_pages(), 7);
+        assert_eq!(grid.page_count(), 7);
+        assert_eq!(grid.row_breaks(), vec![53, 93, 133, 173, 213, 253]);
+        assert_eq!(grid.end_row(), 263);
+
+        // An exact fit needs no extra page
+        let grid = PageGrid::new(10, 40, 3, 13, 80);
+        assert_eq!(grid.row_breaks(), vec![53]);
+
+        let empty = PageGrid::new(10, 40, 0, 13, 0);
+        assert_eq!(empty.page_count(), 1);
+        assert!(empty.row_breaks().is_empty());
+    }
+}
@@ -0,0 +1,932 @@
+use crate::reports::coc::CocSummary;
+use crate::reports::collector::{ReportData, TestResultData};
+use crate::reports::excel_generator::template_values;
+use crate::reports::statistics::numeric_value;
+use crate::reports::template::{color_to_rgb, ReportTemplate, TemplateValues};
+use crate::units::NumberFormat;
+use printpdf::path::PaintMode;
+use printpdf::*;
+
+// ============================================================================
+// PDF Report Rendering
+// ============================================================================
+//
+// Renders the same `ReportData` as `excel_generator` into a PDF. Layout is
+// done first into a list of pages of drawing operations (top-down mm
+// coordinates), so page counts are known before anything is written and the
+// layout can be tested without parsing PDF output.
+// ============================================================================
+
+const MM_PER_INCH: f32 = 25.4;
+const PT_TO_MM: f32 = 0.3528;
+
+// Helvetica averages roughly half an em per character
+const CHAR_WIDTH_EM: f32 = 0.5;
+
+const ROW_HEIGHT: f32 = 6.0;
+const CELL_WIDTH: f32 = 28.0;
+const CELL_PADDING: f32 = 1.5;
+const RUNNING_HEADER_HEIGHT: f32 = 12.0;
+const FOOTER_HEIGHT: f32 = 8.0;
+
+/// Used when the template has no signature block
+const DEFAULT_SIGNATURE_LABELS: [&str; 2] = ["Tested By", "Approved By"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Op {
+    Text {
+        x: f32,
+        y: f32,
+        size: f32,
+        bold: bool,
+        text: String,
+    },
+    Fill {
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        rgb: (u8, u8, u8),
+    },
+    Stroke {
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+    },
+    Rule {
+        x1: f32,
+        x2: f32,
+        y: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CellStyle {
+    Header,
+    Pass,
+    Fail,
+    Plain,
+}
+
+/// Cell values for one table row, styled as a whole
+type TableRow = (Vec<String>, CellStyle);
+
+struct Layout<'a> {
+    template: &'a ReportTemplate,
+    running_header: String,
+    page_width: f32,
+    page_height: f32,
+    left: f32,
+    right: f32,
+    top: f32,
+    bottom: f32,
+    pages: Vec<Vec<Op>>,
+    y: f32,
+}
+
+/// Page width and height in mm
+/// Paper size 9 is A4; everything else is laid out as Letter
+fn page_size(template: &ReportTemplate) -> (f32, f32) {
+    let (short, long) = if template.page.paper_size == 9 {
+        (210.0, 297.0)
+    } else {
+        (215.9, 279.4)
+    };
+
+    if template.page.landscape {
+        (long, short)
+    } else {
+        (short, long)
+    }
+}
+
+impl<'a> Layout<'a> {
+    fn new(template: &'a ReportTemplate, running_header: String) -> Self {
+        let page = &template.page;
+        let (page_width, page_height) = page_size(template);
+
+        let mut layout = Self {
+            template,
+            running_header,
+            page_width,
+            page_height,
+            left: (page.margin_left as f32 * MM_PER_INCH).max(5.0),
+            right: (page.margin_right as f32 * MM_PER_INCH).max(5.0),
+            top: (page.margin_top as f32 * MM_PER_INCH).max(5.0),
+            bottom: (page.margin_bottom as f32 * MM_PER_INCH).max(5.0),
+            pages: Vec::new(),
+            y: 0.0,
+        };
+        layout.new_page();
+        layout
+    }
+
+    fn content_width(&self) -> f32 {
+        self.page_width - self.left - self.right
+    }
+
+    fn content_bottom(&self) -> f32 {
+        self.page_height - self.bottom - FOOTER_HEIGHT
+    }
+
+    fn ops(&mut self) -> &mut Vec<Op> {
+        self.pages.last_mut().expect("layout always has a page")
+    }
+
+    /// Start a new page with the running header repeated at the top
+    fn new_page(&mut self) {
+        self.pages.push(Vec::new());
+        self.y = self.top;
+
+        let style = &self.template.style_config;
+        let (x, y, size) = (self.left, self.y + 5.0, style.heading_font_size as f32);
+        let text = self.running_header.clone();
+        let x2 = self.page_width - self.right;
+        self.ops().push(Op::Text {
+            x,
+            y,
+            size,
+            bold: true,
+            text,
+        });
+        self.ops().push(Op::Rule {
+            x1: x,
+            x2,
+            y: y + 2.0,
+        });
+        self.y += RUNNING_HEADER_HEIGHT;
+    }
+
+    /// Break to a new page unless `height` fits; returns true on a break
+    fn ensure_space(&mut self, height: f32) -> bool {
+        if self.y + height > self.content_bottom() {
+            self.new_page();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn text(&mut self, x: f32, text: &str, size: f32, bold: bool) {
+        let y = self.y + size * PT_TO_MM;
+        self.ops().push(Op::Text {
+            x,
+            y,
+            size,
+            bold,
+            text: text.to_string(),
+        });
+    }
+
+    fn cell(&mut self, x: f32, text: &str, style: CellStyle) {
+        let template_style = &self.template.style_config;
+        let size = template_style.body_font_size as f32;
+        let fill = match style {
+            CellStyle::Header => Some(color_to_rgb(&template_style.result_header_color)),
+            CellStyle::Pass => Some(color_to_rgb(&template_style.pass_color)),
+            CellStyle::Fail => Some(color_to_rgb(&template_style.fail_color)),
+            CellStyle::Plain => None,
+        };
+        let y = self.y;
+
+        if let Some(rgb) = fill {
+            self.ops().push(Op::Fill {
+                x,
+                y,
+                w: CELL_WIDTH,
+                h: ROW_HEIGHT,
+                rgb,
+            });
+        }
+        self.ops().push(Op::Stroke {
+            x,
+            y,
+            w: CELL_WIDTH,
+            h: ROW_HEIGHT,
+        });
+        self.ops().push(Op::Text {
+            x: x + CELL_PADDING,
+            y: y + ROW_HEIGHT - CELL_PADDING,
+            size,
+            bold: matches!(style, CellStyle::Header),
+            text: fit_text(text, CELL_WIDTH - 2.0 * CELL_PADDING, size),
+        });
+    }
+
+    /// Draw a table, splitting wide tables into column groups that fit the page
+    /// The first `key_columns` columns are repeated in every group, and the
+    /// header row is repeated after every page break
+    fn table(&mut self, headers: &[String], rows: &[TableRow], key_columns: usize) {
+        let key_columns = key_columns.min(headers.len());
+        let per_group = ((self.content_width() / CELL_WIDTH) as usize)
+            .saturating_sub(key_columns)
+            .max(1);
+        let data_columns: Vec<usize> = (key_columns..headers.len()).collect();
+
+        let groups: Vec<Vec<usize>> = if data_columns.is_empty() {
+            vec![(0..key_columns).collect()]
+        } else {
+            data_columns
+                .chunks(per_group)
+                .map(|chunk| (0..key_columns).chain(chunk.iter().copied()).collect())
+                .collect()
+        };
+
+        for (group_index, columns) in groups.iter().enumerate() {
+            if group_index > 0 {
+                self.y += ROW_HEIGHT / 2.0;
+            }
+
+            // Keep the header together with at least one row
+            self.ensure_space(ROW_HEIGHT * 2.0);
+            self.table_header(headers, columns);
+
+            for (values, style) in rows {
+                if self.ensure_space(ROW_HEIGHT) {
+                    self.table_header(headers, columns);
+                }
+                for (position, &column) in columns.iter().enumerate() {
+                    let x = self.left + position as f32 * CELL_WIDTH;
+                    let value = values.get(column).map(String::as_str).unwrap_or("");
+                    self.cell(x, value, *style);
+                }
+                self.y += ROW_HEIGHT;
+            }
+        }
+    }
+
+    fn table_header(&mut self, headers: &[String], columns: &[usize]) {
+        for (position, &column) in columns.iter().enumerate() {
+            let x = self.left + position as f32 * CELL_WIDTH;
+            self.cell(x, &headers[column], CellStyle::Header);
+        }
+        self.y += ROW_HEIGHT;
+    }
+
+    /// Add the footer and, unless the template turns them off, "Page X of N" to every page
+    fn finish(mut self, footer: &str) -> Vec<Vec<Op>> {
+        let total = self.pages.len();
+        let size = self.template.style_config.body_font_size as f32 - 2.0;
+        let y = self.page_height - self.bottom;
+        let left = self.left;
+        let right_x = self.page_width - self.right - 25.0;
+
+        for (index, page) in self.pages.iter_mut().enumerate() {
+            page.push(Op::Text {
+                x: left,
+                y,
+                size,
+                bold: false,
+                text: footer.to_string(),
+            });
+            if !self.template.page.page_numbers {
+                continue;
+            }
+            page.push(Op::Text {
+                x: right_x,
+                y,
+                size,
+                bold: false,
+                text: format!("Page {} of {}", index + 1, total),
+            });
+        }
+
+        self.pages
+    }
+}
+
+/// Replace characters the built-in PDF fonts cannot encode
+fn pdf_safe(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'Ω' => "Ohm".to_string(),
+            'µ' | 'μ' => "u".to_string(),
+            '°' => "deg".to_string(),
+            c if c.is_ascii() => c.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+/// Truncate text to roughly fit a width at a font size
+fn fit_text(text: &str, width: f32, size: f32) -> String {
+    let max_chars = (width / (size * CHAR_WIDTH_EM * PT_TO_MM)).max(1.0) as usize;
+    if text.chars().count() <= max_chars {
+        text.to_string()
+    } else {
+        let truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+        format!("{}~", truncated)
+    }
+}
+
+fn format_limit(value: Option<f64>, numbers: &NumberFormat) -> String {
+    value
+        .map(|v| numbers.format(v))
+        .unwrap_or_else(|| "N/A".to_string())
+}
+
+/// Readings, including numeric strings, use the template's number format
+fn measurement_to_string(value: &serde_json::Value, numbers: &NumberFormat) -> String {
+    if let Some(number) = numeric_value(value) {
+        return numbers.format(number);
+    }
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Bool(b) => b.to_string(),
+        serde_json::Value::Null => String::new(),
+        _ => value.to_string(),
+    }
+}
+
+/// Build the result table for one test, following the template's result columns
+fn result_table(
+    test: &TestResultData,
+    template: &ReportTemplate,
+    is_serialized: bool,
+) -> (Vec<String>, Vec<TableRow>, usize) {
+    let measurement_keys: Vec<String> = test
+        .results
+        .first()
+        .and_then(|r| r.measurements.as_object())
+        .map(|obj| obj.keys().cloned().collect())
+        .unwrap_or_default();
+
+    let columns: Vec<&str> = template
+        .result_columns
+        .iter()
+        .map(|c| c.as_str())
+        .filter(|c| is_serialized || !matches!(*c, "serial" | "retests"))
+        .collect();
+
+    let mut headers = Vec::new();
+    for column in &columns {
+        match *column {
+            "serial" => headers.push("Serial #".to_string()),
+            "batch" => headers.push("Batch".to_string()),
+            "date" => headers.push("Date".to_string()),
+            "result" => headers.push("Result".to_string()),
+            "retests" => headers.push("Retests".to_string()),
+            "measurements" => headers.extend(measurement_keys.iter().cloned()),
+            _ => {}
+        }
+    }
+
+    let rows = test
+        .results
+        .iter()
+        .map(|result| {
+            let mut values = Vec::new();
+            for column in &columns {
+                match *column {
+                    "serial" => values.push(result.serial_number.clone().unwrap_or_default()),
+                    "batch" => values.push(result.batch.clone()),
+                    "date" => values.push(result.date.clone()),
+                    "result" => values.push(result.result.clone()),
+                    "retests" => values.push(result.retests.to_string()),
+                    "measurements" => {
+                        let obj = result.measurements.as_object();
+                        values.extend(measurement_keys.iter().map(|key| {
+                            obj.and_then(|o| o.get(key))
+                                .map(|value| measurement_to_string(value, &template.numbers))
+                                .unwrap_or_default()
+                        }));
+                    }
+                    _ => {}
+                }
+            }
+
+            let verdict = result.result.to_uppercase();
+            let style = if verdict.contains("PASS") {
+                CellStyle::Pass
+            } else if verdict.contains("FAIL") {
+                CellStyle::Fail
+            } else {
+                CellStyle::Plain
+            };
+
+            (values.into_iter().map(|v| pdf_safe(&v)).collect(), style)
+        })
+        .collect();
+
+    // Identifying columns (serial/batch/date) are repeated when the table is split
+    let key_columns = columns
+        .iter()
+        .take_while(|c| matches!(**c, "serial" | "batch" | "date"))
+        .count();
+
+    (
+        headers.iter().map(|h| pdf_safe(h)).collect(),
+        rows,
+        key_columns,
+    )
+}
+
+fn layout_report(data: &ReportData, template: &ReportTemplate) -> Vec<Vec<Op>> {
+    let values: TemplateValues = template_values(data);
+    let style = &template.style_config;
+    let body_size = style.body_font_size as f32;
+    let heading_size = style.heading_font_size as f32;
+
+    let running_header = pdf_safe(&format!(
+        "Test Report - FG {} Rev {}",
+        data.fg_number, data.fg_revision
+    ));
+    let mut layout = Layout::new(template, running_header);
+
+    // Title and header fields
+    layout.text(
+        layout.left,
+        &pdf_safe(&format!("FG {} Test Report", data.fg_number)),
+        style.title_font_size as f32,
+        true,
+    );
+    layout.y += style.title_font_size as f32 * PT_TO_MM + 4.0;
+
+    for field in &template.header_fields {
+        let label = pdf_safe(&values.render(&field.label));
+        let value = pdf_safe(&values.render(&field.value));
+        layout.text(layout.left, &label, body_size, true);
+        layout.text(layout.left + 40.0, &value, body_size, false);
+        layout.y += ROW_HEIGHT;
+    }
+    layout.y += ROW_HEIGHT;
+
+    // Test sections
+    for test in &data.test_results {
+        layout.ensure_space(ROW_HEIGHT * 5.0);
+
+        let header_rgb = color_to_rgb(&style.header_color);
+        let width = layout.content_width();
+        let (x, y) = (layout.left, layout.y);
+        layout.ops().push(Op::Fill {
+            x,
+            y,
+            w: width,
+            h: ROW_HEIGHT + 1.0,
+            rgb: header_rgb,
+        });
+        layout.text(
+            x + CELL_PADDING,
+            &pdf_safe(&format!("Test: {}", test.test_name)),
+            heading_size,
+            true,
+        );
+        layout.y += ROW_HEIGHT + 2.0;
+
+        let mut spec = format!(
+            "Source: {}   Min: {}   Max: {}   Unit: {}",
+            test.source_type,
+            format_limit(test.spec_min, &template.numbers),
+            format_limit(test.spec_max, &template.numbers),
+            test.spec_unit.as_deref().unwrap_or("N/A"),
+        );
+        if let Some(associated) = &test.associated_test {
+            spec.push_str(&format!("   Associated Test: {}", associated));
+        }
+        layout.text(layout.left, &pdf_safe(&spec), body_size, false);
+        layout.y += ROW_HEIGHT + 1.0;
+
+        if test.results.is_empty() {
+            layout.text(layout.left, "NO DATA AVAILABLE", body_size, false);
+            layout.y += ROW_HEIGHT;
+        } else {
+            let (headers, rows, key_columns) = result_table(test, template, data.is_serialized);
+            layout.table(&headers, &rows, key_columns);
+        }
+
+        layout.y += ROW_HEIGHT * template.rows_between_tests.min(3) as f32;
+    }
+
+    // Signature / approval block
+    signature_block(&mut layout);
+
+    let footer = pdf_safe(&format!(
+        "FG {} Rev {} - {}",
+        data.fg_number,
+        data.fg_revision,
+        values.render("{lot_label} {lot}")
+    ));
+    layout.finish(&footer)
+}
+
+/// Draw the signature / approval block, using the template's labels if it has any
+fn signature_block(layout: &mut Layout) {
+    let body_size = layout.template.style_config.body_font_size as f32;
+    let labels: Vec<String> = if layout.template.signature_block.is_empty() {
+        DEFAULT_SIGNATURE_LABELS
+            .iter()
+            .map(|l| l.to_string())
+            .collect()
+    } else {
+        layout.template.signature_block.clone()
+    };
+
+    layout.ensure_space(labels.len() as f32 * ROW_HEIGHT * 2.0 + ROW_HEIGHT);
+    layout.y += ROW_HEIGHT;
+    for label in &labels {
+        let x = layout.left;
+        layout.text(x, &pdf_safe(label), body_size, true);
+        let line_y = layout.y + ROW_HEIGHT;
+        layout.ops().push(Op::Rule {
+            x1: x + 35.0,
+            x2: x + 110.0,
+            y: line_y,
+        });
+        layout.text(x + 115.0, "Date", body_size, true);
+        layout.ops().push(Op::Rule {
+            x1: x + 127.0,
+            x2: x + 170.0,
+            y: line_y,
+        });
+        layout.y += ROW_HEIGHT * 2.0;
+    }
+}
+
+const COC_STATEMENT: &str = "We hereby certify that the products listed above were manufactured, \
+inspected and tested in accordance with the applicable drawings and specifications, \
+and conform to all stated requirements.";
+
+/// Break text into lines of roughly `width` mm at a font size
+fn wrap_text(text: &str, width: f32, size: f32) -> Vec<String> {
+    let max_chars = (width / (size * CHAR_WIDTH_EM * PT_TO_MM)).max(1.0) as usize;
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > max_chars {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+fn layout_coc(coc: &CocSummary, template: &ReportTemplate) -> Vec<Vec<Op>> {
+    let style = &template.style_config;
+    let body_size = style.body_font_size as f32;
+    let heading_size = style.heading_font_size as f32;
+
+    let running_header = pdf_safe(&format!(
+        "Certificate of Conformance - FG {} Rev {}",
+        coc.fg_number, coc.fg_revision
+    ));
+    let mut layout = Layout::new(template, running_header);
+
+    layout.text(
+        layout.left,
+        "CERTIFICATE OF CONFORMANCE",
+        style.title_font_size as f32,
+        true,
+    );
+    layout.y += style.title_font_size as f32 * PT_TO_MM + 4.0;
+
+    let fields = [
+        ("Customer", coc.customer.clone()),
+        (
+            "Customer P/N",
+            coc.customer_pn.clone().unwrap_or_else(|| "N/A".to_string()),
+        ),
+        ("FG", coc.fg_number.clone()),
+        ("Revision", coc.fg_revision.clone()),
+        ("Job Number", coc.job_number.clone()),
+        (
+            "Quantity",
+            coc.quantity
+                .map(|q| q.to_string())
+                .unwrap_or_else(|| "N/A".to_string()),
+        ),
+        (coc.scope_label.as_str(), coc.scope.clone()),
+        ("Date Code", coc.date_code.clone()),
+        ("Issue Date", coc.issue_date.clone()),
+    ];
+    for (label, value) in &fields {
+        layout.text(layout.left, &pdf_safe(label), body_size, true);
+        layout.text(layout.left + 40.0, &pdf_safe(value), body_size, false);
+        layout.y += ROW_HEIGHT;
+    }
+    layout.y += ROW_HEIGHT;
+
+    layout.text(layout.left, "Test Summary", heading_size, true);
+    layout.y += ROW_HEIGHT + 1.0;
+
+    let headers: Vec<String> = ["Test", "Source", "Tested", "Passed", "Failed"]
+        .iter()
+        .map(|h| h.to_string())
+        .collect();
+    let rows: Vec<TableRow> = coc
+        .tests
+        .iter()
+        .map(|test| {
+            let style = if test.units_failed > 0 {
+                CellStyle::Fail
+            } else {
+                CellStyle::Plain
+            };
+            (
+                vec![
+                    pdf_safe(&test.test_name),
+                    pdf_safe(&test.source_type),
+                    test.units_tested.to_string(),
+                    test.units_passed.to_string(),
+                    test.units_failed.to_string(),
+                ],
+                style,
+            )
+        })
+        .collect();
+    layout.table(&headers, &rows, 1);
+    layout.y += ROW_HEIGHT;
+
+    let status = if coc.all_pass && coc.is_complete {
+        "Status: ALL TESTS PERFORMED - ALL UNITS PASS".to_string()
+    } else {
+        "Status: ISSUED UNDER OVERRIDE".to_string()
+    };
+    layout.text(layout.left, &status, heading_size, true);
+    layout.y += ROW_HEIGHT + 2.0;
+
+    if let Some(record) = &coc.override_record {
+        let note = format!(
+            "Override approved by {} on {}: {}",
+            record.approved_by, record.created_at, record.reason
+        );
+        for line in wrap_text(&pdf_safe(&note), layout.content_width(), body_size) {
+            layout.text(layout.left, &line, body_size, false);
+            layout.y += ROW_HEIGHT - 1.0;
+        }
+        layout.y += 2.0;
+    }
+
+    for line in wrap_text(COC_STATEMENT, layout.content_width(), body_size) {
+        layout.text(layout.left, &line, body_size, false);
+        layout.y += ROW_HEIGHT - 1.0;
+    }
+
+    signature_block(&mut layout);
+
+    let footer = pdf_safe(&format!(
+        "FG {} Rev {} - Job {}",
+        coc.fg_number, coc.fg_revision, coc.job_number
+    ));
+    layout.finish(&footer)
+}
+
+/// Generate a PDF report from collected data
+pub fn generate_report(data: &ReportData, template: &ReportTemplate) -> Result<Vec<u8>, String> {
+    let title = pdf_safe(&format!("FG {} Test Report", data.fg_number));
+    render(&layout_report(data, template), template, &title)
+}
+
+/// Generate a one-page Certificate of Conformance
+pub fn generate_coc(coc: &CocSummary, template: &ReportTemplate) -> Result<Vec<u8>, String> {
+    let title = pdf_safe(&format!("FG {} Certificate of Conformance", coc.fg_number));
+    render(&layout_coc(coc, template), template, &title)
+}
+
+/// Write laid-out pages to PDF bytes
+fn render(pages: &[Vec<Op>], template: &ReportTemplate, title: &str) -> Result<Vec<u8>, String> {
+    let (width, height) = page_size(template);
+    let (doc, first_page, first_layer) = PdfDocument::new(title, Mm(width), Mm(height), "Report");
+
+    let regular = doc
+        .add_builtin_font(BuiltinFont::Helvetica)
+        .map_err(|e| format!("Failed to load PDF font: {}", e))?;
+    let bold = doc
+        .add_builtin_font(BuiltinFont::HelveticaBold)
+        .map_err(|e| format!("Failed to load PDF font: {}", e))?;
+
+    let black = Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None));
+    let rgb = |(r, g, b): (u8, u8, u8)| {
+        Color::Rgb(Rgb::new(
+            r as f32 / 255.0,
+            g as f32 / 255.0,
+            b as f32 / 255.0,
+            None,
+        ))
+    };
+
+    for (index, ops) in pages.iter().enumerate() {
+        let layer = if index == 0 {
+            doc.get_page(first_page).get_layer(first_layer)
+        } else {
+            let (page, layer) = doc.add_page(Mm(width), Mm(height), "Report");
+            doc.get_page(page).get_layer(layer)
+        };
+
+        layer.set_outline_thickness(0.5);
+        layer.set_outline_color(black.clone());
+
+        // PDF coordinates start at the bottom of the page
+        for op in ops {
+            match op {
+                Op::Text {
+                    x,
+                    y,
+                    size,
+                    bold: is_bold,
+                    text,
+                } => {
+                    layer.set_fill_color(black.clone());
+                    let font = if *is_bold { &bold } else { &regular };
+                    layer.use_text(text.as_str(), *size, Mm(*x), Mm(height - y), font);
+                }
+                Op::Fill {
+                    x,
+                    y,
+                    w,
+                    h,
+                    rgb: color,
+                } => {
+                    layer.set_fill_color(rgb(*color));
+                    layer.add_rect(
+                        Rect::new(Mm(*x), Mm(height - y - h), Mm(x + w), Mm(height - y))
+                            .with_mode(PaintMode::Fill),
+                    );
+                }
+                Op::Stroke { x, y, w, h } => {
+                    layer.add_rect(
+                        Rect::new(Mm(*x), Mm(height - y - h), Mm(x + w), Mm(height - y))
+                            .with_mode(PaintMode::Stroke),
+                    );
+                }
+                Op::Rule { x1, x2, y } => {
+                    layer.add_line(Line {
+                        points: vec![
+                            (Point::new(Mm(*x1), Mm(height - y)), false),
+                            (Point::new(Mm(*x2), Mm(height - y)), false),
+                        ],
+                        is_closed: false,
+                    });
+                }
+            }
+        }
+    }
+
+    doc.save_to_bytes()
+        .map_err(|e| format!("Failed to write PDF: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reports::collector::SingleTestResult;
+    use crate::reports::retest::RetestPolicy;
+    use crate::reports::template::default_template;
+
+    fn report(result_count: usize, measurement_count: usize) -> ReportData {
+        let results = (0..result_count)
+            .map(|i| {
+                let measurements: serde_json::Map<String, serde_json::Value> = (0
+                    ..measurement_count)
+                    .map(|m| {
+                        (
+                            format!("M{:02}", m),
+                            serde_json::json!(i as f64 + m as f64 / 10.0),
+                        )
+                    })
+                    .collect();
+                SingleTestResult {
+                    serial_number: Some(format!("{}", 1000 + i)),
+                    batch: "B1".to_string(),
+                    date: "2025-11-19".to_string(),
+                    result: if i % 7 == 0 { "FAIL" } else { "PASS" }.to_string(),
+                    measurements: serde_json::Value::Object(measurements),
+                    retests: 0,
+                }
+            })
+            .collect();
+
+        ReportData {
+            report_id: 1,
+            fg_id: 1,
+            fg_number: "132520".to_string(),
+            fg_revision: "FTA".to_string(),
+            fg_customer: "ACME".to_string(),
+            is_serialized: true,
+            batch: None,
+            serial_range: Some("1000-1999".to_string()),
+            retest_policy: RetestPolicy::default(),
+            test_results: vec![TestResultData {
+                test_id: 1,
+                test_name: "DCR".to_string(),
+                source_type: "voltech".to_string(),
+                associated_test: None,
+                spec_min: Some(1.0),
+                spec_max: Some(2.0),
+                spec_unit: Some("Ω".to_string()),
+                results,
+            }],
+        }
+    }
+
+    fn page_texts(page: &[Op]) -> Vec<&str> {
+        page.iter()
+            .filter_map(|op| match op {
+                Op::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_long_tables_paginate_with_repeated_headers() {
+        let pages = layout_report(&report(120, 2), &default_template());
+        assert!(pages.len() > 2);
+
+        let total = pages.len();
+        for (index, page) in pages.iter().enumerate() {
+            let texts = page_texts(page);
+            assert!(texts.contains(&"Test Report - FG 132520 Rev FTA"));
+            assert!(texts.contains(&format!("Page {} of {}", index + 1, total).as_str()));
+            // Every page holding result rows starts them under a header row
+            if index > 0 && texts.iter().any(|t| t.starts_with("11")) {
+                assert!(texts.contains(&"Serial #"));
+            }
+        }
+
+        let last = page_texts(pages.last().unwrap());
+        assert!(last.contains(&"Approved By"));
+    }
+
+    #[test]
+    fn test_wide_tables_split_into_column_groups() {
+        let pages = layout_report(&report(3, 20), &default_template());
+        let texts: Vec<&str> = pages.iter().flat_map(|p| page_texts(p)).collect();
+
+        // Key columns are repeated once per column group
+        let groups = texts.iter().filter(|t| **t == "Serial #").count();
+        assert!(groups > 1);
+        assert_eq!(texts.iter().filter(|t| **t == "M19").count(), 1);
+        assert!(texts.iter().any(|t| t.contains("Unit: Ohm")));
+    }
+
+    #[test]
+    fn test_generate_pdf_bytes() {
+        let bytes = generate_report(&report(10, 3), &default_template()).unwrap();
+        assert!(bytes.starts_with(b"%PDF"));
+    }
+
+    #[test]
+    fn test_coc_layout_shows_override_and_signature() {
+        use crate::reports::coc::{CocOverride, CocTestSummary};
+
+        let coc = CocSummary {
+            fg_number: "132520".to_string(),
+            fg_revision: "FTA".to_string(),
+            customer: "ACME".to_string(),
+            customer_pn: Some("AC-100".to_string()),
+            job_number: "J1234".to_string(),
+            quantity: Some(10),
+            scope_label: "Serial Range".to_string(),
+            scope: "1001-1010".to_string(),
+            date_code: "2547".to_string(),
+            issue_date: "2025-11-20".to_string(),
+            tests: vec![CocTestSummary {
+                test_name: "DCR".to_string(),
+                source_type: "voltech".to_string(),
+                units_tested: 10,
+                units_passed: 9,
+                units_failed: 1,
+            }],
+            is_complete: true,
+            all_pass: false,
+            failing_units: vec!["1004".to_string()],
+            blocking_reasons: vec![],
+            override_record: Some(CocOverride {
+                id: 1,
+                report_id: 1,
+                scope: "1001-1010".to_string(),
+                reason: "Unit 1004 scrapped".to_string(),
+                approved_by: "admin".to_string(),
+                created_at: "2025-11-20 10:00:00".to_string(),
+                blocking_reasons: vec!["1 unit(s) have a failing final result: 1004".to_string()],
+            }),
+            can_generate: true,
+        };
+
+        let pages = layout_coc(&coc, &default_template());
+        assert_eq!(pages.len(), 1);
+
+        let texts = page_texts(&pages[0]);
+        assert!(texts.contains(&"AC-100"));
+        assert!(texts.contains(&"Status: ISSUED UNDER OVERRIDE"));
+        assert!(texts.iter().any(|t| t.contains("Unit 1004 scrapped")));
+        assert!(texts.contains(&"Approved By"));
+        assert!(generate_coc(&coc, &default_template())
+            .unwrap()
+            .starts_with(b"%PDF"));
+    }
+
+    #[test]
+    fn test_fit_text() {
+        assert_eq!(fit_text("short", 25.0, 11.0), "short");
+        assert!(fit_text(&"x".repeat(100), 25.0, 11.0).ends_with('~'));
+    }
+}
@@ -0,0 +1,545 @@
+use crate::part_mapping::{manual_parts, voltech_parts};
+use crate::reports::collector::serial_spec;
+use crate::serials::SerialSpec;
+use crate::AppState;
+use ::entity::fg as fg_entity;
+use ::entity::test as test_entity;
+use chrono::NaiveDate;
+use entity_manual::manual_test_results;
+use entity_voltech::test_results as voltech_test_results;
+use sea_orm::*;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use tauri::State;
+
+// ============================================================================
+// Cross-source Reconciliation
+// ============================================================================
+//
+// Compares an FG's Voltech and manual records for one serial range or batch:
+// serials tested on one source but not the other, serials whose batch differs
+// between sources, manual records at another revision than the FG, and
+// operators or dates that stand out from the rest of the run. Nothing is
+// changed; the discrepancies are listed for someone to look at.
+// ============================================================================
+
+/// Dates further than this from the run's median date are outliers
+const DATE_OUTLIER_DAYS: i64 = 14;
+/// Operators with a smaller share of a source's records are outliers...
+const OPERATOR_OUTLIER_SHARE: f64 = 0.05;
+/// ...once the source has at least this many records
+const OPERATOR_MIN_RECORDS: usize = 20;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscrepancyKind {
+    MissingVoltech, // Manual data but no Voltech run
+    MissingManual,  // Voltech run but no manual data
+    BatchMismatch,  // Manual and Voltech batches differ for the serial
+    RevMismatch,    // Manual rev is not the FG rev
+    OperatorOutlier,
+    DateOutlier,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Discrepancy {
+    pub kind: DiscrepancyKind,
+    pub serial: Option<String>,
+    pub source: Option<String>, // "voltech" or "manual"; None when both are involved
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Reconciliation {
+    pub fg_id: i32,
+    pub fg_number: String,
+    pub fg_revision: String,
+    pub serial_range: Option<String>,
+    pub batch: Option<String>,
+    pub voltech_records: usize,
+    pub manual_records: usize,
+    pub serials_checked: usize,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+/// The fields reconciliation compares, from either source
+#[derive(Debug, Clone)]
+struct SourceRecord {
+    source: &'static str,
+    serial: Option<String>,
+    batch: String,
+    operator: String,
+    date: Option<NaiveDate>,
+    rev: Option<String>, // Manual only
+}
+
+/// Which sources the FG's reports take data from
+#[derive(Debug, Clone, Copy)]
+struct ExpectedSources {
+    voltech: bool,
+    manual: bool,
+}
+
+/// Serial a record counts under: the specification's label, or the recorded
+/// serial when reconciling a batch
+fn serial_key(serial: &str, serials: Option<&SerialSpec>) -> Option<String> {
+    match serials {
+        Some(serials) => serials.label(serial),
+        None => Some(serial.trim())
+            .filter(|s| !s.is_empty() && !s.eq_ignore_ascii_case("NONE"))
+            .map(str::to_string),
+    }
+}
+
+fn join(values: &BTreeSet<&str>) -> String {
+    values.iter().copied().collect::<Vec<_>>().join(", ")
+}
+
+/// List the discrepancies between an FG's Voltech and manual records
+fn reconcile(
+    fg_rev: &str,
+    expected: ExpectedSources,
+    records: &[SourceRecord],
+) -> Vec<Discrepancy> {
+    let mut discrepancies = Vec::new();
+
+    // Batches seen per serial and source
+    let mut by_serial: BTreeMap<&str, [BTreeSet<&str>; 2]> = BTreeMap::new();
+    for record in records {
+        if let Some(serial) = &record.serial {
+            let slot = usize::from(record.source == "manual");
+            by_serial.entry(serial).or_default()[slot].insert(record.batch.trim());
+        }
+    }
+
+    for (serial, [voltech, manual]) in &by_serial {
+        let kind = match (voltech.is_empty(), manual.is_empty()) {
+            (true, false) if expected.voltech => Some(DiscrepancyKind::MissingVoltech),
+            (false, true) if expected.manual => Some(DiscrepancyKind::MissingManual),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            let (present, missing) = match kind {
+                DiscrepancyKind::MissingVoltech => ("manual", "voltech"),
+                _ => ("voltech", "manual"),
+            };
+            discrepancies.push(Discrepancy {
+                kind,
+                serial: Some(String::from(*serial)),
+                source: Some(missing.to_string()),
+                detail: format!("Serial has {} data but no {} records", present, missing),
+            });
+        } else if !voltech.is_empty() && !manual.is_empty() && voltech != manual {
+            discrepancies.push(Discrepancy {
+                kind: DiscrepancyKind::BatchMismatch,
+                serial: Some(String::from(*serial)),
+                source: None,
+                detail: format!(
+                    "Voltech batch {} but manual batch {}",
+                    join(voltech),
+                    join(manual)
+                ),
+            });
+        }
+    }
+
+    // Manual revisions other than the FG's, per serial
+    let mut revs: BTreeMap<(Option<&str>, &str), usize> = BTreeMap::new();
+    for record in records {
+        let Some(rev) = record.rev.as_deref().map(str::trim) else {
+            continue;
+        };
+        if !rev.eq_ignore_ascii_case(fg_rev.trim()) {
+            *revs.entry((record.serial.as_deref(), rev)).or_default() += 1;
+        }
+    }
+    for ((serial, rev), count) in revs {
+        discrepancies.push(Discrepancy {
+            kind: DiscrepancyKind::RevMismatch,
+            serial: serial.map(str::to_string),
+            source: Some("manual".to_string()),
+            detail: format!(
+                "{} manual record(s) at rev {} (FG rev {})",
+                count,
+                if rev.is_empty() { "(blank)" } else { rev },
+                fg_rev
+            ),
+        });
+    }
+
+    for source in ["voltech", "manual"] {
+        let source_records: Vec<&SourceRecord> =
+            records.iter().filter(|r| r.source == source).collect();
+        discrepancies.extend(operator_outliers(source, &source_records));
+        discrepancies.extend(date_outliers(source, &source_records));
+    }
+
+    discrepancies
+}
+
+/// Operators who recorded only a sliver of a source's records
+fn operator_outliers(source: &str, records: &[&SourceRecord]) -> Vec<Discrepancy> {
+    if records.len() < OPERATOR_MIN_RECORDS {
+        return Vec::new();
+    }
+
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for record in records {
+        *counts.entry(record.operator.trim()).or_default() += 1;
+    }
+
+    counts
+        .into_iter()
+        .filter(|(_, count)| (*count as f64) < records.len() as f64 * OPERATOR_OUTLIER_SHARE)
+        .map(|(operator, count)| Discrepancy {
+            kind: DiscrepancyKind::OperatorOutlier,
+            serial: None,
+            source: Some(source.to_string()),
+            detail: format!(
+                "Operator {} recorded {} of {} records",
+                if operator.is_empty() {
+                    "(blank)"
+                } else {
+                    operator
+                },
+                count,
+                records.len()
+            ),
+        })
+        .collect()
+}
+
+/// Test dates far from the rest of a source's records
+fn date_outliers(source: &str, records: &[&SourceRecord]) -> Vec<Discrepancy> {
+    let mut dates: Vec<NaiveDate> = records.iter().filter_map(|r| r.date).collect();
+    if dates.is_empty() {
+        return Vec::new();
+    }
+    dates.sort_unstable();
+    let median = dates[dates.len() / 2];
+
+    let mut outliers: BTreeMap<NaiveDate, BTreeSet<&str>> = BTreeMap::new();
+    for record in records {
+        let Some(date) = record.date else {
+            continue;
+        };
+        if (date - median).num_days().abs() > DATE_OUTLIER_DAYS {
+            outliers
+                .entry(date)
+                .or_default()
+                .extend(record.serial.as_deref());
+        }
+    }
+
+    outliers
+        .into_iter()
+        .map(|(date, serials)| Discrepancy {
+            kind: DiscrepancyKind::DateOutlier,
+            serial: None,
+            source: Some(source.to_string()),
+            detail: if serials.is_empty() {
+                format!("Tested {} (most records around {})", date, median)
+            } else {
+                format!(
+                    "Tested {} (most records around {}): {}",
+                    date,
+                    median,
+                    join(&serials)
+                )
+            },
+        })
+        .collect()
+}
+
+/// Load the FG's records from both sources for a serial specification or batch
+///
+/// A batch is resolved to the serials tested in it on either source, and
+/// those serials' records are loaded whatever batch they were recorded under,
+/// so a serial filed under another batch on one source still shows up.
+async fn source_records(
+    voltech_parts: Vec<String>,
+    manual_parts: Vec<String>,
+    serials: Option<&SerialSpec>,
+    batch: Option<&str>,
+    voltech_db: &DbConn,
+    manual_db: &DbConn,
+) -> Result<Vec<SourceRecord>, DbErr> {
+    let mut voltech = voltech_test_results::Entity::find()
+        .filter(voltech_test_results::Column::Part.is_in(voltech_parts));
+    let mut manual = manual_test_results::Entity::find()
+        .filter(manual_test_results::Column::Fg.is_in(manual_parts));
+    if let Some(serials) = serials {
+        voltech = voltech.filter(serials.condition(voltech_test_results::Column::SerialNum));
+        manual = manual.filter(serials.condition(manual_test_results::Column::Sn));
+    }
+    if let Some(batch) = batch {
+        let mut batch_serials: BTreeSet<String> = voltech
+            .clone()
+            .filter(voltech_test_results::Column::Batch.eq(batch))
+            .select_only()
+            .column(voltech_test_results::Column::SerialNum)
+            .into_tuple::<String>()
+            .all(voltech_db)
+            .await?
+            .into_iter()
+            .collect();
+        batch_serials.extend(
+            manual
+                .clone()
+                .filter(manual_test_results::Column::Batch.eq(batch))
+                .select_only()
+                .column(manual_test_results::Column::Sn)
+                .into_tuple::<String>()
+                .all(manual_db)
+                .await?,
+        );
+        // Serial-less records can only be matched on the batch itself
+        batch_serials.retain(|s| serial_key(s, None).is_some());
+
+        voltech = voltech.filter(
+            Condition::any()
+                .add(voltech_test_results::Column::Batch.eq(batch))
+                .add(voltech_test_results::Column::SerialNum.is_in(batch_serials.clone())),
+        );
+        manual = manual.filter(
+            Condition::any()
+                .add(manual_test_results::Column::Batch.eq(batch))
+                .add(manual_test_results::Column::Sn.is_in(batch_serials)),
+        );
+    }
+
+    let mut records: Vec<SourceRecord> = voltech
+        .all(voltech_db)
+        .await?
+        .into_iter()
+        .map(|r| SourceRecord {
+            source: "voltech",
+            serial: serial_key(&r.serial_num, serials),
+            batch: r.batch,
+            operator: r.operator,
+            date: r.normalized_date,
+            rev: None,
+        })
+        .collect();
+    records.extend(
+        manual
+            .all(manual_db)
+            .await?
+            .into_iter()
+            .map(|r| SourceRecord {
+                source: "manual",
+                serial: serial_key(&r.sn, serials),
+                batch: r.batch,
+                operator: r.operator,
+                date: Some(r.normalized_date),
+                rev: Some(r.rev),
+            }),
+    );
+    Ok(records)
+}
+
+// ============================================================================
+// Tauri Command
+// ============================================================================
+
+/// List discrepancies between an FG's Voltech and manual records
+/// Scoped to a serial specification and/or a batch; neither checks every record
+#[tauri::command]
+pub async fn reconcile_sources(
+    fg_id: i32,
+    serial_range: Option<String>,
+    batch: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Reconciliation, String> {
+    let db = &*state.core_db;
+    let fg = fg_entity::Entity::find_by_id(fg_id)
+        .one(db)
+        .await
+        .map_err(|e| format!("Failed to load FG: {}", e))?
+        .ok_or_else(|| "FG not found".to_string())?;
+
+    let serial_range = serial_range.filter(|s| !s.trim().is_empty());
+    let batch = batch.filter(|b| !b.trim().is_empty());
+    let serials = serial_spec(serial_range.as_deref()).map_err(|e| e.to_string())?;
+
+    // A source is only missing if the FG's reports use it
+    let tests = test_entity::Entity::find()
+        .filter(test_entity::Column::FgId.eq(fg.id))
+        .all(db)
+        .await
+        .map_err(|e| format!("Failed to load tests: {}", e))?;
+    let expected = ExpectedSources {
+        voltech: tests.iter().any(|t| t.source_type == "voltech"),
+        manual: tests.iter().any(|t| t.source_type == "manual"),
+    };
+
+    let voltech_parts = voltech_parts(db, &state.voltech_db, &fg.fg, Some(&fg.rev))
+        .await
+        .map_err(|e| format!("Failed to load part numbers: {}", e))?;
+    let manual_parts = manual_parts(db, &state.manual_db, &fg.fg, Some(&fg.rev))
+        .await
+        .map_err(|e| format!("Failed to load part numbers: {}", e))?;
+    let records = source_records(
+        voltech_parts,
+        manual_parts,
+        serials.as_ref(),
+        batch.as_deref(),
+        &state.voltech_db,
+        &state.manual_db,
+    )
+    .await
+    .map_err(|e| format!("Failed to load test records: {}", e))?;
+
+    let serials_checked = records
+        .iter()
+        .filter_map(|r| r.serial.as_deref())
+        .collect::<BTreeSet<_>>()
+        .len();
+
+    Ok(Reconciliation {
+        fg_id: fg.id,
+        voltech_records: records.iter().filter(|r| r.source == "voltech").count(),
+        manual_records: records.iter().filter(|r| r.source == "manual").count(),
+        serials_checked,
+        discrepancies: reconcile(&fg.rev, expected, &records),
+        fg_number: fg.fg,
+        fg_revision: fg.rev,
+        serial_range,
+        batch,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(source: &'static str, serial: &str, batch: &str, rev: Option<&str>) -> SourceRecord {
+        SourceRecord {
+            source,
+            serial: Some(serial.to_string()),
+            batch: batch.to_string(),
+            operator: "AB".to_string(),
+            date: NaiveDate::from_ymd_opt(2025, 11, 19),
+            rev: rev.map(str::to_string),
+        }
+    }
+
+    fn kinds(discrepancies: &[Discrepancy]) -> Vec<(DiscrepancyKind, Option<&str>)> {
+        discrepancies
+            .iter()
+            .map(|d| (d.kind, d.serial.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn test_missing_sources_and_mismatches() {
+        let both = ExpectedSources {
+            voltech: true,
+            manual: true,
+        };
+        let records = vec![
+            record("voltech", "1001", "B1", None),
+            record("manual", "1001", "B1", Some("fta")),
+            record("manual", "1002", "B1", Some("FTA")),
+            record("voltech", "1003", "B1", None),
+            record("manual", "1003", "B2", Some("FTB")),
+        ];
+
+        assert_eq!(
+            kinds(&reconcile("FTA", both, &records)),
+            vec![
+                (DiscrepancyKind::MissingVoltech, Some("1002")),
+                (DiscrepancyKind::BatchMismatch, Some("1003")),
+                (DiscrepancyKind::RevMismatch, Some("1003")),
+            ]
+        );
+
+        // Voltech-only FGs never miss manual data
+        let voltech_only = ExpectedSources {
+            voltech: true,
+            manual: false,
+        };
+        let records = vec![record("voltech", "1001", "B1", None)];
+        assert!(reconcile("FTA", voltech_only, &records).is_empty());
+    }
+
+    #[test]
+    fn test_operator_and_date_outliers() {
+        let mut records: Vec<SourceRecord> = (0..OPERATOR_MIN_RECORDS * 2)
+            .map(|i| record("voltech", &format!("{}", 1001 + i), "B1", None))
+            .collect();
+        records[0].operator = "ZZ".to_string();
+        records[1].date = NaiveDate::from_ymd_opt(2025, 6, 2);
+
+        let discrepancies = reconcile(
+            "FTA",
+            ExpectedSources {
+                voltech: true,
+                manual: false,
+            },
+            &records,
+        );
+        assert_eq!(
+            kinds(&discrepancies),
+            vec![
+                (DiscrepancyKind::OperatorOutlier, None),
+                (DiscrepancyKind::DateOutlier, None),
+            ]
+        );
+        assert!(discrepancies[1].detail.ends_with(": 1002"));
+    }
+
+    #[tokio::test]
+    async fn test_batch_includes_serials_recorded_under_another_batch() {
+        use migration_voltech::{Migrator, MigratorTrait};
+
+        let voltech_db = Database::connect("sqlite::memory:").await.unwrap();
+        Migrator::up(&voltech_db, None).await.unwrap();
+        for (result_num, serial) in [(1, "1001"), (2, "1002")] {
+            voltech_test_results::ActiveModel {
+                part: Set("FT1".to_string()),
+                operator: Set("AB".to_string()),
+                batch: Set("B1".to_string()),
+                date: Set("11-19-25".to_string()),
+                serial_num: Set(serial.to_string()),
+                result_num: Set(result_num),
+                pass_fail: Set("Pass".to_string()),
+                file_path: Set("f.atr".to_string()),
+                measurements: Set("[]".to_string()),
+                created_at: Set(chrono::Utc::now().into()),
+                normalized_date: Set(NaiveDate::from_ymd_opt(2025, 11, 19)),
+                ..Default::default()
+            }
+            .insert(&voltech_db)
+            .await
+            .unwrap();
+        }
+        // Serial 1002 was filed under B2 on the manual side
+        let manual_db = crate::manual::queries::test_db(&[
+            ("FT1", "B1", "AB", "PASS", None),
+            ("FT1", "B2", "AB", "PASS", None),
+        ])
+        .await;
+
+        let records = source_records(
+            vec!["FT1".to_string()],
+            vec!["FT1".to_string()],
+            None,
+            Some("B1"),
+            &voltech_db,
+            &manual_db,
+        )
+        .await
+        .unwrap();
+        assert_eq!(records.len(), 4);
+
+        let both = ExpectedSources {
+            voltech: true,
+            manual: true,
+        };
+        assert_eq!(
+            kinds(&reconcile("A", both, &records)),
+            vec![(DiscrepancyKind::BatchMismatch, Some("1002"))]
+        );
+    }
+}
@@ -0,0 +1,377 @@
+use crate::dates::combine_date_time;
+use chrono::{NaiveDate, NaiveDateTime};
+use entity_manual::manual_test_results;
+use entity_voltech::test_results as voltech_test_results;
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// ============================================================================
+// Retest Resolution
+// ============================================================================
+//
+// A serial may be tested several times for the same test. The report's retest
+// policy decides which of those attempts the report shows; every report path
+// (collector, serialized queries and the BA generator) resolves attempts the
+// same way. A serial that never passed shows its latest failure under the
+// "pass" policies, so failures are never silently hidden.
+//
+// Reports that have not chosen a policy keep what each path did before
+// policies existed: collected reports show every attempt, and the BA
+// serialized report (and `get_serialized`) each serial's latest pass.
+//
+// `report.retest_policy` is not part of the generated entity, so it is read
+// and written with SQL.
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetestPolicy {
+    LatestAttempt, // The most recent attempt, pass or fail
+    LatestPass,    // The most recent pass
+    FirstPass,     // The first pass
+    #[default]
+    AllAttempts, // Every attempt, oldest first
+}
+
+impl RetestPolicy {
+    /// Policy of the BA serialized report when the report has not chosen one
+    pub const SERIALIZED_DEFAULT: RetestPolicy = RetestPolicy::LatestPass;
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RetestPolicy::LatestAttempt => "latest_attempt",
+            RetestPolicy::LatestPass => "latest_pass",
+            RetestPolicy::FirstPass => "first_pass",
+            RetestPolicy::AllAttempts => "all_attempts",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RetestPolicy::LatestAttempt => "Latest attempt",
+            RetestPolicy::LatestPass => "Latest pass",
+            RetestPolicy::FirstPass => "First pass",
+            RetestPolicy::AllAttempts => "All attempts",
+        }
+    }
+
+    /// Parse a stored policy; unknown values fall back to the default
+    pub fn from_setting(value: &str) -> Self {
+        match value.trim() {
+            "latest_attempt" => RetestPolicy::LatestAttempt,
+            "latest_pass" => RetestPolicy::LatestPass,
+            "first_pass" => RetestPolicy::FirstPass,
+            _ => RetestPolicy::AllAttempts,
+        }
+    }
+
+    /// The policy deciding each serial's final result: the report's own, or
+    /// the latest attempt when the report shows every attempt
+    pub fn final_result(self) -> Self {
+        match self {
+            RetestPolicy::AllAttempts => RetestPolicy::LatestAttempt,
+            policy => policy,
+        }
+    }
+
+    /// Which attempts to keep from one serial's attempts, oldest first
+    fn keep(&self, passed: &[bool]) -> Vec<usize> {
+        let latest = passed.len().checked_sub(1);
+        let pass = match self {
+            RetestPolicy::AllAttempts => return (0..passed.len()).collect(),
+            RetestPolicy::LatestAttempt => None,
+            RetestPolicy::LatestPass => passed.iter().rposition(|p| *p),
+            RetestPolicy::FirstPass => passed.iter().position(|p| *p),
+        };
+        pass.or(latest).into_iter().collect()
+    }
+}
+
+/// A record the policy kept, and how many times its serial was tested
+#[derive(Debug, Clone)]
+pub struct Resolved<T> {
+    pub record: T,
+    pub attempts: i32,
+}
+
+impl<T> Resolved<T> {
+    /// Attempts after the first
+    pub fn retests(&self) -> i32 {
+        (self.attempts - 1).max(0)
+    }
+}
+
+/// Apply a retest policy to one test's records
+///
+/// Records are grouped by `serial` and ordered by `tested_at`. Records without
+/// a serial (unserialized batches) are never retests and are all kept. Serials
+/// keep the order they first appear in.
+pub fn resolve<T, K: Ord>(
+    records: Vec<T>,
+    policy: RetestPolicy,
+    serial: impl Fn(&T) -> Option<String>,
+    passed: impl Fn(&T) -> bool,
+    tested_at: impl Fn(&T) -> K,
+) -> Vec<Resolved<T>> {
+    let mut groups: Vec<Vec<T>> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for record in records {
+        match serial(&record) {
+            Some(key) => {
+                let slot = *index.entry(key).or_insert_with(|| {
+                    groups.push(Vec::new());
+                    groups.len() - 1
+                });
+                groups[slot].push(record);
+            }
+            None => groups.push(vec![record]),
+        }
+    }
+
+    let mut resolved = Vec::new();
+    for mut group in groups {
+        group.sort_by_key(&tested_at);
+        let attempts = group.len() as i32;
+        let verdicts: Vec<bool> = group.iter().map(&passed).collect();
+        let keep = policy.keep(&verdicts);
+        resolved.extend(
+            group
+                .into_iter()
+                .enumerate()
+                .filter(|(idx, _)| keep.contains(idx))
+                .map(|(_, record)| Resolved { record, attempts }),
+        );
+    }
+    resolved
+}
+
+/// When a Voltech attempt was tested, for ordering a serial's attempts
+///
+/// Times are parsed rather than compared as text, so "9:30" comes before
+/// "10:15".
+pub fn voltech_attempt_order(
+    record: &voltech_test_results::Model,
+) -> (Option<NaiveDateTime>, Option<NaiveDate>, i32) {
+    let tested_at = record
+        .normalized_date
+        .zip(record.time.as_deref())
+        .and_then(|(date, time)| combine_date_time(date, time));
+    (tested_at, record.normalized_date, record.id)
+}
+
+/// When a manual attempt was tested, for ordering a serial's attempts
+pub fn manual_attempt_order(
+    record: &manual_test_results::Model,
+) -> (Option<NaiveDateTime>, NaiveDate, i32) {
+    (record.tested_at, record.normalized_date, record.id)
+}
+
+#[derive(Debug, FromQueryResult)]
+struct RetestPolicyRow {
+    retest_policy: Option<String>,
+}
+
+/// Get the retest policy of a report; `None` when it has not chosen one
+pub async fn report_policy(db: &DbConn, report_id: i32) -> Result<Option<RetestPolicy>, DbErr> {
+    let row = RetestPolicyRow::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT retest_policy FROM report WHERE id = ?",
+        [report_id.into()],
+    ))
+    .one(db)
+    .await?;
+
+    Ok(row
+        .and_then(|r| r.retest_policy)
+        .map(|value| RetestPolicy::from_setting(&value)))
+}
+
+#[derive(Debug, FromQueryResult)]
+struct ReportPolicyRow {
+    id: i32,
+    retest_policy: Option<String>,
+}
+
+/// Get the retest policies of all reports that set one, keyed by report id
+pub async fn all_report_policies(db: &DbConn) -> Result<HashMap<i32, RetestPolicy>, DbErr> {
+    let rows = ReportPolicyRow::find_by_statement(Statement::from_string(
+        DbBackend::Sqlite,
+        "SELECT id, retest_policy FROM report WHERE retest_policy IS NOT NULL",
+    ))
+    .all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|r| {
+            r.retest_policy
+                .map(|value| (r.id, RetestPolicy::from_setting(&value)))
+        })
+        .collect())
+}
+
+/// Set the retest policy of a report
+pub async fn set_report_policy(
+    db: &DbConn,
+    report_id: i32,
+    policy: RetestPolicy,
+) -> Result<(), DbErr> {
+    db.execute_raw(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "UPDATE report SET retest_policy = ? WHERE id = ?",
+        [policy.as_str().into(), report_id.into()],
+    ))
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // (serial, passed, tested at)
+    fn attempts() -> Vec<(&'static str, bool, i32)> {
+        vec![
+            ("1002", true, 1),
+            ("1001", false, 2),
+            ("1001", true, 3),
+            ("1001", false, 4),
+            ("1003", false, 5),
+            ("1002", true, 6),
+        ]
+    }
+
+    fn kept(policy: RetestPolicy) -> Vec<(i32, i32)> {
+        resolve(
+            attempts(),
+            policy,
+            |a| Some(a.0.to_string()),
+            |a| a.1,
+            |a| a.2,
+        )
+        .into_iter()
+        .map(|r| (r.record.2, r.attempts))
+        .collect()
+    }
+
+    #[test]
+    fn test_policies_pick_attempts() {
+        assert_eq!(
+            kept(RetestPolicy::LatestAttempt),
+            vec![(6, 2), (4, 3), (5, 1)]
+        );
+        assert_eq!(kept(RetestPolicy::LatestPass), vec![(6, 2), (3, 3), (5, 1)]);
+        assert_eq!(kept(RetestPolicy::FirstPass), vec![(1, 2), (3, 3), (5, 1)]);
+        assert_eq!(
+            kept(RetestPolicy::AllAttempts),
+            vec![(1, 2), (6, 2), (2, 3), (3, 3), (4, 3), (5, 1)]
+        );
+    }
+
+    #[test]
+    fn test_unserialized_records_are_not_retests() {
+        let resolved = resolve(
+            vec![("", true, 1), ("", false, 2)],
+            RetestPolicy::LatestPass,
+            |a| {
+                Some(a.0.trim())
+                    .filter(|s| !s.is_empty())
+                    .map(str::to_string)
+            },
+            |a| a.1,
+            |a| a.2,
+        );
+        assert_eq!(resolved.len(), 2);
+        assert!(resolved.iter().all(|r| r.retests() == 0));
+        assert_eq!(
+            RetestPolicy::from_setting("first_pass"),
+            RetestPolicy::FirstPass
+        );
+        assert_eq!(RetestPolicy::from_setting("bogus"), RetestPolicy::default());
+    }
+
+    #[test]
+    fn test_attempts_are_ordered_by_parsed_time() {
+        use chrono::Utc;
+
+        let date = NaiveDate::from_ymd_opt(2025, 11, 19);
+        let manual = |id: i32, time: &str, passfail: &str| manual_test_results::Model {
+            id,
+            result: 0,
+            test: "132520-LFG-DCR1".to_string(),
+            fg: "132520".to_string(),
+            rev: "FTA".to_string(),
+            batch: "B1".to_string(),
+            operator: "op".to_string(),
+            date: "11/19/2025".to_string(),
+            time: time.to_string(),
+            sn: "1001".to_string(),
+            passfail: passfail.to_string(),
+            minimum: 0.0,
+            reading: 1.0,
+            maximum: 2.0,
+            uom: "Ohm".to_string(),
+            file_path: "f".to_string(),
+            created_at: Utc::now().into(),
+            normalized_date: date.unwrap(),
+            computed_passfail: None,
+            tested_at: date.and_then(|d| combine_date_time(d, time)),
+        };
+        // Text order would put "9:30" after "10:15"
+        let latest = resolve(
+            vec![manual(1, "9:30", "PASS"), manual(2, "10:15", "FAIL")],
+            RetestPolicy::LatestAttempt,
+            |r| Some(r.sn.clone()),
+            |r| r.passfail.eq_ignore_ascii_case("pass"),
+            manual_attempt_order,
+        );
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].record.id, 2);
+
+        // "1:05 PM" is after "11:40 AM"
+        let first_pass = resolve(
+            vec![manual(3, "1:05 PM", "PASS"), manual(4, "11:40 AM", "FAIL")],
+            RetestPolicy::FirstPass,
+            |r| Some(r.sn.clone()),
+            |r| r.passfail.eq_ignore_ascii_case("pass"),
+            manual_attempt_order,
+        );
+        assert_eq!(first_pass[0].record.id, 3);
+        assert_eq!(first_pass[0].retests(), 1);
+        let latest = resolve(
+            vec![manual(3, "1:05 PM", "PASS"), manual(4, "11:40 AM", "FAIL")],
+            RetestPolicy::LatestAttempt,
+            |r| Some(r.sn.clone()),
+            |r| r.passfail.eq_ignore_ascii_case("pass"),
+            manual_attempt_order,
+        );
+        assert_eq!(latest[0].record.id, 3);
+    }
+
+    #[tokio::test]
+    async fn test_unset_policy_is_left_to_each_path() {
+        use migration::{Migrator, MigratorTrait};
+
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        Migrator::up(&db, None).await.unwrap();
+        db.execute_unprepared(
+            "INSERT INTO fg (id, fg, rev, customer) VALUES (1, '132520', 'FTA', 'ACME');
+             INSERT INTO report (id, fg_id) VALUES (1, 1);",
+        )
+        .await
+        .unwrap();
+
+        // Reports from before policies keep no stored policy
+        assert_eq!(report_policy(&db, 1).await.unwrap(), None);
+        assert!(all_report_policies(&db).await.unwrap().is_empty());
+
+        set_report_policy(&db, 1, RetestPolicy::FirstPass)
+            .await
+            .unwrap();
+        assert_eq!(
+            report_policy(&db, 1).await.unwrap(),
+            Some(RetestPolicy::FirstPass)
+        );
+    }
+}
@@ -1,68 +1,67 @@
 use entity_voltech::test_results;
 use entity_manual::manual_test_results;
 use sea_orm::*;
-use sea_orm::sea_query::Expr;
+use std::collections::HashMap;
 use tauri::State;
+use crate::manual::limits::manual_verdict;
+use crate::part_mapping::voltech_parts;
+use crate::reports::retest::{manual_attempt_order, resolve, voltech_attempt_order, Resolved, RetestPolicy};
+use crate::serials::SerialSpec;
 use crate::AppState;
 
 #[tauri::command]
-pub async fn get_serialized(fg: &str, rev: &str, start_serial: i32, end_serial: i32, state: State<'_, AppState>) -> Result<Vec<test_results::Model>, String> {
+pub async fn get_serialized(fg: &str, rev: &str, serials: &str, retest_policy: Option<RetestPolicy>, state: State<'_, AppState>) -> Result<Vec<test_results::Model>, String> {
     let db = &*state.voltech_db;
+    let serials = SerialSpec::parse(serials)?;
+    let parts = voltech_parts(&state.core_db, db, fg, Some(rev))
+        .await
+        .map_err(|e| format!("Failed to match part numbers: {}", e))?;
 
-    let results = query_serialized(fg, rev, start_serial, end_serial, db)
+    let results = query_serialized(&parts, &serials, retest_policy.unwrap_or(RetestPolicy::SERIALIZED_DEFAULT), db)
         .await
         .map_err(|e| format!("Failed to query serialized test results: {}", e))?;
-    Ok(results)
+    Ok(results.into_iter().map(|r| r.record).collect())
+}
+
+/// Position of each serial in the spec, for sorting records in spec order
+fn serial_order(serials: &SerialSpec) -> HashMap<String, usize> {
+    serials
+        .serials()
+        .into_iter()
+        .enumerate()
+        .map(|(index, serial)| (serial, index))
+        .collect()
 }
 
 /// # Query Test Results
-/// - `Full Test Name`, `Serial Number Range`, `Passed`
-/// - Deduplicates serial numbers and keeps the most recent test
+/// - `Voltech Part Numbers` (see `part_mapping`), `Serial Number Specification`, `Retest Policy`
+/// - Groups attempts by serial ("026" and "26" are one serial) and keeps the ones the policy selects
 pub async fn query_serialized(
-    fg: &str, 
-    rev: &str, 
-    start_serial: i32, 
-    end_serial: i32, 
+    parts: &[String],
+    serials: &SerialSpec,
+    policy: RetestPolicy,
     db: &DbConn,
-) -> Result<Vec<test_results::Model>, DbErr> {
-    // Build the part pattern: FG + rev (e.g., "132520FTA")
-    let part_pattern = format!("{}{}", fg, rev);
-    
+) -> Result<Vec<Resolved<test_results::Model>>, DbErr> {
     // First, get all matching records
     let results = test_results::Entity::find()
-        .filter(test_results::Column::Part.starts_with(&part_pattern))
-        .filter(
-            Condition::any()
-                .add(test_results::Column::SerialNum.between(
-                    start_serial.to_string(),
-                    end_serial.to_string(),
-                ))
-                .add(Expr::cust(format!(
-                    "CAST(serial_num AS INTEGER) BETWEEN {} AND {}",
-                    start_serial, end_serial
-                )))
-        )
-        .filter(test_results::Column::PassFail.eq("Pass"))
-        .order_by_desc(test_results::Column::CreatedAt) // Most recent first
+        .filter(test_results::Column::Part.is_in(parts.iter().cloned()))
+        .filter(serials.condition(test_results::Column::SerialNum))
         .all(db)
         .await?;
 
-    // Deduplicate by serial_num, keeping only the most recent (first occurrence)
-    let mut seen_serials = std::collections::HashSet::new();
-    let deduplicated: Vec<test_results::Model> = results
-        .into_iter()
-        .filter(|r| seen_serials.insert(r.serial_num.clone()))
-        .collect();
-
-    // Sort by serial number for consistent output
-    let mut sorted : Vec<test_results::Model> = deduplicated;
-    sorted.sort_by(|a, b| {
-        let a_num: i32 = a.serial_num.parse().unwrap_or(0);
-        let b_num: i32 = b.serial_num.parse().unwrap_or(0);
-        a_num.cmp(&b_num)
-    });
-
-    Ok(sorted)
+    let mut resolved = resolve(
+        results,
+        policy,
+        |r| serials.label(&r.serial_num),
+        |r| r.pass_fail.eq_ignore_ascii_case("pass"),
+        voltech_attempt_order,
+    );
+
+    // Sort in spec order for consistent output; attempts stay oldest first
+    let order = serial_order(serials);
+    resolved.sort_by_key(|r| serials.label(&r.record.serial_num).and_then(|sn| order.get(&sn).copied()));
+
+    Ok(resolved)
 }
 
 #[cfg(test)]
@@ -79,14 +78,14 @@ mod tests {
 
         // Test with your actual data
         let results = query_serialized(
-            "132520",    // fg
-            "FTA",       // rev
-            11664,       // start_serial
-            11667,       // end_serial
+            &["132520FTA".to_string()], // FG 132520 rev FTA
+            &SerialSpec::parse("11664-11667").unwrap(),
+            RetestPolicy::LatestPass,
             &db,
         )
         .await
         .expect("Query failed");
+        let results: Vec<_> = results.into_iter().map(|r| r.record).collect();
 
         println!("Found {} results", results.len());
         
@@ -124,14 +123,14 @@ mod tests {
             .expect("Failed to connect to database");
 
         let results = query_serialized(
-            "MTPL-2013-0023L", // fg
-            "FTA",             // rev (common for this FG)
-            26,                 // start_serial
-            95,                 // end_serial
+            &["MTPL-2013-0023LFTA".to_string()], // rev FTA is common for this FG
+            &SerialSpec::parse("26-95").unwrap(),
+            RetestPolicy::LatestPass,
             &db,
         )
         .await
         .expect("Query failed");
+        let results: Vec<_> = results.into_iter().map(|r| r.record).collect();
 
         println!("Found {} results for MTPL-2013-0023L 26-95", results.len());
         for result in results.iter() {
@@ -197,10 +196,10 @@ mod tests {
             .await
             .expect("Failed to connect to database");
 
-        let results = query_serialized("MTPL-2013-0023L", "FTA", 26, 26, &db).await.expect("Query failed");
+        let results = query_serialized(&["MTPL-2013-0023LFTA".to_string()], &SerialSpec::parse("26").unwrap(), RetestPolicy::LatestPass, &db).await.expect("Query failed");
         assert!(!results.is_empty(), "Expected at least one result for SN 26");
 
-        let first = &results[0];
+        let first = &results[0].record;
         let map: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&first.measurements).expect("Failed to parse measurements");
         let normalized: HashMap<String, &serde_json::Value> = map.iter().map(|(k,v)| (normalize_spaces(k), v)).collect();
 
@@ -217,14 +216,14 @@ mod tests {
 
         // Test with your actual data
         let results = query_manual_serialized(
-            "132520",    // fg
-            "A",       // rev
-            11664,       // start_serial
-            11667,       // end_serial
+            &["132520".to_string()], // fg
+            &SerialSpec::parse("11664-11667").unwrap(),
+            RetestPolicy::LatestPass,
             &db,
         )
         .await
         .expect("Query failed");
+        let results: Vec<_> = results.into_iter().map(|r| r.record).collect();
 
         println!("Found {} results", results.len());
         
@@ -256,53 +255,33 @@ mod tests {
 }
 
 /// # Query Test Results
-/// - `Full Test Name`, `Serial Number Range`, `Passed`
-/// - Deduplicates serial numbers and keeps the most recent test
+/// - `Manual FG Numbers` (see `part_mapping`), `Serial Number Specification`, `Retest Policy`
+/// - Groups attempts by serial and test, and keeps the ones the policy selects
 pub async fn query_manual_serialized(
-    fg: &str, 
-    rev: &str, 
-    start_serial: i32, 
-    end_serial: i32, 
+    parts: &[String],
+    serials: &SerialSpec,
+    policy: RetestPolicy,
     db: &DbConn,
-) -> Result<Vec<manual_test_results::Model>, DbErr> {
-    // Build the part pattern: FG + rev (e.g., "132520FTA")
-    let part_pattern = format!("{}", fg);
-
-    // FIXME : Sort by date, not createdAt
-    
+) -> Result<Vec<Resolved<manual_test_results::Model>>, DbErr> {
     // First, get all matching records
     let results = manual_test_results::Entity::find()
-        .filter(manual_test_results::Column::Fg.starts_with(&part_pattern))
-        .filter(
-            Condition::any()
-                .add(manual_test_results::Column::Sn.between(
-                    start_serial.to_string(),
-                    end_serial.to_string(),
-                ))
-                .add(Expr::cust(format!(
-                    "CAST(sn AS INTEGER) BETWEEN {} AND {}",
-                    start_serial, end_serial
-                )))
-        )
-        .filter(manual_test_results::Column::Passfail.eq("PASS"))
-        .order_by_desc(manual_test_results::Column::CreatedAt) // Most recent first
+        .filter(manual_test_results::Column::Fg.is_in(parts.iter().cloned()))
+        .filter(serials.condition(manual_test_results::Column::Sn))
         .all(db)
         .await?;
 
-    // Deduplicate by serial_num, keeping only the most recent (first occurrence)
-    let mut seen_serials = std::collections::HashSet::new();
-    let deduplicated: Vec<manual_test_results::Model> = results
-        .into_iter()
-        .filter(|r| seen_serials.insert(r.sn.clone()))
-        .collect();
-
-    // Sort by serial number for consistent output
-    let mut sorted : Vec<manual_test_results::Model> = deduplicated;
-    sorted.sort_by(|a, b| {
-        let a_num: i32 = a.sn.parse().unwrap_or(0);
-        let b_num: i32 = b.sn.parse().unwrap_or(0);
-        a_num.cmp(&b_num)
-    });
-
-    Ok(sorted)
+    // Each manual test of a serial is retested on its own
+    let mut resolved = resolve(
+        results,
+        policy,
+        |r| serials.label(&r.sn).map(|sn| format!("{}|{}", sn, r.test)),
+        |r| manual_verdict(r).eq_ignore_ascii_case("pass"),
+        manual_attempt_order,
+    );
+
+    // Sort in spec order for consistent output; attempts stay oldest first
+    let order = serial_order(serials);
+    resolved.sort_by_key(|r| serials.label(&r.record.sn).and_then(|sn| order.get(&sn).copied()));
+
+    Ok(resolved)
 }
\ No newline at end of file
@@ -0,0 +1,245 @@
+use crate::dates::combine_date_time;
+use chrono::NaiveDateTime;
+use entity_manual::manual_test_results;
+use entity_voltech::test_results as voltech_test_results;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+
+// ============================================================================
+// Test Sessions
+// ============================================================================
+//
+// A session is one block of a results file: the records written under one
+// part / batch / operator header on one test date. Two sessions on the same
+// day for the same batch (another operator, another tester) stay apart.
+//
+// Session ids are derived from the file path and the block header, so they
+// are stable across re-imports of the same file and can be stored in report
+// parameters. Batch-mode reports can include or exclude sessions by id, on
+// top of (or instead of) whole days.
+// ============================================================================
+
+/// Sessions a batch-mode report includes or excludes by id
+/// A non-empty `include` replaces the date selection
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionSelection {
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+impl SessionSelection {
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Whether records of the session belong in the report
+    pub fn keeps(&self, session_id: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|id| id == session_id))
+            && !self.exclude.iter().any(|id| id == session_id)
+    }
+}
+
+/// The block of a results file a record was read from
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionBlock<'r> {
+    pub source: &'static str, // "voltech" or "manual"
+    pub file_path: &'r str,
+    pub part: &'r str,
+    pub batch: &'r str,
+    pub operator: &'r str,
+    pub date: &'r str, // Date as written in the file
+}
+
+impl<'r> SessionBlock<'r> {
+    pub fn voltech(record: &'r voltech_test_results::Model) -> Self {
+        SessionBlock {
+            source: "voltech",
+            file_path: &record.file_path,
+            part: &record.part,
+            batch: &record.batch,
+            operator: &record.operator,
+            date: &record.date,
+        }
+    }
+
+    pub fn manual(record: &'r manual_test_results::Model) -> Self {
+        SessionBlock {
+            source: "manual",
+            file_path: &record.file_path,
+            part: &record.fg,
+            batch: &record.batch,
+            operator: &record.operator,
+            date: &record.date,
+        }
+    }
+
+    /// Stable session id: source prefix and a hash of the file and block header
+    pub fn id(&self) -> String {
+        let key = [
+            self.file_path,
+            self.part,
+            self.batch,
+            self.operator,
+            self.date,
+        ]
+        .join("\n");
+        let digest = Sha256::digest(key.as_bytes());
+        let hash: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+        format!("{}-{}", &self.source[..1], hash)
+    }
+
+    /// Voltech testers export into their own folder; the folder names the tester
+    fn tester(&self) -> Option<String> {
+        if self.source != "voltech" {
+            return None;
+        }
+        Path::new(self.file_path)
+            .parent()
+            .and_then(|dir| dir.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+    }
+}
+
+/// Represents an available test session (for batch mode)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AvailableSession {
+    pub id: String,
+    pub source: String,
+    pub date: String, // NaiveDate as string "YYYY-MM-DD"; the raw date if it did not parse
+    pub batch: String,
+    pub part: String,
+    pub operator: String,
+    pub tester: Option<String>,
+    pub file_path: String,
+    pub started_at: Option<String>, // "YYYY-MM-DD HH:MM:SS"; None when no record has a usable time
+    pub ended_at: Option<String>,
+    pub record_count: i32,
+}
+
+/// Group records into sessions, newest first
+fn group_sessions<'r, T>(
+    records: &'r [T],
+    block: impl Fn(&'r T) -> SessionBlock<'r>,
+    date: impl Fn(&T) -> String,
+    tested_at: impl Fn(&T) -> Option<NaiveDateTime>,
+) -> Vec<AvailableSession> {
+    let mut sessions: Vec<AvailableSession> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut spans: Vec<Option<(NaiveDateTime, NaiveDateTime)>> = Vec::new();
+
+    for record in records {
+        let block = block(record);
+        let id = block.id();
+        let slot = *index.entry(id.clone()).or_insert_with(|| {
+            sessions.push(AvailableSession {
+                id,
+                source: block.source.to_string(),
+                date: date(record),
+                batch: block.batch.to_string(),
+                part: block.part.to_string(),
+                operator: block.operator.to_string(),
+                tester: block.tester(),
+                file_path: block.file_path.to_string(),
+                started_at: None,
+                ended_at: None,
+                record_count: 0,
+            });
+            spans.push(None);
+            sessions.len() - 1
+        });
+
+        sessions[slot].record_count += 1;
+        if let Some(at) = tested_at(record) {
+            let span = spans[slot].get_or_insert((at, at));
+            span.0 = span.0.min(at);
+            span.1 = span.1.max(at);
+        }
+    }
+
+    for (session, span) in sessions.iter_mut().zip(spans) {
+        if let Some((start, end)) = span {
+            session.started_at = Some(start.format("%Y-%m-%d %H:%M:%S").to_string());
+            session.ended_at = Some(end.format("%Y-%m-%d %H:%M:%S").to_string());
+        }
+    }
+
+    // Sort by date descending, then by start time
+    sessions.sort_by(|a, b| {
+        b.date
+            .cmp(&a.date)
+            .then_with(|| a.started_at.cmp(&b.started_at))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    sessions
+}
+
+/// Voltech records grouped into sessions
+pub fn voltech_sessions(records: &[voltech_test_results::Model]) -> Vec<AvailableSession> {
+    group_sessions(
+        records,
+        SessionBlock::voltech,
+        |r| {
+            r.normalized_date
+                .map(|d| d.to_string())
+                .unwrap_or_else(|| r.date.clone())
+        },
+        |r| {
+            let date = r.normalized_date?;
+            combine_date_time(date, r.time.as_deref()?)
+        },
+    )
+}
+
+/// Manual records grouped into sessions
+pub fn manual_sessions(records: &[manual_test_results::Model]) -> Vec<AvailableSession> {
+    group_sessions(
+        records,
+        SessionBlock::manual,
+        |r| r.normalized_date.to_string(),
+        |r| r.tested_at,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block<'a>(operator: &'a str, file_path: &'a str) -> SessionBlock<'a> {
+        SessionBlock {
+            source: "voltech",
+            file_path,
+            part: "FG1001A",
+            batch: "B1",
+            operator,
+            date: "19-11-25",
+        }
+    }
+
+    #[test]
+    fn test_session_ids_split_operators_and_files() {
+        let first = block("AB", "C:/results/AT3600-1/run.txt");
+        assert_eq!(first.id(), block("AB", "C:/results/AT3600-1/run.txt").id());
+        assert_ne!(first.id(), block("CD", "C:/results/AT3600-1/run.txt").id());
+        assert_ne!(first.id(), block("AB", "C:/results/AT3600-2/run.txt").id());
+        assert!(first.id().starts_with("v-"));
+        assert_eq!(first.tester().as_deref(), Some("AT3600-1"));
+    }
+
+    #[test]
+    fn test_selection_includes_and_excludes() {
+        let all = SessionSelection::default();
+        assert!(all.keeps("v-1"));
+
+        let include = SessionSelection {
+            include: vec!["v-1".to_string(), "v-2".to_string()],
+            exclude: vec!["v-2".to_string()],
+        };
+        assert!(include.keeps("v-1"));
+        assert!(!include.keeps("v-2"));
+        assert!(!include.keeps("v-3"));
+    }
+}
@@ -0,0 +1,762 @@
+// Immutable snapshots of generated reports
+//
+// Every generated report is stored with the parameters, collected data, spec
+// rows and template it was built from, plus the exact output bytes. Snapshots
+// can be downloaded byte-for-byte, diffed against each other, and re-rendered
+// without touching live data.
+use crate::reports::collector::{RawRecords, RecordSelection, ReportData};
+use crate::reports::sessions::SessionSelection;
+use crate::reports::template::ReportTemplate;
+use crate::reports::workbook::{generate_workbook, WorkbookExtras, WorkbookSheets};
+use crate::reports::{pdf, write_report_file};
+use crate::voltech::commands::get_current_username;
+use crate::AppState;
+use ::entity::test as test_entity;
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use tauri::State;
+
+pub const FORMAT_XLSX: &str = "xlsx";
+pub const FORMAT_PDF: &str = "pdf";
+/// BA workbooks and CoCs are stored for download and diffing but are built
+/// from live records by their own layouts, so they are never re-rendered
+pub const FORMAT_BA_XLSX: &str = "ba_xlsx";
+pub const FORMAT_COC_PDF: &str = "coc_pdf";
+
+/// Spec columns that change on every save and are not part of the spec
+const IGNORED_SPEC_FIELDS: [&str; 3] = ["created_at", "updated_at", "added_by"];
+
+/// Parameters a report was generated with
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReportParameters {
+    pub report_id: i32,
+    pub batch: Option<String>,
+    pub serial_range: Option<String>,
+    pub selected_dates: Option<Vec<String>>,
+    #[serde(default)]
+    pub sessions: SessionSelection,
+    pub template_id: Option<i32>,
+    #[serde(default)]
+    pub sheets: WorkbookSheets,
+}
+
+impl ReportParameters {
+    /// The records the report is built from
+    pub fn selection(&self) -> RecordSelection {
+        RecordSelection {
+            batch: self.batch.clone(),
+            serial_range: self.serial_range.clone(),
+            selected_dates: self.selected_dates.clone(),
+            sessions: self.sessions.clone(),
+        }
+    }
+}
+
+/// Snapshot listing row, without the stored data or file
+#[derive(Debug, Clone, Serialize, FromQueryResult)]
+pub struct ReportSnapshotSummary {
+    pub id: i32,
+    pub report_id: i32,
+    pub fg_id: i32,
+    pub format: String,
+    pub parameters: String,
+    pub file_hash: String,
+    pub file_size: i64,
+    pub generated_by: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, FromQueryResult)]
+struct SnapshotRow {
+    id: i32,
+    report_id: i32,
+    format: String,
+    parameters: String,
+    report_data: String,
+    spec_rows: String,
+    template: String,
+    raw_records: Option<String>,
+    file_hash: String,
+    generated_by: String,
+    created_at: String,
+}
+
+#[derive(Debug, Clone, FromQueryResult)]
+struct SnapshotFileRow {
+    format: String,
+    file_hash: String,
+    file_bytes: Vec<u8>,
+}
+
+/// Everything a snapshot was generated from
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReportSnapshot {
+    pub id: i32,
+    pub report_id: i32,
+    pub format: String,
+    pub parameters: ReportParameters,
+    pub report_data: ReportData,
+    pub spec_rows: Vec<Value>,
+    pub template: ReportTemplate,
+    pub raw_records: Option<RawRecords>,
+    pub file_hash: String,
+    pub generated_by: String,
+    pub created_at: String,
+}
+
+/// A changed parameter or spec field
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FieldChange {
+    pub item: String,
+    pub field: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// A result row that was added, removed or changed between snapshots
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ResultChange {
+    pub test_name: String,
+    pub key: String,
+    pub change: String, // "added", "removed" or "changed"
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotDiff {
+    pub from_id: i32,
+    pub to_id: i32,
+    pub identical_output: bool,
+    pub parameter_changes: Vec<FieldChange>,
+    pub spec_changes: Vec<FieldChange>,
+    pub result_changes: Vec<ResultChange>,
+}
+
+/// Lowercase hex SHA-256 of a file
+pub fn file_hash(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string(value).map_err(|e| format!("Failed to serialize snapshot: {}", e))
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(json: &str, what: &str) -> Result<T, String> {
+    serde_json::from_str(json).map_err(|e| format!("Invalid snapshot {}: {}", what, e))
+}
+
+/// What a generated report was built from
+pub struct SnapshotInput<'a> {
+    pub format: &'a str,
+    pub parameters: &'a ReportParameters,
+    pub data: &'a ReportData,
+    pub template: &'a ReportTemplate,
+    pub spec_rows: &'a [test_entity::Model],
+    pub raw: Option<&'a RawRecords>,
+    pub bytes: &'a [u8],
+}
+
+/// Current test rows of a report, in report order
+pub async fn load_spec_rows(
+    db: &DbConn,
+    report_id: i32,
+) -> Result<Vec<test_entity::Model>, String> {
+    test_entity::Entity::find()
+        .filter(test_entity::Column::ReportId.eq(report_id))
+        .order_by_asc(test_entity::Column::Order)
+        .all(db)
+        .await
+        .map_err(|e| format!("Failed to load spec rows: {}", e))
+}
+
+/// Store a generated report and return the snapshot id
+pub async fn record_snapshot(db: &DbConn, input: SnapshotInput<'_>) -> Result<i32, String> {
+    let generated_by = get_current_username()?;
+    let raw_records = input.raw.map(to_json).transpose()?;
+
+    let result = db
+        .execute_raw(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            r#"
+            INSERT INTO report_snapshots
+                (report_id, fg_id, format, parameters, report_data, spec_rows, template,
+                 raw_records, file_hash, file_bytes, generated_by)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            [
+                input.parameters.report_id.into(),
+                input.data.fg_id.into(),
+                input.format.into(),
+                to_json(input.parameters)?.into(),
+                to_json(input.data)?.into(),
+                to_json(&input.spec_rows)?.into(),
+                input.template.to_json()?.into(),
+                raw_records.into(),
+                file_hash(input.bytes).into(),
+                input.bytes.to_vec().into(),
+                generated_by.into(),
+            ],
+        ))
+        .await
+        .map_err(|e| format!("Failed to store report snapshot: {}", e))?;
+
+    Ok(result.last_insert_id() as i32)
+}
+
+/// List snapshots, newest first, optionally for one report
+pub async fn list_snapshots(
+    db: &DbConn,
+    report_id: Option<i32>,
+) -> Result<Vec<ReportSnapshotSummary>, DbErr> {
+    ReportSnapshotSummary::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        r#"
+        SELECT id, report_id, fg_id, format, parameters, file_hash,
+               length(file_bytes) as file_size, generated_by,
+               CAST(created_at AS TEXT) as created_at
+        FROM report_snapshots
+        WHERE ?1 IS NULL OR report_id = ?1
+        ORDER BY created_at DESC, id DESC
+        "#,
+        [report_id.into()],
+    ))
+    .all(db)
+    .await
+}
+
+/// Load a snapshot's stored inputs
+pub async fn load_snapshot(db: &DbConn, id: i32) -> Result<ReportSnapshot, String> {
+    let row = SnapshotRow::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        r#"
+        SELECT id, report_id, format, parameters, report_data, spec_rows, template,
+               raw_records, file_hash, generated_by, CAST(created_at AS TEXT) as created_at
+        FROM report_snapshots
+        WHERE id = ?
+        "#,
+        [id.into()],
+    ))
+    .one(db)
+    .await
+    .map_err(|e| format!("Failed to load report snapshot: {}", e))?
+    .ok_or_else(|| format!("Report snapshot {} not found", id))?;
+
+    Ok(ReportSnapshot {
+        id: row.id,
+        report_id: row.report_id,
+        format: row.format,
+        parameters: from_json(&row.parameters, "parameters")?,
+        report_data: from_json(&row.report_data, "report data")?,
+        spec_rows: from_json(&row.spec_rows, "spec rows")?,
+        template: ReportTemplate::from_json(&row.template)?,
+        raw_records: row
+            .raw_records
+            .as_deref()
+            .map(|json| from_json(json, "raw records"))
+            .transpose()?,
+        file_hash: row.file_hash,
+        generated_by: row.generated_by,
+        created_at: row.created_at,
+    })
+}
+
+/// Load a snapshot's stored file, checking it against the recorded hash
+pub async fn load_snapshot_file(db: &DbConn, id: i32) -> Result<(String, Vec<u8>), String> {
+    let row = SnapshotFileRow::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT format, file_hash, file_bytes FROM report_snapshots WHERE id = ?",
+        [id.into()],
+    ))
+    .one(db)
+    .await
+    .map_err(|e| format!("Failed to load report snapshot: {}", e))?
+    .ok_or_else(|| format!("Report snapshot {} not found", id))?;
+
+    if file_hash(&row.file_bytes) != row.file_hash {
+        return Err(format!(
+            "Report snapshot {} is corrupt: file hash does not match",
+            id
+        ));
+    }
+
+    Ok((row.format, row.file_bytes))
+}
+
+/// Re-render a snapshot from its stored data and template
+pub fn render_snapshot(snapshot: &ReportSnapshot) -> Result<Vec<u8>, String> {
+    match snapshot.format.as_str() {
+        FORMAT_PDF => return pdf::generate_report(&snapshot.report_data, &snapshot.template),
+        FORMAT_XLSX => {}
+        FORMAT_BA_XLSX | FORMAT_COC_PDF => {
+            return Err(format!(
+                "Report snapshot {} ({}) cannot be re-rendered; download the stored file instead",
+                snapshot.id, snapshot.format
+            ))
+        }
+        other => return Err(format!("Unknown report snapshot format: {}", other)),
+    }
+
+    // Spec rows are kept as JSON so diffs survive schema changes; the spec
+    // sheet needs them typed again
+    let spec_rows: Vec<test_entity::Model> = if snapshot.parameters.sheets.spec {
+        snapshot
+            .spec_rows
+            .iter()
+            .map(|row| serde_json::from_value(row.clone()))
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("Invalid snapshot spec rows: {}", e))?
+    } else {
+        Vec::new()
+    };
+    let empty = RawRecords::default();
+
+    generate_workbook(
+        &snapshot.report_data,
+        &snapshot.template,
+        &WorkbookExtras {
+            sheets: &snapshot.parameters.sheets,
+            raw: snapshot.raw_records.as_ref().unwrap_or(&empty),
+            spec_rows: &spec_rows,
+        },
+    )
+    .map_err(|e| format!("Failed to generate Excel report: {}", e))
+}
+
+// ============================================================================
+// Diffing
+// ============================================================================
+
+fn value_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+fn optional_text<T: Serialize>(value: &T) -> Option<String> {
+    serde_json::to_value(value)
+        .ok()
+        .as_ref()
+        .and_then(value_text)
+}
+
+fn diff_parameters(from: &ReportParameters, to: &ReportParameters) -> Vec<FieldChange> {
+    let fields = [
+        (
+            "batch",
+            optional_text(&from.batch),
+            optional_text(&to.batch),
+        ),
+        (
+            "serial_range",
+            optional_text(&from.serial_range),
+            optional_text(&to.serial_range),
+        ),
+        (
+            "selected_dates",
+            optional_text(&from.selected_dates),
+            optional_text(&to.selected_dates),
+        ),
+        (
+            "sessions",
+            optional_text(&from.sessions),
+            optional_text(&to.sessions),
+        ),
+        (
+            "template_id",
+            optional_text(&from.template_id),
+            optional_text(&to.template_id),
+        ),
+        (
+            "sheets",
+            optional_text(&from.sheets),
+            optional_text(&to.sheets),
+        ),
+    ];
+
+    fields
+        .into_iter()
+        .filter(|(_, a, b)| a != b)
+        .map(|(field, from, to)| FieldChange {
+            item: "parameters".to_string(),
+            field: field.to_string(),
+            from,
+            to,
+        })
+        .collect()
+}
+
+fn spec_rows_by_id(rows: &[Value]) -> BTreeMap<i64, &serde_json::Map<String, Value>> {
+    rows.iter()
+        .filter_map(|row| {
+            let obj = row.as_object()?;
+            Some((obj.get("id")?.as_i64()?, obj))
+        })
+        .collect()
+}
+
+fn diff_spec_rows(from: &[Value], to: &[Value]) -> Vec<FieldChange> {
+    let from = spec_rows_by_id(from);
+    let to = spec_rows_by_id(to);
+    let mut changes = Vec::new();
+
+    let label = |obj: &serde_json::Map<String, Value>, id: i64| {
+        obj.get("test_type")
+            .and_then(Value::as_str)
+            .map(|t| format!("{} (#{})", t, id))
+            .unwrap_or_else(|| format!("Test #{}", id))
+    };
+
+    for (id, before) in &from {
+        let Some(after) = to.get(id) else {
+            changes.push(FieldChange {
+                item: label(before, *id),
+                field: "test".to_string(),
+                from: Some("present".to_string()),
+                to: None,
+            });
+            continue;
+        };
+
+        let mut fields: Vec<&String> = before.keys().chain(after.keys()).collect();
+        fields.sort();
+        fields.dedup();
+
+        for field in fields {
+            if IGNORED_SPEC_FIELDS.contains(&field.as_str()) {
+                continue;
+            }
+            let a = before.get(field).and_then(value_text);
+            let b = after.get(field).and_then(value_text);
+            if a != b {
+                changes.push(FieldChange {
+                    item: label(after, *id),
+                    field: field.clone(),
+                    from: a,
+                    to: b,
+                });
+            }
+        }
+    }
+
+    for (id, after) in &to {
+        if !from.contains_key(id) {
+            changes.push(FieldChange {
+                item: label(after, *id),
+                field: "test".to_string(),
+                from: None,
+                to: Some("present".to_string()),
+            });
+        }
+    }
+
+    changes
+}
+
+/// Result rows per test keyed by unit, date and occurrence
+fn results_by_key(data: &ReportData) -> BTreeMap<(String, String), String> {
+    let mut rows = BTreeMap::new();
+
+    for test in &data.test_results {
+        let mut seen: BTreeMap<String, usize> = BTreeMap::new();
+        for result in &test.results {
+            let unit = result
+                .serial_number
+                .clone()
+                .unwrap_or_else(|| result.batch.clone());
+            let base = format!("{} @ {}", unit, result.date);
+            let occurrence = seen.entry(base.clone()).or_insert(0);
+            *occurrence += 1;
+
+            let key = if *occurrence == 1 {
+                base
+            } else {
+                format!("{} #{}", base, occurrence)
+            };
+            rows.insert(
+                (test.test_name.clone(), key),
+                format!("{} {}", result.result, result.measurements),
+            );
+        }
+    }
+
+    rows
+}
+
+fn diff_results(from: &ReportData, to: &ReportData) -> Vec<ResultChange> {
+    let before = results_by_key(from);
+    let after = results_by_key(to);
+    let mut changes = Vec::new();
+
+    for ((test_name, key), value) in &before {
+        let change = match after.get(&(test_name.clone(), key.clone())) {
+            None => "removed",
+            Some(other) if other != value => "changed",
+            Some(_) => continue,
+        };
+        changes.push(ResultChange {
+            test_name: test_name.clone(),
+            key: key.clone(),
+            change: change.to_string(),
+            from: Some(value.clone()),
+            to: after.get(&(test_name.clone(), key.clone())).cloned(),
+        });
+    }
+
+    for ((test_name, key), value) in &after {
+        if !before.contains_key(&(test_name.clone(), key.clone())) {
+            changes.push(ResultChange {
+                test_name: test_name.clone(),
+                key: key.clone(),
+                change: "added".to_string(),
+                from: None,
+                to: Some(value.clone()),
+            });
+        }
+    }
+
+    changes
+}
+
+/// Compare two snapshots' parameters, spec rows and collected results
+pub fn diff_snapshots(from: &ReportSnapshot, to: &ReportSnapshot) -> SnapshotDiff {
+    SnapshotDiff {
+        from_id: from.id,
+        to_id: to.id,
+        identical_output: from.file_hash == to.file_hash,
+        parameter_changes: diff_parameters(&from.parameters, &to.parameters),
+        spec_changes: diff_spec_rows(&from.spec_rows, &to.spec_rows),
+        result_changes: diff_results(&from.report_data, &to.report_data),
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn list_report_snapshots(
+    report_id: Option<i32>,
+    state: State<'_, AppState>,
+) -> Result<Vec<ReportSnapshotSummary>, String> {
+    list_snapshots(&state.core_db, report_id)
+        .await
+        .map_err(|e| format!("Failed to list report snapshots: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_report_snapshot(
+    id: i32,
+    state: State<'_, AppState>,
+) -> Result<ReportSnapshot, String> {
+    load_snapshot(&state.core_db, id).await
+}
+
+/// Save the stored file of a snapshot, exactly as it was generated
+#[tauri::command]
+pub async fn download_report_snapshot(
+    id: i32,
+    file_path: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let (_, bytes) = load_snapshot_file(&state.core_db, id).await?;
+    write_report_file(&file_path, bytes)
+}
+
+#[tauri::command]
+pub async fn diff_report_snapshots(
+    from_id: i32,
+    to_id: i32,
+    state: State<'_, AppState>,
+) -> Result<SnapshotDiff, String> {
+    let from = load_snapshot(&state.core_db, from_id).await?;
+    let to = load_snapshot(&state.core_db, to_id).await?;
+    Ok(diff_snapshots(&from, &to))
+}
+
+/// Re-render a snapshot from its stored data rather than live data
+#[tauri::command]
+pub async fn regenerate_report_snapshot(
+    id: i32,
+    file_path: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<u8>, String> {
+    let snapshot = load_snapshot(&state.core_db, id).await?;
+    let bytes = render_snapshot(&snapshot)?;
+
+    if let Some(path) = file_path {
+        write_report_file(&path, bytes.clone())?;
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reports::collector::{SingleTestResult, TestResultData};
+    use crate::reports::retest::RetestPolicy;
+    use crate::reports::template::default_template;
+
+    fn snapshot(id: i32, results: Vec<(&str, &str, &str)>, spec_max: f64) -> ReportSnapshot {
+        let report_data = ReportData {
+            report_id: 1,
+            fg_id: 1,
+            fg_number: "132520".to_string(),
+            fg_revision: "FTA".to_string(),
+            fg_customer: "ACME".to_string(),
+            is_serialized: true,
+            batch: None,
+            serial_range: Some("1001-1002".to_string()),
+            retest_policy: RetestPolicy::default(),
+            test_results: vec![TestResultData {
+                test_id: 7,
+                test_name: "DCR".to_string(),
+                source_type: "voltech".to_string(),
+                associated_test: None,
+                spec_min: Some(1.0),
+                spec_max: Some(spec_max),
+                spec_unit: Some("Ohm".to_string()),
+                results: results
+                    .into_iter()
+                    .map(|(serial, date, verdict)| SingleTestResult {
+                        serial_number: Some(serial.to_string()),
+                        batch: "B1".to_string(),
+                        date: date.to_string(),
+                        result: verdict.to_string(),
+                        measurements: serde_json::json!({ "R": 1.5 }),
+                        retests: 0,
+                    })
+                    .collect(),
+            }],
+        };
+
+        ReportSnapshot {
+            id,
+            report_id: 1,
+            format: FORMAT_XLSX.to_string(),
+            parameters: ReportParameters {
+                report_id: 1,
+                batch: None,
+                serial_range: Some("1001-1002".to_string()),
+                selected_dates: None,
+                sessions: SessionSelection::default(),
+                template_id: None,
+                sheets: WorkbookSheets::default(),
+            },
+            report_data,
+            spec_rows: vec![serde_json::json!({
+                "id": 7,
+                "test_type": "DCR",
+                "minimum": 1.0,
+                "maximum": spec_max,
+                "updated_at": format!("2025-11-{:02}", id),
+            })],
+            template: default_template(),
+            raw_records: None,
+            file_hash: format!("hash{}", id),
+            generated_by: "tester".to_string(),
+            created_at: "2025-11-20".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_file_hash() {
+        assert_eq!(
+            file_hash(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_diff_finds_retest_and_spec_edit() {
+        let shipped = snapshot(1, vec![("1001", "2025-11-18", "FAIL")], 2.0);
+        let later = snapshot(
+            2,
+            vec![
+                ("1001", "2025-11-18", "FAIL"),
+                ("1001", "2025-11-19", "PASS"),
+            ],
+            2.5,
+        );
+
+        let diff = diff_snapshots(&shipped, &later);
+        assert!(!diff.identical_output);
+        assert!(diff.parameter_changes.is_empty());
+
+        // updated_at is ignored; only the limit change is reported
+        assert_eq!(diff.spec_changes.len(), 1);
+        assert_eq!(diff.spec_changes[0].field, "maximum");
+        assert_eq!(diff.spec_changes[0].item, "DCR (#7)");
+
+        assert_eq!(diff.result_changes.len(), 1);
+        assert_eq!(diff.result_changes[0].change, "added");
+        assert_eq!(diff.result_changes[0].key, "1001 @ 2025-11-19");
+    }
+
+    #[test]
+    fn test_snapshot_data_round_trips() {
+        let original = snapshot(1, vec![("1001", "2025-11-18", "PASS")], 2.0);
+        let json = serde_json::to_string(&original.report_data).unwrap();
+        let restored: ReportData = serde_json::from_str(&json).unwrap();
+
+        let copy = ReportSnapshot {
+            report_data: restored,
+            ..snapshot(1, vec![], 2.0)
+        };
+        assert!(diff_results(&original.report_data, &copy.report_data).is_empty());
+        assert!(render_snapshot(&copy).is_ok());
+    }
+
+    #[test]
+    fn test_live_layouts_are_not_re_rendered() {
+        for format in [FORMAT_BA_XLSX, FORMAT_COC_PDF] {
+            let stored = ReportSnapshot {
+                format: format.to_string(),
+                ..snapshot(3, vec![("1001", "2025-11-18", "PASS")], 2.0)
+            };
+            let err = render_snapshot(&stored).unwrap_err();
+            assert!(err.contains("download the stored file"), "{}", err);
+        }
+
+        let unknown = ReportSnapshot {
+            format: "docx".to_string(),
+            ..snapshot(4, vec![], 2.0)
+        };
+        assert!(render_snapshot(&unknown).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stored_snapshots_cannot_change() {
+        use migration::{Migrator, MigratorTrait};
+
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        Migrator::up(&db, None).await.unwrap();
+        db.execute_unprepared(
+            "INSERT INTO report_snapshots
+                (report_id, fg_id, format, parameters, report_data, spec_rows, template,
+                 file_hash, file_bytes, generated_by)
+             VALUES (1, 1, 'pdf', '{}', '{}', '[]', '{}', 'hash', x'00', 'tester');",
+        )
+        .await
+        .unwrap();
+
+        assert!(db
+            .execute_unprepared("UPDATE report_snapshots SET file_hash = 'other'")
+            .await
+            .is_err());
+        assert!(db
+            .execute_unprepared("DELETE FROM report_snapshots")
+            .await
+            .is_err());
+        assert_eq!(list_snapshots(&db, Some(1)).await.unwrap().len(), 1);
+    }
+}
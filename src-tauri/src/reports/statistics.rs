@@ -0,0 +1,376 @@
+use crate::reports::collector::TestResultData;
+use rust_xlsxwriter::*;
+use serde::Serialize;
+use serde_json::Value;
+
+// ============================================================================
+// Summary Statistics
+// ============================================================================
+//
+// Min, max, mean, standard deviation and Cpk per measured column, plus
+// pass/fail and out-of-spec counts. Spec limits only apply to the column the
+// test's limits describe: the associated Voltech measurement, or the manual
+// "reading".
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ColumnStats {
+    pub column: String,
+    pub count: usize,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub mean: Option<f64>,
+    pub std_dev: Option<f64>, // Sample standard deviation
+    pub cpk: Option<f64>,
+    pub out_of_spec: usize,
+    pub spec_min: Option<f64>,
+    pub spec_max: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TestStats {
+    pub pass_count: usize,
+    pub fail_count: usize,
+    pub columns: Vec<ColumnStats>,
+}
+
+/// Numbers and numeric strings; anything else is not a reading
+pub fn numeric_value(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+pub fn column_stats(
+    column: &str,
+    values: &[f64],
+    spec_min: Option<f64>,
+    spec_max: Option<f64>,
+) -> ColumnStats {
+    let count = values.len();
+    let min = values.iter().copied().reduce(f64::min);
+    let max = values.iter().copied().reduce(f64::max);
+    let mean = (count > 0).then(|| values.iter().sum::<f64>() / count as f64);
+
+    let std_dev = match (mean, count) {
+        (Some(mean), n) if n > 1 => {
+            let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+            Some(variance.sqrt())
+        }
+        _ => None,
+    };
+
+    // One-sided when only one limit is set
+    let cpk = match (mean, std_dev) {
+        (Some(mean), Some(sd)) if sd > 0.0 => {
+            let upper = spec_max.map(|usl| (usl - mean) / (3.0 * sd));
+            let lower = spec_min.map(|lsl| (mean - lsl) / (3.0 * sd));
+            match (upper, lower) {
+                (Some(u), Some(l)) => Some(u.min(l)),
+                (u, l) => u.or(l),
+            }
+        }
+        _ => None,
+    };
+
+    let out_of_spec = values
+        .iter()
+        .filter(|v| spec_min.is_some_and(|lsl| **v < lsl) || spec_max.is_some_and(|usl| **v > usl))
+        .count();
+
+    ColumnStats {
+        column: column.to_string(),
+        count,
+        min,
+        max,
+        mean,
+        std_dev,
+        cpk,
+        out_of_spec,
+        spec_min,
+        spec_max,
+    }
+}
+
+/// Numeric measurement columns, in the order of the first result
+/// Manual results carry their limits alongside the reading, so only the reading counts
+pub fn measured_columns(test: &TestResultData) -> Vec<String> {
+    let keys: Vec<String> = test
+        .results
+        .first()
+        .and_then(|r| r.measurements.as_object())
+        .map(|obj| obj.keys().cloned().collect())
+        .unwrap_or_default();
+
+    keys.into_iter()
+        .filter(|key| test.source_type != "manual" || key == "reading")
+        .filter(|key| {
+            test.results.iter().any(|r| {
+                r.measurements
+                    .get(key.as_str())
+                    .and_then(numeric_value)
+                    .is_some()
+            })
+        })
+        .collect()
+}
+
+pub fn test_statistics(test: &TestResultData) -> TestStats {
+    let verdicts: Vec<String> = test
+        .results
+        .iter()
+        .map(|r| r.result.to_uppercase())
+        .collect();
+
+    let columns = measured_columns(test)
+        .iter()
+        .map(|column| {
+            let values: Vec<f64> = test
+                .results
+                .iter()
+                .filter_map(|r| r.measurements.get(column.as_str()).and_then(numeric_value))
+                .collect();
+
+            let (spec_min, spec_max) = if test.is_spec_column(column) {
+                (test.spec_min, test.spec_max)
+            } else {
+                (None, None)
+            };
+            column_stats(column, &values, spec_min, spec_max)
+        })
+        .collect();
+
+    TestStats {
+        pass_count: verdicts.iter().filter(|v| v.contains("PASS")).count(),
+        fail_count: verdicts.iter().filter(|v| v.contains("FAIL")).count(),
+        columns,
+    }
+}
+
+// ============================================================================
+// Excel Output
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stat {
+    Count,
+    Min,
+    Max,
+    Mean,
+    StdDev,
+    Cpk,
+    OutOfSpec,
+}
+
+/// Rows of the statistics block, in order
+pub const STATS: [Stat; 7] = [
+    Stat::Count,
+    Stat::Min,
+    Stat::Max,
+    Stat::Mean,
+    Stat::StdDev,
+    Stat::Cpk,
+    Stat::OutOfSpec,
+];
+
+impl Stat {
+    pub fn label(self) -> &'static str {
+        match self {
+            Stat::Count => "Count",
+            Stat::Min => "Min",
+            Stat::Max => "Max",
+            Stat::Mean => "Mean",
+            Stat::StdDev => "Std Dev",
+            Stat::Cpk => "Cpk",
+            Stat::OutOfSpec => "Out of Spec",
+        }
+    }
+
+    pub fn value(self, stats: &ColumnStats) -> Option<f64> {
+        match self {
+            Stat::Count => Some(stats.count as f64),
+            Stat::Min => stats.min,
+            Stat::Max => stats.max,
+            Stat::Mean => stats.mean,
+            Stat::StdDev => stats.std_dev,
+            Stat::Cpk => stats.cpk,
+            Stat::OutOfSpec => Some(stats.out_of_spec as f64),
+        }
+    }
+
+    /// Excel formula over `range` computing the same value
+    pub fn formula(self, range: &str, stats: &ColumnStats) -> Option<String> {
+        let mean = format!("AVERAGE({})", range);
+        let sigma3 = format!("(3*STDEV({}))", range);
+
+        match self {
+            Stat::Count => Some(format!("=COUNT({})", range)),
+            Stat::Min => Some(format!("=MIN({})", range)),
+            Stat::Max => Some(format!("=MAX({})", range)),
+            Stat::Mean => Some(format!("={}", mean)),
+            Stat::StdDev => Some(format!("=STDEV({})", range)),
+            Stat::Cpk => match (stats.spec_min, stats.spec_max) {
+                (Some(lsl), Some(usl)) => Some(format!(
+                    "=MIN(({usl}-{mean})/{sigma3},({mean}-{lsl})/{sigma3})"
+                )),
+                (None, Some(usl)) => Some(format!("=({usl}-{mean})/{sigma3}")),
+                (Some(lsl), None) => Some(format!("=({mean}-{lsl})/{sigma3}")),
+                (None, None) => None,
+            },
+            Stat::OutOfSpec => {
+                let mut parts = Vec::new();
+                if let Some(lsl) = stats.spec_min {
+                    parts.push(format!("COUNTIF({},\"<{}\")", range, lsl));
+                }
+                if let Some(usl) = stats.spec_max {
+                    parts.push(format!("COUNTIF({},\">{}\")", range, usl));
+                }
+                (!parts.is_empty()).then(|| format!("={}", parts.join("+")))
+            }
+        }
+    }
+}
+
+/// Write one statistic as a native number, or as a live formula with its
+/// value cached when `range` is given
+/// Statistics that cannot be computed are written as "N/A"
+pub fn write_stat(
+    worksheet: &mut Worksheet,
+    (row, col): (u32, u16),
+    stat: Stat,
+    stats: &ColumnStats,
+    range: Option<&str>,
+    format: &Format,
+) -> Result<(), XlsxError> {
+    let Some(value) = stat.value(stats) else {
+        worksheet.write_with_format(row, col, "N/A", format)?;
+        return Ok(());
+    };
+
+    match range.and_then(|r| stat.formula(r, stats)) {
+        Some(formula) => {
+            let formula = Formula::new(formula).set_result(value.to_string());
+            worksheet.write_formula_with_format(row, col, formula, format)?;
+        }
+        None => {
+            worksheet.write_with_format(row, col, value, format)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write a pass or fail count, as a COUNTIF over `range` when given
+pub fn write_verdict_count(
+    worksheet: &mut Worksheet,
+    (row, col): (u32, u16),
+    verdict: &str,
+    count: usize,
+    range: Option<&str>,
+    format: &Format,
+) -> Result<(), XlsxError> {
+    match range {
+        Some(range) => {
+            let formula = Formula::new(format!("=COUNTIF({},\"*{}*\")", range, verdict))
+                .set_result(count.to_string());
+            worksheet.write_formula_with_format(row, col, formula, format)?;
+        }
+        None => {
+            worksheet.write_with_format(row, col, count as f64, format)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reports::collector::SingleTestResult;
+
+    fn close(a: Option<f64>, b: f64) -> bool {
+        a.is_some_and(|a| (a - b).abs() < 1e-9)
+    }
+
+    #[test]
+    fn test_column_stats() {
+        let stats = column_stats("R", &[1.0, 2.0, 3.0, 4.0, 5.0], Some(0.0), Some(4.5));
+        assert_eq!(stats.count, 5);
+        assert!(close(stats.min, 1.0));
+        assert!(close(stats.max, 5.0));
+        assert!(close(stats.mean, 3.0));
+        assert!(close(stats.std_dev, 2.5f64.sqrt()));
+        // Upper side is closer: (4.5 - 3) / (3 * 1.5811)
+        assert!(close(stats.cpk, 1.5 / (3.0 * 2.5f64.sqrt())));
+        assert_eq!(stats.out_of_spec, 1);
+    }
+
+    #[test]
+    fn test_column_stats_edge_cases() {
+        let single = column_stats("R", &[2.0], Some(1.0), Some(3.0));
+        assert_eq!(single.std_dev, None);
+        assert_eq!(single.cpk, None);
+
+        let one_sided = column_stats("R", &[1.0, 3.0], None, Some(5.0));
+        assert!(close(one_sided.cpk, 3.0 / (3.0 * 2f64.sqrt())));
+
+        let empty = column_stats("R", &[], Some(1.0), None);
+        assert_eq!(empty.mean, None);
+        assert_eq!(empty.out_of_spec, 0);
+    }
+
+    #[test]
+    fn test_manual_statistics_use_reading_only() {
+        let results = [("PASS", 1.5), ("PASS", 1.8), ("FAIL", 2.4)]
+            .iter()
+            .enumerate()
+            .map(|(i, (verdict, reading))| SingleTestResult {
+                serial_number: Some(format!("{}", 1001 + i)),
+                batch: "B1".to_string(),
+                date: "2025-11-19".to_string(),
+                result: verdict.to_string(),
+                measurements: serde_json::json!({
+                    "minimum": 1.0,
+                    "reading": reading.to_string(),
+                    "maximum": 2.0,
+                    "uom": "mH"
+                }),
+                retests: 0,
+            })
+            .collect();
+
+        let test = TestResultData {
+            test_id: 1,
+            test_name: "Inductance".to_string(),
+            source_type: "manual".to_string(),
+            associated_test: None,
+            spec_min: Some(1.0),
+            spec_max: Some(2.0),
+            spec_unit: Some("mH".to_string()),
+            results,
+        };
+
+        let stats = test_statistics(&test);
+        assert_eq!(stats.pass_count, 2);
+        assert_eq!(stats.fail_count, 1);
+        assert_eq!(stats.columns.len(), 1);
+        assert_eq!(stats.columns[0].column, "reading");
+        assert_eq!(stats.columns[0].out_of_spec, 1);
+    }
+
+    #[test]
+    fn test_formulas() {
+        let stats = column_stats("R", &[1.0, 2.0], Some(0.5), Some(2.5));
+        assert_eq!(
+            Stat::OutOfSpec.formula("E8:E9", &stats).unwrap(),
+            "=COUNTIF(E8:E9,\"<0.5\")+COUNTIF(E8:E9,\">2.5\")"
+        );
+        assert_eq!(
+            Stat::Cpk.formula("E8:E9", &stats).unwrap(),
+            "=MIN((2.5-AVERAGE(E8:E9))/(3*STDEV(E8:E9)),(AVERAGE(E8:E9)-0.5)/(3*STDEV(E8:E9)))"
+        );
+
+        let no_limits = column_stats("F", &[1.0, 2.0], None, None);
+        assert_eq!(Stat::Cpk.formula("F8:F9", &no_limits), None);
+    }
+}
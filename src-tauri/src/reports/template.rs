@@ -1,21 +1,81 @@
+use crate::units::NumberFormat;
 use rust_xlsxwriter::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 // ============================================================================
 // Template Configuration
 // ============================================================================
 //
 // This file defines the layout and styling for Excel reports.
-// Modify these structures to customize the report appearance.
+// Templates are stored as JSON in the `report_templates` table (see
+// `reports::template_store`); `default_template()` is the built-in fallback.
+// Every field has a default, so a stored template only needs the fields it
+// changes.
 // ============================================================================
 
-#[derive(Debug, Clone)]
+/// Colours are stored as "#RRGGBB" strings in template JSON
+mod hex_color {
+    use rust_xlsxwriter::Color;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn to_hex(color: &Color) -> String {
+        match color {
+            Color::RGB(rgb) => format!("#{:06X}", rgb),
+            Color::White => "#FFFFFF".to_string(),
+            Color::Black => "#000000".to_string(),
+            Color::Gray => "#808080".to_string(),
+            Color::Red => "#FF0000".to_string(),
+            Color::Green => "#008000".to_string(),
+            Color::Blue => "#0000FF".to_string(),
+            Color::Yellow => "#FFFF00".to_string(),
+            _ => "#000000".to_string(),
+        }
+    }
+
+    pub fn from_hex(value: &str) -> Result<Color, String> {
+        let hex = value.trim().trim_start_matches('#');
+        if hex.len() != 6 {
+            return Err(format!("Invalid colour '{}', expected #RRGGBB", value));
+        }
+        u32::from_str_radix(hex, 16)
+            .map(Color::RGB)
+            .map_err(|_| format!("Invalid colour '{}', expected #RRGGBB", value))
+    }
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&to_hex(color))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        from_hex(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct StyleConfig {
+    #[serde(with = "hex_color")]
     pub header_color: Color,
+    #[serde(with = "hex_color")]
     pub header_text_color: Color,
+    #[serde(with = "hex_color")]
     pub pass_color: Color,
+    #[serde(with = "hex_color")]
     pub fail_color: Color,
+    #[serde(with = "hex_color")]
     pub spec_header_color: Color,
+    #[serde(with = "hex_color")]
     pub result_header_color: Color,
+
+    // Fonts
+    pub title_font: String,
+    pub title_font_size: f64,
+    pub heading_font: String,
+    pub heading_font_size: f64,
+    pub body_font: String,
+    pub body_font_size: f64,
 }
 
 impl Default for StyleConfig {
@@ -27,28 +87,214 @@ impl Default for StyleConfig {
             fail_color: Color::RGB(0xFFC7CE),          // Light red
             spec_header_color: Color::RGB(0xE7E6E6),   // Light gray
             result_header_color: Color::RGB(0xD9E1F2), // Light blue
+            title_font: "Aptos Display".to_string(),
+            title_font_size: 20.0,
+            heading_font: "Aptos Narrow".to_string(),
+            heading_font_size: 12.0,
+            body_font: "Aptos Narrow".to_string(),
+            body_font_size: 11.0,
+        }
+    }
+}
+
+/// A label/value pair in a report header
+/// Both may contain placeholders such as "{fg_number}" (see `TemplateValues`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeaderField {
+    pub label: String,
+    pub value: String,
+}
+
+impl HeaderField {
+    fn new(label: &str, value: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+/// A cell in the BA info row, spanning `span` columns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InfoCell {
+    pub text: String,
+    pub span: u16,
+}
+
+impl InfoCell {
+    fn new(text: &str, span: u16) -> Self {
+        Self {
+            text: text.to_string(),
+            span,
+        }
+    }
+}
+
+/// A spec row in the BA report: `field` is a test column
+/// ("test_type", "source_type", "voltage", "frequency", "minimum", "maximum",
+/// "uo_m", "primary_pins", "secondary_pins", "shorted_pins" or "description")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpecRow {
+    pub label: String,
+    pub field: String,
+}
+
+impl SpecRow {
+    fn new(label: &str, field: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            field: field.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PageSetup {
+    pub landscape: bool,
+    pub paper_size: u8, // Excel paper size index (0 = printer default, 1 = Letter, 9 = A4)
+    pub margin_left: f64,
+    pub margin_right: f64,
+    pub margin_top: f64,
+    pub margin_bottom: f64,
+    pub fit_to_width: bool, // Ignored by the BA report, which sets its own page breaks
+    pub page_numbers: bool, // "Page X of Y" footer
+}
+
+impl Default for PageSetup {
+    fn default() -> Self {
+        Self {
+            landscape: true,
+            paper_size: 0,
+            margin_left: 0.3,
+            margin_right: 0.3,
+            margin_top: 0.3,
+            margin_bottom: 0.3,
+            fit_to_width: false,
+            page_numbers: true,
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogoConfig {
+    pub path: String,
+    #[serde(default)]
+    pub row: u32,
+    #[serde(default)]
+    pub col: u16,
+    #[serde(default = "default_logo_scale")]
+    pub scale: f64,
+}
+
+fn default_logo_scale() -> f64 {
+    1.0
+}
+
+/// Summary statistics written under each test (`reports::statistics`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatisticsConfig {
+    pub enabled: bool,
+    pub live_formulas: bool, // Excel formulas over the data rows, with cached values
+}
+
+impl Default for StatisticsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            live_formulas: false,
+        }
+    }
+}
+
+/// Layout of the customer BA report (`reports::excel`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BaLayout {
+    pub title: String,
+    pub info_cells: Vec<InfoCell>,
+    pub spec_rows: Vec<SpecRow>,
+    pub serial_label: String,
+    pub columns_per_page: u16, // Includes the row label column
+    pub rows_per_page: u32,    // Serial rows per printed page
+    pub label_column_width: f64,
+    pub data_column_width: f64,
+    pub title_row_height: u16,
+    pub info_row_height: u16,
+}
+
+impl Default for BaLayout {
+    fn default() -> Self {
+        Self {
+            title: "VISHAY HIREL TEST REPORT FG {fg_number} REV {fg_revision}".to_string(),
+            info_cells: vec![
+                InfoCell::new("Customer PN {customer_pn}", 3),
+                InfoCell::new("Job", 1),
+                InfoCell::new("{job_number}", 2),
+                InfoCell::new("Split", 1),
+                InfoCell::new("{split}", 1),
+                InfoCell::new("Date Code", 1),
+                InfoCell::new("{date_code}", 1),
+            ],
+            spec_rows: vec![
+                SpecRow::new("Test", "test_type"),
+                SpecRow::new("Source", "source_type"),
+                SpecRow::new("Level", "voltage"),
+                SpecRow::new("Frequency", "frequency"),
+                SpecRow::new("Minimum", "minimum"),
+                SpecRow::new("Maximum", "maximum"),
+                SpecRow::new("UoM", "uo_m"),
+                SpecRow::new("Pins", "primary_pins"),
+                SpecRow::new("Notes", "description"),
+            ],
+            serial_label: "SN".to_string(),
+            columns_per_page: 10,
+            rows_per_page: 40,
+            label_column_width: 14.0,
+            data_column_width: 12.0,
+            title_row_height: 35,
+            info_row_height: 27,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ReportTemplate {
     // Header section positioning
-    pub fg_label_row: u32,
-    pub fg_label_col: u16,
-    pub revision_label_row: u32,
-    pub customer_label_row: u32,
-    pub batch_serial_label_row: u32,
+    pub header_start_row: u32,
+    pub header_col: u16,
+    pub header_fields: Vec<HeaderField>,
 
     // Test section starting position
     pub test_section_start_row: u32,
     pub test_section_col: u16,
 
+    // Result table columns: "serial", "batch", "date", "result", "retests",
+    // "measurements"
+    pub result_columns: Vec<String>,
+    pub column_widths: Vec<f64>,
+
     // Spacing
     pub rows_between_tests: u32,
     pub spec_table_width: u16,
     pub result_table_max_width: u16,
 
+    // Page, branding and sign-off
+    pub page: PageSetup,
+    pub logo: Option<LogoConfig>,
+    pub signature_block: Vec<String>,
+
+    // Summary statistics
+    pub statistics: StatisticsConfig,
+
+    // Significant digits and notation for readings and limits
+    pub numbers: NumberFormat,
+
+    // Customer BA layout
+    pub ba: BaLayout,
+
     // Styling
     pub style_config: StyleConfig,
 }
@@ -56,32 +302,205 @@ pub struct ReportTemplate {
 impl Default for ReportTemplate {
     fn default() -> Self {
         Self {
-            fg_label_row: 0,
-            fg_label_col: 0,
-            revision_label_row: 1,
-            customer_label_row: 2,
-            batch_serial_label_row: 3,
+            header_start_row: 0,
+            header_col: 0,
+            header_fields: vec![
+                HeaderField::new("FG Number:", "{fg_number}"),
+                HeaderField::new("Revision:", "{fg_revision}"),
+                HeaderField::new("Customer:", "{customer}"),
+                HeaderField::new("{lot_label}", "{lot}"),
+            ],
             test_section_start_row: 5,
             test_section_col: 0,
+            result_columns: vec![
+                "serial".to_string(),
+                "batch".to_string(),
+                "date".to_string(),
+                "result".to_string(),
+                "retests".to_string(),
+                "measurements".to_string(),
+            ],
+            column_widths: vec![15.0, 12.0, 12.0, 12.0],
             rows_between_tests: 3,
             spec_table_width: 6,
             result_table_max_width: 15,
+            page: PageSetup::default(),
+            logo: None,
+            signature_block: Vec::new(),
+            statistics: StatisticsConfig::default(),
+            numbers: NumberFormat::default(),
+            ba: BaLayout::default(),
             style_config: StyleConfig::default(),
         }
     }
 }
 
+impl ReportTemplate {
+    /// Parse a stored template definition
+    pub fn from_json(definition: &str) -> Result<Self, String> {
+        serde_json::from_str(definition).map_err(|e| format!("Invalid report template: {}", e))
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize report template: {}", e))
+    }
+
+    /// Check settings that serde accepts but the renderers cannot lay out
+    pub fn validate(&self) -> Result<(), String> {
+        let info_width: u32 = self
+            .ba
+            .info_cells
+            .iter()
+            .map(|c| c.span.max(1) as u32)
+            .sum();
+        if info_width > self.ba.columns_per_page as u32 {
+            return Err(format!(
+                "BA info cells span {} columns but a page has only {}",
+                info_width, self.ba.columns_per_page
+            ));
+        }
+        Ok(())
+    }
+}
+
 /// Get the default template
-/// Modify this function to change the default report layout
+/// Used when no stored template matches the FG or customer
 pub fn default_template() -> ReportTemplate {
     ReportTemplate::default()
 }
 
+/// RGB components of a template colour, for renderers other than Excel
+pub fn color_to_rgb(color: &Color) -> (u8, u8, u8) {
+    let hex = hex_color::to_hex(color);
+    let value = u32::from_str_radix(&hex[1..], 16).unwrap_or(0);
+    ((value >> 16) as u8, (value >> 8) as u8, value as u8)
+}
+
+/// Values substituted into template text
+/// Placeholders are written as "{name}"; unknown placeholders are left as-is
+#[derive(Debug, Clone, Default)]
+pub struct TemplateValues {
+    values: HashMap<String, String>,
+}
+
+impl TemplateValues {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, key: &str, value: impl Into<String>) -> &mut Self {
+        self.values.insert(key.to_string(), value.into());
+        self
+    }
+
+    pub fn render(&self, text: &str) -> String {
+        let mut rendered = text.to_string();
+        for (key, value) in &self.values {
+            rendered = rendered.replace(&format!("{{{}}}", key), value);
+        }
+        rendered
+    }
+}
+
+/// Apply page setup to a worksheet
+pub fn apply_page_setup(worksheet: &mut Worksheet, page: &PageSetup) {
+    if page.landscape {
+        worksheet.set_landscape();
+    } else {
+        worksheet.set_portrait();
+    }
+
+    if page.paper_size > 0 {
+        worksheet.set_paper_size(page.paper_size);
+    }
+
+    // Header and footer sit inside the top and bottom margins
+    worksheet.set_margins(
+        page.margin_left,
+        page.margin_right,
+        page.margin_top,
+        page.margin_bottom,
+        page.margin_top / 2.0,
+        page.margin_bottom / 2.0,
+    );
+
+    if page.fit_to_width {
+        worksheet.set_print_fit_to_pages(1, 0);
+    }
+
+    if page.page_numbers {
+        worksheet.set_footer("&CPage &P of &N");
+    }
+}
+
+/// Insert the template logo, if one is configured
+/// `col_offset` shifts the logo onto later BA pages
+pub fn insert_logo(
+    worksheet: &mut Worksheet,
+    logo: Option<&LogoConfig>,
+    col_offset: u16,
+) -> Result<(), XlsxError> {
+    if let Some(logo) = logo {
+        let image = Image::new(&logo.path)?
+            .set_scale_width(logo.scale)
+            .set_scale_height(logo.scale);
+        worksheet.insert_image(logo.row, logo.col + col_offset, &image)?;
+    }
+    Ok(())
+}
+
+/// Write the signature block (one "label / signature / date" line per entry)
+/// Returns the row after the block
+pub fn write_signature_block(
+    worksheet: &mut Worksheet,
+    labels: &[String],
+    style: &StyleConfig,
+    start_row: u32,
+    col: u16,
+) -> Result<u32, XlsxError> {
+    let label_fmt = create_body_format(style).set_bold();
+    let line_fmt = create_body_format(style).set_border_bottom(FormatBorder::Thin);
+
+    let mut row = start_row;
+    for label in labels {
+        worksheet.write_with_format(row, col, label.as_str(), &label_fmt)?;
+        worksheet.merge_range(row, col + 1, row, col + 3, "", &line_fmt)?;
+        worksheet.write_with_format(row, col + 4, "Date", &label_fmt)?;
+        worksheet.merge_range(row, col + 5, row, col + 6, "", &line_fmt)?;
+        row += 2;
+    }
+
+    Ok(row)
+}
+
+/// Write a reading or limit, displayed with the template's number format
+/// The cell keeps the full-precision value
+pub fn write_number(
+    worksheet: &mut Worksheet,
+    (row, col): (u32, u16),
+    value: f64,
+    numbers: &NumberFormat,
+    format: &Format,
+) -> Result<(), XlsxError> {
+    let format = format.clone().set_num_format(numbers.excel_format(value));
+    worksheet.write_with_format(row, col, value, &format)?;
+    Ok(())
+}
+
+/// Create format for plain body text in the template font
+pub fn create_body_format(style: &StyleConfig) -> Format {
+    Format::new()
+        .set_font_name(&style.body_font)
+        .set_font_size(style.body_font_size)
+}
+
 /// Create format for FG header
 pub fn create_header_format(style: &StyleConfig) -> Format {
     Format::new()
         .set_bold()
-        .set_font_size(14)
+        .set_font_name(&style.heading_font)
+        .set_font_size(style.heading_font_size + 2.0)
         .set_background_color(style.header_color)
         .set_font_color(style.header_text_color)
         .set_border(FormatBorder::Thin)
@@ -91,7 +510,8 @@ pub fn create_header_format(style: &StyleConfig) -> Format {
 pub fn create_test_header_format(style: &StyleConfig) -> Format {
     Format::new()
         .set_bold()
-        .set_font_size(12)
+        .set_font_name(&style.heading_font)
+        .set_font_size(style.heading_font_size)
         .set_background_color(style.header_color)
         .set_font_color(style.header_text_color)
         .set_border(FormatBorder::Thin)
@@ -99,7 +519,7 @@ pub fn create_test_header_format(style: &StyleConfig) -> Format {
 
 /// Create format for spec table header
 pub fn create_spec_header_format(style: &StyleConfig) -> Format {
-    Format::new()
+    create_body_format(style)
         .set_bold()
         .set_background_color(style.spec_header_color)
         .set_border(FormatBorder::Thin)
@@ -107,7 +527,7 @@ pub fn create_spec_header_format(style: &StyleConfig) -> Format {
 
 /// Create format for result table header
 pub fn create_result_header_format(style: &StyleConfig) -> Format {
-    Format::new()
+    create_body_format(style)
         .set_bold()
         .set_background_color(style.result_header_color)
         .set_border(FormatBorder::Thin)
@@ -115,27 +535,107 @@ pub fn create_result_header_format(style: &StyleConfig) -> Format {
 
 /// Create format for PASS results
 pub fn create_pass_format(style: &StyleConfig) -> Format {
-    Format::new()
+    create_body_format(style)
         .set_background_color(style.pass_color)
         .set_border(FormatBorder::Thin)
 }
 
 /// Create format for FAIL results
 pub fn create_fail_format(style: &StyleConfig) -> Format {
-    Format::new()
+    create_body_format(style)
         .set_background_color(style.fail_color)
         .set_border(FormatBorder::Thin)
 }
 
 /// Create format for regular cells
-pub fn create_regular_format() -> Format {
-    Format::new().set_border(FormatBorder::Thin)
+pub fn create_regular_format(style: &StyleConfig) -> Format {
+    create_body_format(style).set_border(FormatBorder::Thin)
 }
 
 /// Create format for NO DATA placeholder
-pub fn create_no_data_format() -> Format {
-    Format::new()
+pub fn create_no_data_format(style: &StyleConfig) -> Format {
+    create_body_format(style)
         .set_italic()
         .set_font_color(Color::RGB(0x808080)) // Gray
         .set_border(FormatBorder::Thin)
 }
+
+/// Create format for the BA report title
+pub fn create_title_format(style: &StyleConfig) -> Format {
+    Format::new()
+        .set_font_name(&style.title_font)
+        .set_font_size(style.title_font_size)
+        .set_align(FormatAlign::VerticalCenter)
+        .set_align(FormatAlign::Center)
+        .set_border(FormatBorder::Medium)
+        .set_bold()
+}
+
+/// Create format for BA info and row label cells
+pub fn create_label_format(style: &StyleConfig) -> Format {
+    Format::new()
+        .set_font_name(&style.heading_font)
+        .set_font_size(style.heading_font_size)
+        .set_align(FormatAlign::VerticalCenter)
+        .set_align(FormatAlign::Center)
+        .set_border(FormatBorder::Medium)
+        .set_bold()
+}
+
+/// Create format for BA spec and data cells
+pub fn create_cell_format(style: &StyleConfig) -> Format {
+    create_body_format(style)
+        .set_align(FormatAlign::VerticalCenter)
+        .set_align(FormatAlign::Center)
+        .set_bold()
+        .set_text_wrap()
+        .set_border(FormatBorder::Thin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_template_json() {
+        let template = ReportTemplate::from_json(
+            r##"{"ba": {"title": "ACME REPORT {fg_number}"}, "style_config": {"pass_color": "#00FF00"}}"##,
+        )
+        .unwrap();
+
+        assert_eq!(template.ba.title, "ACME REPORT {fg_number}");
+        assert_eq!(template.ba.columns_per_page, 10);
+        assert_eq!(
+            hex_color::to_hex(&template.style_config.pass_color),
+            "#00FF00"
+        );
+        assert_eq!(template.header_fields.len(), 4);
+
+        let round_trip = ReportTemplate::from_json(&template.to_json().unwrap()).unwrap();
+        assert_eq!(round_trip.ba.title, template.ba.title);
+    }
+
+    #[test]
+    fn test_info_cells_fit_the_page() {
+        assert!(default_template().validate().is_ok());
+
+        let mut template = default_template();
+        template.ba.columns_per_page = 8;
+        assert!(template.validate().unwrap_err().contains("span 10 columns"));
+    }
+
+    #[test]
+    fn test_invalid_colour() {
+        assert!(ReportTemplate::from_json(r#"{"style_config": {"fail_color": "red"}}"#).is_err());
+    }
+
+    #[test]
+    fn test_render_placeholders() {
+        let mut values = TemplateValues::new();
+        values.set("fg_number", "132520").set("job_number", "J42");
+        assert_eq!(
+            values.render("FG {fg_number} / Job {job_number} / {unknown}"),
+            "FG 132520 / Job J42 / {unknown}"
+        );
+    }
+}
@@ -0,0 +1,267 @@
+// Report templates persisted in the core `report_templates` table
+use crate::reports::template::{default_template, ReportTemplate};
+use crate::voltech::commands::{check_admin_permission, get_current_username};
+use crate::AppState;
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromQueryResult)]
+pub struct ReportTemplateRecord {
+    pub id: i32,
+    pub name: String,
+    pub customer: Option<String>,
+    pub fg_id: Option<i32>,
+    pub definition: String,
+    pub is_default: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// A template as created or edited from the frontend
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReportTemplateInput {
+    pub id: Option<i32>,
+    pub name: String,
+    pub customer: Option<String>,
+    pub fg_id: Option<i32>,
+    pub definition: ReportTemplate,
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+const SELECT_TEMPLATE: &str = r#"
+    SELECT id, name, customer, fg_id, definition, is_default,
+           CAST(created_at AS TEXT) as created_at,
+           CAST(updated_at AS TEXT) as updated_at
+    FROM report_templates
+"#;
+
+/// Get all stored templates ordered by name
+pub async fn list_templates(db: &DbConn) -> Result<Vec<ReportTemplateRecord>, DbErr> {
+    ReportTemplateRecord::find_by_statement(Statement::from_string(
+        DbBackend::Sqlite,
+        format!("{} ORDER BY name COLLATE NOCASE", SELECT_TEMPLATE),
+    ))
+    .all(db)
+    .await
+}
+
+/// Get a stored template by id
+pub async fn get_template(db: &DbConn, id: i32) -> Result<Option<ReportTemplateRecord>, DbErr> {
+    ReportTemplateRecord::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        format!("{} WHERE id = ?", SELECT_TEMPLATE),
+        [id.into()],
+    ))
+    .one(db)
+    .await
+}
+
+/// Insert or update a template and return its id
+/// Marking a template as default clears the flag on every other template
+pub async fn save_template(db: &DbConn, input: ReportTemplateInput) -> Result<i32, DbErr> {
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Err(DbErr::Custom("Template name is required".to_string()));
+    }
+
+    let customer = input
+        .customer
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty());
+    input.definition.validate().map_err(DbErr::Custom)?;
+    let definition = input.definition.to_json().map_err(DbErr::Custom)?;
+
+    let txn = db.begin().await?;
+
+    if input.is_default {
+        txn.execute_raw(Statement::from_string(
+            DbBackend::Sqlite,
+            "UPDATE report_templates SET is_default = 0",
+        ))
+        .await?;
+    }
+
+    let id = match input.id {
+        Some(id) => {
+            let result = txn
+                .execute_raw(Statement::from_sql_and_values(
+                    DbBackend::Sqlite,
+                    r#"
+                    UPDATE report_templates
+                    SET name = ?, customer = ?, fg_id = ?, definition = ?, is_default = ?,
+                        updated_at = CURRENT_TIMESTAMP
+                    WHERE id = ?
+                    "#,
+                    [
+                        name.into(),
+                        customer.into(),
+                        input.fg_id.into(),
+                        definition.into(),
+                        input.is_default.into(),
+                        id.into(),
+                    ],
+                ))
+                .await?;
+
+            if result.rows_affected() == 0 {
+                return Err(DbErr::RecordNotFound(format!(
+                    "Report template {} not found",
+                    id
+                )));
+            }
+            id
+        }
+        None => {
+            let result = txn
+                .execute_raw(Statement::from_sql_and_values(
+                    DbBackend::Sqlite,
+                    r#"
+                    INSERT INTO report_templates (name, customer, fg_id, definition, is_default)
+                    VALUES (?, ?, ?, ?, ?)
+                    "#,
+                    [
+                        name.into(),
+                        customer.into(),
+                        input.fg_id.into(),
+                        definition.into(),
+                        input.is_default.into(),
+                    ],
+                ))
+                .await?;
+            result.last_insert_id() as i32
+        }
+    };
+
+    txn.commit().await?;
+    Ok(id)
+}
+
+/// Delete a template, returning whether it existed
+pub async fn delete_template(db: &DbConn, id: i32) -> Result<bool, DbErr> {
+    let result = db
+        .execute_raw(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "DELETE FROM report_templates WHERE id = ?",
+            [id.into()],
+        ))
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Select the template for an FG
+/// Precedence: FG-specific, then customer, then the stored default, then the built-in default
+pub async fn resolve_template(
+    db: &DbConn,
+    fg_id: Option<i32>,
+    customer: Option<&str>,
+) -> Result<ReportTemplate, DbErr> {
+    let customer = customer.map(str::trim).unwrap_or("");
+
+    let record = ReportTemplateRecord::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        format!(
+            r#"{}
+            WHERE fg_id = ?1
+               OR (fg_id IS NULL AND ?2 != '' AND customer = ?2 COLLATE NOCASE)
+               OR is_default = 1
+            ORDER BY
+                CASE
+                    WHEN fg_id = ?1 THEN 0
+                    WHEN customer = ?2 COLLATE NOCASE THEN 1
+                    ELSE 2
+                END,
+                updated_at DESC
+            LIMIT 1
+            "#,
+            SELECT_TEMPLATE
+        ),
+        [fg_id.into(), customer.into()],
+    ))
+    .one(db)
+    .await?;
+
+    match record {
+        Some(record) => ReportTemplate::from_json(&record.definition)
+            .map_err(|e| DbErr::Custom(format!("Template '{}': {}", record.name, e))),
+        None => Ok(default_template()),
+    }
+}
+
+/// Load an explicitly selected template, or resolve one for the FG
+pub async fn select_template(
+    db: &DbConn,
+    template_id: Option<i32>,
+    fg_id: Option<i32>,
+    customer: Option<&str>,
+) -> Result<ReportTemplate, DbErr> {
+    match template_id {
+        Some(id) => {
+            let record = get_template(db, id).await?.ok_or_else(|| {
+                DbErr::RecordNotFound(format!("Report template {} not found", id))
+            })?;
+            ReportTemplate::from_json(&record.definition).map_err(DbErr::Custom)
+        }
+        None => resolve_template(db, fg_id, customer).await,
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+async fn require_admin(state: &State<'_, AppState>) -> Result<(), String> {
+    let username = get_current_username()?;
+    if !check_admin_permission(state, &username).await? {
+        return Err("Admin permission required to manage report templates".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_report_templates(
+    state: State<'_, AppState>,
+) -> Result<Vec<ReportTemplateRecord>, String> {
+    list_templates(&state.core_db)
+        .await
+        .map_err(|e| format!("Failed to list report templates: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_report_template(
+    state: State<'_, AppState>,
+    id: i32,
+) -> Result<Option<ReportTemplateRecord>, String> {
+    get_template(&state.core_db, id)
+        .await
+        .map_err(|e| format!("Failed to get report template: {}", e))
+}
+
+#[tauri::command]
+pub async fn save_report_template(
+    state: State<'_, AppState>,
+    template: ReportTemplateInput,
+) -> Result<i32, String> {
+    require_admin(&state).await?;
+
+    save_template(&state.core_db, template)
+        .await
+        .map_err(|e| format!("Failed to save report template: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_report_template(state: State<'_, AppState>, id: i32) -> Result<bool, String> {
+    require_admin(&state).await?;
+
+    delete_template(&state.core_db, id)
+        .await
+        .map_err(|e| format!("Failed to delete report template: {}", e))
+}
+
+/// Get the built-in template, used as a starting point for new templates
+#[tauri::command]
+pub async fn get_default_report_template() -> Result<ReportTemplate, String> {
+    Ok(default_template())
+}
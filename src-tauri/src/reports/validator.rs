@@ -1,9 +1,21 @@
+use crate::manual::limits::manual_verdict;
+use crate::part_mapping::{manual_parts, voltech_parts};
+use crate::reports::collector::{run_scope, serial_spec, RecordSelection};
+use crate::reports::other_results::{run_results, serial_outcomes, OtherResult};
+use crate::reports::retest::{
+    manual_attempt_order, report_policy, resolve, voltech_attempt_order, RetestPolicy,
+};
+use crate::reports::sessions::{
+    manual_sessions, voltech_sessions, AvailableSession, SessionSelection,
+};
+use crate::serials::SerialSpec;
 use crate::AppState;
 use ::entity::test;
 use entity_manual::manual_test_results;
 use entity_voltech::test_results as voltech_test_results;
 use sea_orm::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use tauri::State;
 
 /// Status of a single test in the validation
@@ -19,14 +31,6 @@ pub struct TestStatus {
     pub search_method: String, // "serial_range" or "batch"
 }
 
-/// Represents an available test session (for batch mode)
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct AvailableSession {
-    pub date: String, // NaiveDate as string "YYYY-MM-DD"
-    pub batch: String,
-    pub record_count: i32,
-}
-
 /// Result of report validation
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ValidationResult {
@@ -34,21 +38,163 @@ pub struct ValidationResult {
     pub fg_serialized: bool,
     pub test_statuses: Vec<TestStatus>,
     pub missing_test_count: i32,
+    pub matrix: Option<CompletenessMatrix>, // Serialized mode only
+}
+
+/// State of one serial for one test
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CellStatus {
+    Pass,             // A single passing record
+    FailOnly,         // Records exist but none pass
+    Failing,          // Passed once, but the attempt the policy keeps fails
+    Missing,          // No record
+    MultipleAttempts, // Retested, and the attempt the policy keeps passes
+}
+
+impl CellStatus {
+    fn from_attempts(attempts: i32, passes: i32, final_passed: bool) -> Self {
+        match (attempts, passes) {
+            (0, _) => CellStatus::Missing,
+            (_, 0) => CellStatus::FailOnly,
+            _ if !final_passed => CellStatus::Failing,
+            (1, _) => CellStatus::Pass,
+            _ => CellStatus::MultipleAttempts,
+        }
+    }
+
+    /// Whether the serial's final result for the test passes
+    pub fn is_complete(&self) -> bool {
+        matches!(self, CellStatus::Pass | CellStatus::MultipleAttempts)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MatrixCell {
+    pub test_id: i32,
+    pub status: CellStatus,
+    pub attempts: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SerialRow {
+    pub serial: String,
+    pub cells: Vec<MatrixCell>,
+}
+
+/// Serial x test grid for a serialized report
+/// "Other" tests are judged by the outcomes recorded for each serial, or for
+/// the whole run when none name the serial
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompletenessMatrix {
+    pub test_ids: Vec<i32>,
+    pub rows: Vec<SerialRow>,
+    pub complete_serials: i32,
+    pub total_serials: i32,
+}
+
+impl CompletenessMatrix {
+    pub fn is_complete(&self) -> bool {
+        self.complete_serials == self.total_serials
+    }
+
+    /// Cells for one test, in serial order
+    fn column(&self, test_id: i32) -> impl Iterator<Item = &MatrixCell> {
+        self.rows
+            .iter()
+            .flat_map(move |row| row.cells.iter().filter(move |c| c.test_id == test_id))
+    }
+}
+
+/// Records found for one test in a serial specification: (serial, passed)
+/// per attempt, oldest first, with serials labelled as the specification
+/// writes them
+struct TestAttempts {
+    test_id: i32,
+    attempts: Vec<(String, bool)>,
+}
+
+/// Build the matrix for the given serials, taking each serial's final result
+/// the way the report resolves retests under `policy`
+fn build_matrix(
+    serials: &[String],
+    tests: &[TestAttempts],
+    policy: RetestPolicy,
+) -> CompletenessMatrix {
+    let policy = policy.final_result();
+
+    // (attempts, passes, final attempt passed) per serial, for each test
+    let tallies: Vec<HashMap<&str, (i32, i32, bool)>> = tests
+        .iter()
+        .map(|test| {
+            let mut tally: HashMap<&str, (i32, i32, bool)> = HashMap::new();
+            for (serial, passed) in &test.attempts {
+                let entry = tally.entry(serial.as_str()).or_default();
+                entry.0 += 1;
+                entry.1 += *passed as i32;
+            }
+            let finals = resolve(
+                test.attempts.iter().enumerate().collect(),
+                policy,
+                |(_, (serial, _))| Some(serial.clone()),
+                |(_, (_, passed))| *passed,
+                |(index, _)| *index,
+            );
+            for kept in finals {
+                let (_, (serial, passed)) = kept.record;
+                if let Some(entry) = tally.get_mut(serial.as_str()) {
+                    entry.2 = *passed;
+                }
+            }
+            tally
+        })
+        .collect();
+
+    let rows: Vec<SerialRow> = serials
+        .iter()
+        .map(|serial| SerialRow {
+            serial: serial.clone(),
+            cells: tests
+                .iter()
+                .zip(&tallies)
+                .map(|(test, tally)| {
+                    let (attempts, passes, final_passed) =
+                        tally.get(serial.as_str()).copied().unwrap_or_default();
+                    MatrixCell {
+                        test_id: test.test_id,
+                        status: CellStatus::from_attempts(attempts, passes, final_passed),
+                        attempts,
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+
+    let complete_serials = rows
+        .iter()
+        .filter(|row| row.cells.iter().all(|c| c.status.is_complete()))
+        .count() as i32;
+
+    CompletenessMatrix {
+        test_ids: tests.iter().map(|t| t.test_id).collect(),
+        total_serials: rows.len() as i32,
+        complete_serials,
+        rows,
+    }
 }
 
 /// Validate report data availability
 ///
 /// # Arguments
 /// * `report_id` - Report ID to validate
-/// * `batch` - Optional batch identifier (for batch mode)
-/// * `serial_range` - Optional serial range (for serialized mode, e.g., "1001-1010")
+/// * `selection` - Batch, selected dates and sessions (batch mode) or serial
+///   specification (serialized mode), as the report will collect them
 /// * `core_db` - Core database connection
 /// * `voltech_db` - Voltech database connection
 /// * `manual_db` - Manual database connection
 pub async fn validate_report_data(
     report_id: i32,
-    batch: Option<String>,
-    serial_range: Option<String>,
+    selection: &RecordSelection,
     core_db: &DbConn,
     voltech_db: &DbConn,
     manual_db: &DbConn,
@@ -66,6 +212,7 @@ pub async fn validate_report_data(
             fg_serialized: false,
             test_statuses: vec![],
             missing_test_count: 0,
+            matrix: None,
         });
     }
 
@@ -76,42 +223,44 @@ pub async fn validate_report_data(
         .await?
         .ok_or(DbErr::RecordNotFound("FG not found".to_string()))?;
 
-    let is_serialized = fg.serialized;
-    let search_method = if is_serialized {
-        "serial_range"
-    } else {
-        "batch"
-    };
+    // Tester part numbers recorded for this FG and revision
+    let fg_voltech_parts = voltech_parts(core_db, voltech_db, &fg.fg, Some(&fg.rev)).await?;
+    let fg_manual_parts = manual_parts(core_db, manual_db, &fg.fg, Some(&fg.rev)).await?;
+
+    // Outcomes recorded by hand for "other" tests in this run
+    let run = run_scope(
+        selection.batch.as_deref(),
+        selection.serial_range.as_deref(),
+    )
+    .map_err(DbErr::Custom)?;
+    let other = run_results(core_db, report_id, run.as_deref()).await?;
+    let policy = report_policy(core_db, report_id).await?.unwrap_or_default();
+
+    if fg.serialized {
+        return validate_serialized(
+            &tests,
+            selection.serial_range.as_deref(),
+            policy,
+            &fg_voltech_parts,
+            &fg_manual_parts,
+            &other,
+            voltech_db,
+            manual_db,
+        )
+        .await;
+    }
 
     let mut test_statuses = Vec::new();
     let mut missing_count = 0;
 
     for test_model in tests {
         let status = if test_model.source_type == "voltech" {
-            validate_voltech_test(
-                &test_model,
-                &fg.fg,
-                &batch,
-                &serial_range,
-                is_serialized,
-                voltech_db,
-            )
-            .await?
+            validate_voltech_test(&test_model, &fg_voltech_parts, selection, voltech_db).await?
         } else if test_model.source_type == "manual" {
-            validate_manual_test(&test_model, &batch, &serial_range, is_serialized, manual_db)
-                .await?
+            validate_manual_test(&test_model, &fg_manual_parts, selection, manual_db).await?
         } else {
-            // "other" source type - no validation needed, always has data
-            TestStatus {
-                test_id: test_model.id,
-                test_name: test_model.test_type.clone(),
-                source_type: test_model.source_type.clone(),
-                associated_test: test_model.associated_test.clone(),
-                has_data: true,
-                record_count: 1,
-                available_sessions: vec![],
-                search_method: search_method.to_string(),
-            }
+            // "other" source type - needs a passing outcome recorded for the run
+            other_test_status(&test_model, &other, policy)
         };
 
         if !status.has_data {
@@ -123,216 +272,284 @@ pub async fn validate_report_data(
 
     Ok(ValidationResult {
         is_complete: missing_count == 0,
-        fg_serialized: is_serialized,
+        fg_serialized: false,
         test_statuses,
         missing_test_count: missing_count,
+        matrix: None,
     })
 }
 
-/// Validate a voltech test
-async fn validate_voltech_test(
+fn test_status(test_model: &test::Model, search_method: &str) -> TestStatus {
+    TestStatus {
+        test_id: test_model.id,
+        test_name: test_model.test_type.clone(),
+        source_type: test_model.source_type.clone(),
+        associated_test: test_model.associated_test.clone(),
+        has_data: false,
+        record_count: 0,
+        available_sessions: vec![],
+        search_method: search_method.to_string(),
+    }
+}
+
+/// Status of a test from the outcomes recorded by hand for a batch: each
+/// serial's outcomes (or the run-wide ones) are retests of each other, and
+/// the final result of every one must pass
+fn other_test_status(
     test_model: &test::Model,
-    fg_number: &str,
-    batch: &Option<String>,
-    serial_range: &Option<String>,
-    is_serialized: bool,
-    voltech_db: &DbConn,
-) -> Result<TestStatus, DbErr> {
-    let associated_test = test_model.associated_test.as_deref().unwrap_or("");
+    other: &[OtherResult],
+    policy: RetestPolicy,
+) -> TestStatus {
+    let outcomes: Vec<&OtherResult> = other.iter().filter(|r| r.test_id == test_model.id).collect();
+    let record_count = outcomes.len() as i32;
+    let finals = resolve(
+        outcomes,
+        policy.final_result(),
+        |r| Some(r.serial_number.as_deref().unwrap_or("").trim().to_string()),
+        |r| r.result.eq_ignore_ascii_case("pass"),
+        |r| (r.recorded_at.clone(), r.id),
+    );
+
+    TestStatus {
+        has_data: !finals.is_empty()
+            && finals
+                .iter()
+                .all(|r| r.record.result.eq_ignore_ascii_case("pass")),
+        record_count,
+        ..test_status(test_model, "batch")
+    }
+}
 
-    if is_serialized {
-        // Serialized mode: check if records exist for serial numbers in range
-        let (has_data, record_count) = if let Some(range) = serial_range {
-            // Parse serial range (e.g., "1001-1010")
-            let parts: Vec<&str> = range.split('-').collect();
-            if parts.len() == 2 {
-                if let (Ok(start), Ok(end)) = (parts[0].parse::<i32>(), parts[1].parse::<i32>()) {
-                    // Query for passing tests with matching FG (starts_with for FG+type+rev), serial range, and measurement key
-                    let count = voltech_test_results::Entity::find()
-                        .filter(voltech_test_results::Column::Part.starts_with(fg_number))
-                        .filter(voltech_test_results::Column::SerialNum.between(start.to_string(), end.to_string()))
-                        .filter(voltech_test_results::Column::PassFail.eq("Pass"))
-                        .filter(
-                            voltech_test_results::Column::Measurements
-                                .contains(&format!("\"{}\"", associated_test)),
-                        )
-                        .count(voltech_db)
-                        .await?;
-                    (count > 0, count as i32)
-                } else {
-                    (false, 0)
-                }
-            } else {
-                (false, 0)
-            }
-        } else {
-            (false, 0)
-        };
+/// Serialized mode: every serial in the specification needs a passing final
+/// result for every test, under the report's retest policy
+async fn validate_serialized(
+    tests: &[test::Model],
+    serial_range: Option<&str>,
+    policy: RetestPolicy,
+    voltech_parts: &[String],
+    manual_parts: &[String],
+    other: &[OtherResult],
+    voltech_db: &DbConn,
+    manual_db: &DbConn,
+) -> Result<ValidationResult, DbErr> {
+    let serials = serial_spec(serial_range)?;
+
+    let mut attempts = Vec::new();
+    if let Some(serials) = &serials {
+        let labels = serials.serials();
+        for test_model in tests {
+            let records = match test_model.source_type.as_str() {
+                "voltech" => voltech_attempts(test_model, voltech_parts, serials, voltech_db).await?,
+                "manual" => manual_attempts(test_model, manual_parts, serials, manual_db).await?,
+                _ => other_attempts(test_model, other, &labels, serials),
+            };
+            attempts.push(TestAttempts {
+                test_id: test_model.id,
+                attempts: records,
+            });
+        }
+    }
 
-        Ok(TestStatus {
-            test_id: test_model.id,
-            test_name: test_model.test_type.clone(),
-            source_type: test_model.source_type.clone(),
-            associated_test: test_model.associated_test.clone(),
-            has_data,
-            record_count,
-            available_sessions: vec![],
-            search_method: "serial_range".to_string(),
-        })
-    } else {
-        // Batch mode: find available test sessions grouped by date
-        let results = voltech_test_results::Entity::find()
-            .filter(voltech_test_results::Column::Part.starts_with(fg_number))
-            .filter(voltech_test_results::Column::PassFail.eq("Pass"))
-            .filter(
-                voltech_test_results::Column::Measurements
-                    .contains(&format!("\"{}\"", associated_test)),
-            )
-            .all(voltech_db)
-            .await?;
+    // Without serials there is nothing to check against
+    let matrix = serials.map(|serials| build_matrix(&serials.serials(), &attempts, policy));
 
-        // Group by normalized_date and batch
-        let mut sessions_map: std::collections::HashMap<(String, String), i32> =
-            std::collections::HashMap::new();
+    let mut test_statuses = Vec::new();
+    let mut missing_count = 0;
+    for test_model in tests {
+        // Complete when every serial's final result passes
+        let mut status = test_status(test_model, "serial_range");
+        let cells: Vec<&MatrixCell> = matrix
+            .iter()
+            .flat_map(|m| m.column(test_model.id))
+            .collect();
+        status.has_data = matrix.is_some() && cells.iter().all(|c| c.status.is_complete());
+        status.record_count = cells.iter().map(|c| c.attempts).sum();
 
-        for result in results {
-            if let Some(date) = result.normalized_date {
-                let key = (date.to_string(), result.batch.clone());
-                *sessions_map.entry(key).or_insert(0) += 1;
-            }
+        if !status.has_data {
+            missing_count += 1;
         }
+        test_statuses.push(status);
+    }
 
-        let mut available_sessions: Vec<AvailableSession> = sessions_map
-            .into_iter()
-            .map(|((date, batch_name), count)| AvailableSession {
-                date,
-                batch: batch_name,
-                record_count: count,
-            })
-            .collect();
+    Ok(ValidationResult {
+        // Without a serial specification every test counts as missing
+        is_complete: matrix.as_ref().is_some_and(|m| m.is_complete()) && missing_count == 0,
+        fg_serialized: true,
+        test_statuses,
+        missing_test_count: missing_count,
+        matrix,
+    })
+}
 
-        // Sort by date descending
-        available_sessions.sort_by(|a, b| b.date.cmp(&a.date));
+/// Outcomes recorded for an "other" test that decide each serial, oldest
+/// first: the serial's own, or the run-wide ones when none name it
+fn other_attempts(
+    test_model: &test::Model,
+    other: &[OtherResult],
+    labels: &[String],
+    serials: &SerialSpec,
+) -> Vec<(String, bool)> {
+    labels
+        .iter()
+        .flat_map(|serial| {
+            serial_outcomes(other, test_model.id, serial, serials)
+                .into_iter()
+                .map(move |r| (serial.clone(), r.result.eq_ignore_ascii_case("pass")))
+        })
+        .collect()
+}
 
-        // Check if specific batch has data
-        let has_data = if let Some(batch_filter) = batch {
-            available_sessions.iter().any(|s| &s.batch == batch_filter)
-        } else {
-            !available_sessions.is_empty()
-        };
+/// Voltech records for a test within a serial specification, passing or
+/// not, oldest first
+async fn voltech_attempts(
+    test_model: &test::Model,
+    parts: &[String],
+    serials: &SerialSpec,
+    voltech_db: &DbConn,
+) -> Result<Vec<(String, bool)>, DbErr> {
+    let associated_test = test_model.associated_test.as_deref().unwrap_or("");
 
-        let record_count = if let Some(batch_filter) = batch {
-            available_sessions
-                .iter()
-                .find(|s| &s.batch == batch_filter)
-                .map(|s| s.record_count)
-                .unwrap_or(0)
-        } else {
-            available_sessions.iter().map(|s| s.record_count).sum()
-        };
+    // Matching FG part numbers and measurement key
+    let mut records = voltech_test_results::Entity::find()
+        .filter(voltech_test_results::Column::Part.is_in(parts.iter().cloned()))
+        .filter(
+            voltech_test_results::Column::Measurements
+                .contains(format!("\"{}\"", associated_test)),
+        )
+        .filter(serials.condition(voltech_test_results::Column::SerialNum))
+        .all(voltech_db)
+        .await?;
+    records.sort_by_key(voltech_attempt_order);
 
-        Ok(TestStatus {
-            test_id: test_model.id,
-            test_name: test_model.test_type.clone(),
-            source_type: test_model.source_type.clone(),
-            associated_test: test_model.associated_test.clone(),
-            has_data,
-            record_count,
-            available_sessions,
-            search_method: "batch".to_string(),
+    Ok(records
+        .iter()
+        .filter_map(|r| {
+            let serial = serials.label(&r.serial_num)?;
+            Some((serial, r.pass_fail.eq_ignore_ascii_case("pass")))
         })
-    }
+        .collect())
 }
 
-/// Validate a manual test
-async fn validate_manual_test(
+/// Manual records for a test within a serial specification, passing or
+/// not, oldest first
+async fn manual_attempts(
     test_model: &test::Model,
-    batch: &Option<String>,
-    serial_range: &Option<String>,
-    is_serialized: bool,
+    parts: &[String],
+    serials: &SerialSpec,
     manual_db: &DbConn,
-) -> Result<TestStatus, DbErr> {
+) -> Result<Vec<(String, bool)>, DbErr> {
     let associated_test = test_model.associated_test.as_deref().unwrap_or("");
 
-    if is_serialized {
-        // Serialized mode: check if records exist for serial numbers in range
-        let (has_data, record_count) = if let Some(_range) = serial_range {
-            // For manual tests, we need serial numbers from the data
-            let count = manual_test_results::Entity::find()
-                .filter(manual_test_results::Column::Test.eq(associated_test))
-                .count(manual_db)
-                .await?;
-            (count > 0, count as i32)
-        } else {
-            (false, 0)
-        };
+    let mut records = manual_test_results::Entity::find()
+        .filter(manual_test_results::Column::Fg.is_in(parts.iter().cloned()))
+        .filter(manual_test_results::Column::Test.eq(associated_test))
+        .filter(serials.condition(manual_test_results::Column::Sn))
+        .all(manual_db)
+        .await?;
+    records.sort_by_key(manual_attempt_order);
 
-        Ok(TestStatus {
-            test_id: test_model.id,
-            test_name: test_model.test_type.clone(),
-            source_type: test_model.source_type.clone(),
-            associated_test: test_model.associated_test.clone(),
-            has_data,
-            record_count,
-            available_sessions: vec![],
-            search_method: "serial_range".to_string(),
+    Ok(records
+        .iter()
+        .filter_map(|r| {
+            let serial = serials.label(&r.sn)?;
+            Some((serial, manual_verdict(r).eq_ignore_ascii_case("pass")))
         })
-    } else {
-        // Batch mode: find available test sessions grouped by date
-        let results = manual_test_results::Entity::find()
-            .filter(manual_test_results::Column::Test.eq(associated_test))
-            .all(manual_db)
-            .await?;
-
-        // Group by normalized_date and batch (manual has Date, not Option<Date>)
-        let mut sessions_map: std::collections::HashMap<(String, String), i32> =
-            std::collections::HashMap::new();
-
-        for result in results {
-            let key = (result.normalized_date.to_string(), result.batch.clone());
-            *sessions_map.entry(key).or_insert(0) += 1;
+        .collect())
+}
+
+/// Whether the report would include a session: its batch, then the selected
+/// dates (unless sessions are included by id) and sessions
+fn is_selected(session: &AvailableSession, selection: &RecordSelection) -> bool {
+    let batch = selection.batch.as_ref().map_or(true, |b| &session.batch == b);
+    let date = match &selection.selected_dates {
+        Some(dates) if selection.sessions.include.is_empty() => {
+            dates.iter().any(|d| d.trim() == session.date)
         }
+        _ => true,
+    };
+    batch && date && selection.sessions.keeps(&session.id)
+}
 
-        let mut available_sessions: Vec<AvailableSession> = sessions_map
-            .into_iter()
-            .map(|((date, batch_name), count)| AvailableSession {
-                date,
-                batch: batch_name,
-                record_count: count,
-            })
-            .collect();
+/// Validate a voltech test (batch mode)
+async fn validate_voltech_test(
+    test_model: &test::Model,
+    parts: &[String],
+    selection: &RecordSelection,
+    voltech_db: &DbConn,
+) -> Result<TestStatus, DbErr> {
+    let associated_test = test_model.associated_test.as_deref().unwrap_or("");
 
-        // Sort by date descending
-        available_sessions.sort_by(|a, b| b.date.cmp(&a.date));
+    // Find available test sessions grouped by date
+    let results = voltech_test_results::Entity::find()
+        .filter(voltech_test_results::Column::Part.is_in(parts.iter().cloned()))
+        .filter(voltech_test_results::Column::PassFail.eq("Pass"))
+        .filter(
+            voltech_test_results::Column::Measurements
+                .contains(&format!("\"{}\"", associated_test)),
+        )
+        .all(voltech_db)
+        .await?;
 
-        // Check if specific batch has data
-        let has_data = if let Some(batch_filter) = batch {
-            available_sessions.iter().any(|s| &s.batch == batch_filter)
-        } else {
-            !available_sessions.is_empty()
-        };
+    // Group by file block (sessions), newest first
+    let available_sessions: Vec<AvailableSession> = voltech_sessions(&results);
+
+    // Check if the selected sessions have data
+    let selected: Vec<&AvailableSession> = available_sessions
+        .iter()
+        .filter(|s| is_selected(s, selection))
+        .collect();
+    let has_data = !selected.is_empty();
+    let record_count = selected.iter().map(|s| s.record_count).sum();
+
+    Ok(TestStatus {
+        test_id: test_model.id,
+        test_name: test_model.test_type.clone(),
+        source_type: test_model.source_type.clone(),
+        associated_test: test_model.associated_test.clone(),
+        has_data,
+        record_count,
+        available_sessions,
+        search_method: "batch".to_string(),
+    })
+}
 
-        let record_count = if let Some(batch_filter) = batch {
-            available_sessions
-                .iter()
-                .find(|s| &s.batch == batch_filter)
-                .map(|s| s.record_count)
-                .unwrap_or(0)
-        } else {
-            available_sessions.iter().map(|s| s.record_count).sum()
-        };
+/// Validate a manual test (batch mode)
+async fn validate_manual_test(
+    test_model: &test::Model,
+    parts: &[String],
+    selection: &RecordSelection,
+    manual_db: &DbConn,
+) -> Result<TestStatus, DbErr> {
+    let associated_test = test_model.associated_test.as_deref().unwrap_or("");
 
-        Ok(TestStatus {
-            test_id: test_model.id,
-            test_name: test_model.test_type.clone(),
-            source_type: test_model.source_type.clone(),
-            associated_test: test_model.associated_test.clone(),
-            has_data,
-            record_count,
-            available_sessions,
-            search_method: "batch".to_string(),
-        })
-    }
+    // Find available test sessions grouped by date
+    let results = manual_test_results::Entity::find()
+        .filter(manual_test_results::Column::Fg.is_in(parts.iter().cloned()))
+        .filter(manual_test_results::Column::Test.eq(associated_test))
+        .all(manual_db)
+        .await?;
+
+    // Group by file block (sessions), newest first
+    let available_sessions: Vec<AvailableSession> = manual_sessions(&results);
+
+    // Check if the selected sessions have data
+    let selected: Vec<&AvailableSession> = available_sessions
+        .iter()
+        .filter(|s| is_selected(s, selection))
+        .collect();
+    let has_data = !selected.is_empty();
+    let record_count = selected.iter().map(|s| s.record_count).sum();
+
+    Ok(TestStatus {
+        test_id: test_model.id,
+        test_name: test_model.test_type.clone(),
+        source_type: test_model.source_type.clone(),
+        associated_test: test_model.associated_test.clone(),
+        has_data,
+        record_count,
+        available_sessions,
+        search_method: "batch".to_string(),
+    })
 }
 
 // ============================================================================
@@ -344,12 +561,19 @@ pub async fn validate_report(
     report_id: i32,
     batch: Option<String>,
     serial_range: Option<String>,
+    selected_dates: Option<Vec<String>>,
+    sessions: Option<SessionSelection>,
     state: State<'_, AppState>,
 ) -> Result<ValidationResult, String> {
-    match validate_report_data(
-        report_id,
+    let selection = RecordSelection {
         batch,
         serial_range,
+        selected_dates,
+        sessions: sessions.unwrap_or_default(),
+    };
+    match validate_report_data(
+        report_id,
+        &selection,
         &state.core_db,
         &state.voltech_db,
         &state.manual_db,
@@ -360,3 +584,196 @@ pub async fn validate_report(
         Err(e) => Err(format!("Failed to validate report: {}", e)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn serials(spec: &str) -> Vec<String> {
+        SerialSpec::parse(spec).unwrap().serials()
+    }
+
+    fn attempts(records: &[(i32, bool)]) -> Vec<(String, bool)> {
+        records
+            .iter()
+            .map(|(sn, passed)| (sn.to_string(), *passed))
+            .collect()
+    }
+
+    #[test]
+    fn test_matrix_flags_each_serial() {
+        let tests = vec![
+            TestAttempts {
+                test_id: 1,
+                // 1001 passes, 1002 failed then passed, 1003 only failed, 1004 never tested
+                attempts: attempts(&[(1001, true), (1002, false), (1002, true), (1003, false)]),
+            },
+            TestAttempts {
+                test_id: 2,
+                attempts: attempts(&[(1001, true), (1002, true), (1003, true), (1004, true)]),
+            },
+        ];
+        let matrix = build_matrix(&serials("1001-1004"), &tests, RetestPolicy::default());
+
+        let statuses: Vec<CellStatus> = matrix.column(1).map(|c| c.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                CellStatus::Pass,
+                CellStatus::MultipleAttempts,
+                CellStatus::FailOnly,
+                CellStatus::Missing
+            ]
+        );
+        assert_eq!(matrix.rows[1].cells[0].attempts, 2);
+        assert_eq!(matrix.total_serials, 4);
+        assert_eq!(matrix.complete_serials, 2);
+        assert!(!matrix.is_complete());
+    }
+
+    #[test]
+    fn test_matrix_follows_retest_policy() {
+        // 1001 passed then failed; 1002 failed then passed
+        let tests = vec![TestAttempts {
+            test_id: 1,
+            attempts: attempts(&[(1001, true), (1002, false), (1001, false), (1002, true)]),
+        }];
+        let statuses = |policy| {
+            build_matrix(&serials("1001-1002"), &tests, policy)
+                .column(1)
+                .map(|c| c.status)
+                .collect::<Vec<_>>()
+        };
+
+        // The report shows 1001 failing, so validation does too
+        assert_eq!(
+            statuses(RetestPolicy::LatestAttempt),
+            vec![CellStatus::Failing, CellStatus::MultipleAttempts]
+        );
+        assert_eq!(
+            statuses(RetestPolicy::AllAttempts),
+            vec![CellStatus::Failing, CellStatus::MultipleAttempts]
+        );
+        assert_eq!(
+            statuses(RetestPolicy::LatestPass),
+            vec![CellStatus::MultipleAttempts, CellStatus::MultipleAttempts]
+        );
+        let matrix = build_matrix(&serials("1001-1002"), &tests, RetestPolicy::LatestAttempt);
+        assert!(!matrix.is_complete());
+    }
+
+    #[test]
+    fn test_sparse_range_is_incomplete() {
+        // Data for 5 of 100 serials is not a complete report
+        let tests = vec![TestAttempts {
+            test_id: 1,
+            attempts: (1001..=1005).map(|sn| (sn.to_string(), true)).collect(),
+        }];
+        let matrix = build_matrix(&serials("1001-1100"), &tests, RetestPolicy::default());
+        assert_eq!(matrix.complete_serials, 5);
+        assert!(!matrix.is_complete());
+
+        let matrix = build_matrix(&serials("1001-1005"), &tests, RetestPolicy::default());
+        assert!(matrix.is_complete());
+
+        // Serials listed out of order keep the order they were given in
+        let matrix = build_matrix(&serials("1005, 1001-1002"), &tests, RetestPolicy::default());
+        let rows: Vec<&str> = matrix.rows.iter().map(|r| r.serial.as_str()).collect();
+        assert_eq!(rows, vec!["1005", "1001", "1002"]);
+        assert!(matrix.is_complete());
+    }
+
+    #[test]
+    fn test_batch_sessions_follow_the_selection() {
+        let session = |id: &str, batch: &str, date: &str| AvailableSession {
+            id: id.to_string(),
+            source: "voltech".to_string(),
+            date: date.to_string(),
+            batch: batch.to_string(),
+            part: "FT1".to_string(),
+            operator: "AB".to_string(),
+            tester: None,
+            file_path: "f.atr".to_string(),
+            started_at: None,
+            ended_at: None,
+            record_count: 5,
+        };
+        let early = session("v-1", "B1", "2025-11-18");
+        let late = session("v-2", "B1", "2025-11-19");
+        let other = session("v-3", "B2", "2025-11-19");
+
+        let mut selection = RecordSelection {
+            batch: Some("B1".to_string()),
+            serial_range: None,
+            selected_dates: Some(vec!["2025-11-19".to_string()]),
+            sessions: SessionSelection::default(),
+        };
+        assert!(!is_selected(&early, &selection));
+        assert!(is_selected(&late, &selection));
+        assert!(!is_selected(&other, &selection));
+
+        // Sessions included by id replace the dates; excluded ones always drop
+        selection.sessions.include = vec!["v-1".to_string(), "v-2".to_string()];
+        selection.sessions.exclude = vec!["v-2".to_string()];
+        assert!(is_selected(&early, &selection));
+        assert!(!is_selected(&late, &selection));
+    }
+
+    #[tokio::test]
+    async fn test_other_outcomes_must_pass_for_each_serial() {
+        use migration::{Migrator, MigratorTrait};
+
+        let core_db = Database::connect("sqlite::memory:").await.unwrap();
+        Migrator::up(&core_db, None).await.unwrap();
+        core_db
+            .execute_unprepared(
+                "INSERT INTO fg (id, fg, rev, customer, serialized) VALUES (1, '132520', 'FTA', 'ACME', 1);
+                 INSERT INTO report (id, fg_id, retest_policy) VALUES (1, 1, 'latest_attempt');
+                 INSERT INTO test (id, report_id, fg_id, test_type, uo_m, \"order\", source_type)
+                 VALUES (1, 1, 1, 'Visual', '', 1, 'other');
+                 INSERT INTO other_test_results (report_id, test_id, scope, serial_number, result, recorded_by)
+                 VALUES (1, 1, '1001-1002', '1001', 'PASS', 'AB'),
+                        (1, 1, 'B1', NULL, 'FAIL', 'AB');",
+            )
+            .await
+            .unwrap();
+        let voltech_db = Database::connect("sqlite::memory:").await.unwrap();
+        migration_voltech::Migrator::up(&voltech_db, None)
+            .await
+            .unwrap();
+        let manual_db = crate::manual::queries::test_db(&[]).await;
+        let validate = |selection: RecordSelection| {
+            let (core_db, voltech_db, manual_db) = (&core_db, &voltech_db, &manual_db);
+            async move {
+                validate_report_data(1, &selection, core_db, voltech_db, manual_db)
+                    .await
+                    .unwrap()
+            }
+        };
+
+        // An outcome for 1001 does not cover 1002
+        let serialized = validate(RecordSelection {
+            serial_range: Some("1001 - 1002".to_string()),
+            ..Default::default()
+        })
+        .await;
+        let matrix = serialized.matrix.unwrap();
+        let statuses: Vec<CellStatus> = matrix.column(1).map(|c| c.status).collect();
+        assert_eq!(statuses, vec![CellStatus::Pass, CellStatus::Missing]);
+        assert!(!serialized.is_complete);
+
+        // A batch whose only outcome failed is not complete
+        core_db
+            .execute_unprepared("UPDATE fg SET serialized = 0")
+            .await
+            .unwrap();
+        let batch = validate(RecordSelection {
+            batch: Some("B1".to_string()),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(batch.test_statuses[0].record_count, 1);
+        assert!(!batch.test_statuses[0].has_data);
+        assert!(!batch.is_complete);
+    }
+}
@@ -0,0 +1,456 @@
+use crate::manual::limits::manual_verdict;
+use crate::reports::collector::{RawRecords, ReportData};
+use crate::reports::excel_generator::write_report_sheet;
+use crate::reports::template::{
+    create_fail_format, create_pass_format, create_regular_format, create_result_header_format,
+    ReportTemplate,
+};
+use ::entity::test as test_entity;
+use rust_xlsxwriter::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// ============================================================================
+// Multi-Sheet Workbook
+// ============================================================================
+//
+// The report sheet from `excel_generator`, optionally followed by sheets with
+// every raw source row, a serial-to-source traceability table and the spec
+// rows the report was generated against.
+// ============================================================================
+
+/// Extra sheets to add after the report sheet
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkbookSheets {
+    pub raw_data: bool,
+    pub traceability: bool,
+    pub spec: bool,
+}
+
+impl WorkbookSheets {
+    /// Whether raw source rows are needed to build the requested sheets
+    pub fn needs_raw_records(&self) -> bool {
+        self.raw_data || self.traceability
+    }
+}
+
+/// Data for the extra sheets
+pub struct WorkbookExtras<'a> {
+    pub sheets: &'a WorkbookSheets,
+    pub raw: &'a RawRecords,
+    pub spec_rows: &'a [test_entity::Model],
+}
+
+/// Generate the report workbook with any requested extra sheets
+pub fn generate_workbook(
+    data: &ReportData,
+    template: &ReportTemplate,
+    extras: &WorkbookExtras,
+) -> Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+    write_report_sheet(workbook.add_worksheet(), data, template)?;
+
+    if extras.sheets.raw_data {
+        write_raw_voltech_sheet(workbook.add_worksheet(), template, &extras.raw.voltech)?;
+        write_raw_manual_sheet(workbook.add_worksheet(), template, &extras.raw.manual)?;
+    }
+
+    if extras.sheets.traceability {
+        write_traceability_sheet(workbook.add_worksheet(), template, extras.raw)?;
+    }
+
+    if extras.sheets.spec {
+        write_spec_sheet(workbook.add_worksheet(), template, extras.spec_rows)?;
+    }
+
+    let buffer = workbook.save_to_buffer()?;
+    Ok(buffer)
+}
+
+fn write_headers(
+    worksheet: &mut Worksheet,
+    headers: &[&str],
+    format: &Format,
+) -> Result<(), XlsxError> {
+    for (col, header) in headers.iter().enumerate() {
+        worksheet.write_with_format(0, col as u16, *header, format)?;
+    }
+    worksheet.set_freeze_panes(1, 0)?;
+    Ok(())
+}
+
+fn verdict_format<'a>(
+    verdict: &str,
+    pass: &'a Format,
+    fail: &'a Format,
+    regular: &'a Format,
+) -> &'a Format {
+    let verdict = verdict.to_uppercase();
+    if verdict.contains("PASS") {
+        pass
+    } else if verdict.contains("FAIL") {
+        fail
+    } else {
+        regular
+    }
+}
+
+/// Voltech rows with their measurements spread across columns
+fn write_raw_voltech_sheet(
+    worksheet: &mut Worksheet,
+    template: &ReportTemplate,
+    records: &[entity_voltech::test_results::Model],
+) -> Result<(), XlsxError> {
+    worksheet.set_name("Raw Data - Voltech")?;
+    let style = &template.style_config;
+    let header_fmt = create_result_header_format(style);
+    let regular_fmt = create_regular_format(style);
+    let pass_fmt = create_pass_format(style);
+    let fail_fmt = create_fail_format(style);
+
+    let measurements: Vec<serde_json::Map<String, Value>> = records
+        .iter()
+        .map(|r| serde_json::from_str(&r.measurements).unwrap_or_default())
+        .collect();
+
+    // Every measurement key, in first-seen order
+    let mut keys: Vec<String> = Vec::new();
+    for map in &measurements {
+        for key in map.keys() {
+            if !keys.contains(key) {
+                keys.push(key.clone());
+            }
+        }
+    }
+
+    let fixed = [
+        "ID",
+        "Serial #",
+        "Batch",
+        "Part",
+        "Date",
+        "Time",
+        "Result #",
+        "Result",
+        "Retries",
+        "Operator",
+        "Source File",
+    ];
+    write_headers(worksheet, &fixed, &header_fmt)?;
+    for (offset, key) in keys.iter().enumerate() {
+        worksheet.write_with_format(0, (fixed.len() + offset) as u16, key, &header_fmt)?;
+    }
+
+    for (index, (record, map)) in records.iter().zip(&measurements).enumerate() {
+        let row = index as u32 + 1;
+        let result_fmt = verdict_format(&record.pass_fail, &pass_fmt, &fail_fmt, &regular_fmt);
+
+        worksheet.write_with_format(row, 0, record.id, &regular_fmt)?;
+        worksheet.write_with_format(row, 1, &record.serial_num, &regular_fmt)?;
+        worksheet.write_with_format(row, 2, &record.batch, &regular_fmt)?;
+        worksheet.write_with_format(row, 3, &record.part, &regular_fmt)?;
+        worksheet.write_with_format(row, 4, &record.date, &regular_fmt)?;
+        worksheet.write_with_format(row, 5, record.time.as_deref().unwrap_or(""), &regular_fmt)?;
+        worksheet.write_with_format(row, 6, record.result_num, &regular_fmt)?;
+        worksheet.write_with_format(row, 7, &record.pass_fail, result_fmt)?;
+        worksheet.write_with_format(
+            row,
+            8,
+            record.retries.as_deref().unwrap_or(""),
+            &regular_fmt,
+        )?;
+        worksheet.write_with_format(row, 9, &record.operator, &regular_fmt)?;
+        worksheet.write_with_format(row, 10, &record.file_path, &regular_fmt)?;
+
+        for (offset, key) in keys.iter().enumerate() {
+            let col = (fixed.len() + offset) as u16;
+            match map.get(key) {
+                Some(Value::Number(n)) => {
+                    worksheet.write_with_format(
+                        row,
+                        col,
+                        n.as_f64().unwrap_or_default(),
+                        &regular_fmt,
+                    )?;
+                }
+                Some(Value::String(s)) => {
+                    worksheet.write_with_format(row, col, s, &regular_fmt)?;
+                }
+                Some(other) => {
+                    worksheet.write_with_format(row, col, other.to_string(), &regular_fmt)?;
+                }
+                None => {}
+            }
+        }
+    }
+
+    worksheet.autofit();
+    Ok(())
+}
+
+fn write_raw_manual_sheet(
+    worksheet: &mut Worksheet,
+    template: &ReportTemplate,
+    records: &[entity_manual::manual_test_results::Model],
+) -> Result<(), XlsxError> {
+    worksheet.set_name("Raw Data - Manual")?;
+    let style = &template.style_config;
+    let header_fmt = create_result_header_format(style);
+    let regular_fmt = create_regular_format(style);
+    let pass_fmt = create_pass_format(style);
+    let fail_fmt = create_fail_format(style);
+
+    write_headers(
+        worksheet,
+        &[
+            "ID",
+            "Serial #",
+            "Batch",
+            "Test",
+            "Date",
+            "Time",
+            "Result #",
+            "Result",
+            "Minimum",
+            "Reading",
+            "Maximum",
+            "UoM",
+            "Operator",
+            "Source File",
+        ],
+        &header_fmt,
+    )?;
+
+    for (index, record) in records.iter().enumerate() {
+        let row = index as u32 + 1;
+        let result_fmt = verdict_format(&record.passfail, &pass_fmt, &fail_fmt, &regular_fmt);
+
+        worksheet.write_with_format(row, 0, record.id, &regular_fmt)?;
+        worksheet.write_with_format(row, 1, &record.sn, &regular_fmt)?;
+        worksheet.write_with_format(row, 2, &record.batch, &regular_fmt)?;
+        worksheet.write_with_format(row, 3, &record.test, &regular_fmt)?;
+        worksheet.write_with_format(row, 4, &record.date, &regular_fmt)?;
+        worksheet.write_with_format(row, 5, &record.time, &regular_fmt)?;
+        worksheet.write_with_format(row, 6, record.result, &regular_fmt)?;
+        worksheet.write_with_format(row, 7, &record.passfail, result_fmt)?;
+        worksheet.write_with_format(row, 8, record.minimum, &regular_fmt)?;
+        worksheet.write_with_format(row, 9, record.reading, &regular_fmt)?;
+        worksheet.write_with_format(row, 10, record.maximum, &regular_fmt)?;
+        worksheet.write_with_format(row, 11, &record.uom, &regular_fmt)?;
+        worksheet.write_with_format(row, 12, &record.operator, &regular_fmt)?;
+        worksheet.write_with_format(row, 13, &record.file_path, &regular_fmt)?;
+    }
+
+    worksheet.autofit();
+    Ok(())
+}
+
+/// One traceability line: which file, station and operator produced a result
+#[derive(Debug, Clone, PartialEq)]
+struct TraceRow {
+    serial: String,
+    batch: String,
+    source: &'static str,
+    test: String,
+    operator: String,
+    tested_at: String,
+    result: String,
+    file_path: String,
+    record_id: i32,
+}
+
+fn trace_rows(raw: &RawRecords) -> Vec<TraceRow> {
+    let voltech = raw.voltech.iter().map(|r| TraceRow {
+        serial: r.serial_num.clone(),
+        batch: r.batch.clone(),
+        source: "Voltech",
+        test: r.part.clone(),
+        operator: r.operator.clone(),
+        tested_at: match &r.time {
+            Some(time) => format!("{} {}", r.date, time),
+            None => r.date.clone(),
+        },
+        result: r.pass_fail.clone(),
+        file_path: r.file_path.clone(),
+        record_id: r.id,
+    });
+
+    let manual = raw.manual.iter().map(|r| TraceRow {
+        serial: r.sn.clone(),
+        batch: r.batch.clone(),
+        source: "Manual",
+        test: r.test.clone(),
+        operator: r.operator.clone(),
+        tested_at: r
+            .tested_at
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| format!("{} {}", r.normalized_date, r.time)),
+        result: manual_verdict(r).to_string(),
+        file_path: r.file_path.clone(),
+        record_id: r.id,
+    });
+
+    let mut rows: Vec<TraceRow> = voltech.chain(manual).collect();
+    rows.sort_by(|a, b| {
+        (&a.serial, &a.tested_at, a.source, a.record_id).cmp(&(
+            &b.serial,
+            &b.tested_at,
+            b.source,
+            b.record_id,
+        ))
+    });
+    rows
+}
+
+/// Each serial mapped to the source file, station, operator and time of every result
+fn write_traceability_sheet(
+    worksheet: &mut Worksheet,
+    template: &ReportTemplate,
+    raw: &RawRecords,
+) -> Result<(), XlsxError> {
+    worksheet.set_name("Traceability")?;
+    let style = &template.style_config;
+    let header_fmt = create_result_header_format(style);
+    let regular_fmt = create_regular_format(style);
+    let pass_fmt = create_pass_format(style);
+    let fail_fmt = create_fail_format(style);
+
+    write_headers(
+        worksheet,
+        &[
+            "Serial #",
+            "Batch",
+            "Source",
+            "Test / Program",
+            "Operator",
+            "Tested At",
+            "Result",
+            "Source File",
+            "Record ID",
+        ],
+        &header_fmt,
+    )?;
+
+    for (index, trace) in trace_rows(raw).iter().enumerate() {
+        let row = index as u32 + 1;
+        let result_fmt = verdict_format(&trace.result, &pass_fmt, &fail_fmt, &regular_fmt);
+
+        worksheet.write_with_format(row, 0, &trace.serial, &regular_fmt)?;
+        worksheet.write_with_format(row, 1, &trace.batch, &regular_fmt)?;
+        worksheet.write_with_format(row, 2, trace.source, &regular_fmt)?;
+        worksheet.write_with_format(row, 3, &trace.test, &regular_fmt)?;
+        worksheet.write_with_format(row, 4, &trace.operator, &regular_fmt)?;
+        worksheet.write_with_format(row, 5, &trace.tested_at, &regular_fmt)?;
+        worksheet.write_with_format(row, 6, &trace.result, result_fmt)?;
+        worksheet.write_with_format(row, 7, &trace.file_path, &regular_fmt)?;
+        worksheet.write_with_format(row, 8, trace.record_id, &regular_fmt)?;
+    }
+
+    worksheet.autofit();
+    Ok(())
+}
+
+/// The report's test rows with every spec column
+fn write_spec_sheet(
+    worksheet: &mut Worksheet,
+    template: &ReportTemplate,
+    spec_rows: &[test_entity::Model],
+) -> Result<(), XlsxError> {
+    worksheet.set_name("Specifications")?;
+    let style = &template.style_config;
+    let header_fmt = create_result_header_format(style);
+    let regular_fmt = create_regular_format(style);
+
+    write_headers(
+        worksheet,
+        &[
+            "Order",
+            "Test",
+            "Source",
+            "Associated Test",
+            "Minimum",
+            "Maximum",
+            "UoM",
+            "Voltage",
+            "Frequency",
+            "Primary Pins",
+            "Secondary Pins",
+            "Shorted Pins",
+            "Description",
+            "Last Updated",
+        ],
+        &header_fmt,
+    )?;
+
+    for (index, test) in spec_rows.iter().enumerate() {
+        let row = index as u32 + 1;
+
+        worksheet.write_with_format(row, 0, test.order, &regular_fmt)?;
+        worksheet.write_with_format(row, 1, &test.test_type, &regular_fmt)?;
+        worksheet.write_with_format(row, 2, &test.source_type, &regular_fmt)?;
+        worksheet.write_with_format(row, 3, test.associated_test.clone(), &regular_fmt)?;
+        worksheet.write_with_format(row, 4, test.minimum, &regular_fmt)?;
+        worksheet.write_with_format(row, 5, test.maximum, &regular_fmt)?;
+        worksheet.write_with_format(row, 6, &test.uo_m, &regular_fmt)?;
+        worksheet.write_with_format(row, 7, test.voltage, &regular_fmt)?;
+        worksheet.write_with_format(row, 8, test.frequency, &regular_fmt)?;
+        worksheet.write_with_format(row, 9, test.primary_pins.clone(), &regular_fmt)?;
+        worksheet.write_with_format(row, 10, test.secondary_pins.clone(), &regular_fmt)?;
+        worksheet.write_with_format(row, 11, test.shorted_pins.clone(), &regular_fmt)?;
+        worksheet.write_with_format(row, 12, test.description.clone(), &regular_fmt)?;
+        worksheet.write_with_format(row, 13, test.updated_at.to_string(), &regular_fmt)?;
+    }
+
+    worksheet.autofit();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_rows_merge_sources_by_serial_and_time() {
+        let raw: RawRecords = serde_json::from_value(serde_json::json!({
+            "voltech": [{
+                "id": 1, "part": "132520", "operator": "amy", "batch": "B1",
+                "date": "2025-11-19", "serial_num": "1002", "result_num": 1,
+                "pass_fail": "Pass", "time": "09:00:00", "retries": null,
+                "file_path": "V1/132520.atr", "measurements": "{}",
+                "created_at": "2025-11-19T09:00:00+00:00", "normalized_date": "2025-11-19"
+            }],
+            "manual": [{
+                "id": 7, "result": 1, "test": "132520-LFG-DCR1", "fg": "132520", "rev": "FTA",
+                "batch": "B1", "operator": "bob", "date": "11/18/2025", "time": "14:30",
+                "sn": "1002", "passfail": "FAIL", "minimum": 1.0, "reading": 2.5,
+                "maximum": 2.0, "uom": "Ohm", "file_path": "manual/dcr.csv",
+                "created_at": "2025-11-18T14:30:00+00:00", "normalized_date": "2025-11-18",
+                "computed_passfail": null, "tested_at": "2025-11-18T14:30:00"
+            }]
+        }))
+        .unwrap();
+
+        let rows = trace_rows(&raw);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].source, "Manual");
+        assert_eq!(rows[0].tested_at, "2025-11-18 14:30:00");
+        assert_eq!(rows[1].source, "Voltech");
+        assert_eq!(rows[1].tested_at, "2025-11-19 09:00:00");
+        assert_eq!(rows[1].file_path, "V1/132520.atr");
+    }
+
+    #[test]
+    fn test_workbook_sheets_flags() {
+        let sheets = WorkbookSheets {
+            spec: true,
+            ..Default::default()
+        };
+        assert!(!sheets.needs_raw_records());
+        assert!(WorkbookSheets {
+            traceability: true,
+            ..Default::default()
+        }
+        .needs_raw_records());
+    }
+}
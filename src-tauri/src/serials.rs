@@ -0,0 +1,325 @@
+// Serial number specifications for serialized reports
+//
+// A spec is a comma-separated list of serials and serial ranges, such as
+// "1001-1050, 1060, 1070-1080". Serials may carry an alphanumeric prefix
+// ("SN-1001-SN-1050", "A001-A010") and leading zeros. Records match on prefix
+// (ignoring case) and numeric value, so "001001" and "1001" are one serial.
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, Condition, IdenStatic, Value};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Largest number of serials a spec may cover
+const MAX_SERIALS: i64 = 100_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SerialRange {
+    prefix: String, // As typed on the first serial, e.g. "SN-"
+    start: i64,
+    end: i64,
+    width: usize, // Digits on the first serial; labels are zero-padded to it
+}
+
+/// Split a serial into its prefix and trailing digits
+fn split_serial(serial: &str) -> Option<(&str, &str)> {
+    let serial = serial.trim();
+    let prefix = serial.trim_end_matches(|c: char| c.is_ascii_digit());
+    let digits = &serial[prefix.len()..];
+    (!digits.is_empty()).then_some((prefix, digits))
+}
+
+fn parse_number(digits: &str) -> Result<i64, String> {
+    digits
+        .parse()
+        .map_err(|_| format!("Serial number '{}' is too long", digits))
+}
+
+impl SerialRange {
+    /// Parse "1001", "1001-1050", "A001-A010" or "A001-010"
+    /// The range dash is the first one with a serial on both sides, since
+    /// prefixes may contain dashes themselves
+    fn parse(token: &str) -> Result<Self, String> {
+        let range = token
+            .match_indices('-')
+            .find_map(|(i, _)| Some((split_serial(&token[..i])?, split_serial(&token[i + 1..])?)));
+
+        let ((prefix, start), end) = match range {
+            Some((first, (end_prefix, end))) => {
+                // The last serial may leave out the prefix
+                if !end_prefix.is_empty() && !end_prefix.eq_ignore_ascii_case(first.0) {
+                    return Err(format!("Serial range '{}' mixes prefixes", token));
+                }
+                (first, end)
+            }
+            None => {
+                let serial = split_serial(token)
+                    .ok_or_else(|| format!("'{}' is not a serial number", token))?;
+                (serial, serial.1)
+            }
+        };
+
+        let range = SerialRange {
+            prefix: prefix.to_string(),
+            start: parse_number(start)?,
+            end: parse_number(end)?,
+            width: start.len(),
+        };
+        if range.start > range.end {
+            return Err(format!("Serial range '{}' runs backwards", token));
+        }
+        Ok(range)
+    }
+
+    fn label(&self, number: i64) -> String {
+        format!("{}{:0width$}", self.prefix, number, width = self.width)
+    }
+
+    fn matches(&self, prefix: &str, number: i64) -> bool {
+        prefix.eq_ignore_ascii_case(&self.prefix) && (self.start..=self.end).contains(&number)
+    }
+
+    /// SQL equivalent of `matches` for a serial column
+    fn expr(&self, column: &str) -> Expr {
+        let serial = format!("TRIM({})", column);
+        let digits = format!("SUBSTR({}, {})", serial, self.prefix.chars().count() + 1);
+        Expr::cust_with_values(
+            format!(
+                "(UPPER(SUBSTR({serial}, 1, {len})) = ? AND {digits} <> '' \
+                 AND {digits} NOT GLOB '*[^0-9]*' AND CAST({digits} AS INTEGER) BETWEEN ? AND ?)",
+                serial = serial,
+                len = self.prefix.chars().count(),
+                digits = digits,
+            ),
+            [
+                Value::from(self.prefix.to_ascii_uppercase()),
+                Value::from(self.start),
+                Value::from(self.end),
+            ],
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SerialSpec {
+    ranges: Vec<SerialRange>,
+}
+
+impl SerialSpec {
+    /// Parse a spec such as "1001-1050, 1060, 1070-1080"
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let ranges = text
+            .split([',', ';', '\n'])
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .map(SerialRange::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if ranges.is_empty() {
+            return Err("No serial numbers given".to_string());
+        }
+        // Ranges near the i64 limits would overflow the count
+        let total = ranges.iter().try_fold(0i64, |total, r| {
+            r.end
+                .checked_sub(r.start)?
+                .checked_add(1)?
+                .checked_add(total)
+        });
+        match total {
+            Some(total) if total <= MAX_SERIALS => {}
+            Some(total) => {
+                return Err(format!(
+                    "Serial specification covers {} serials; the limit is {}",
+                    total, MAX_SERIALS
+                ))
+            }
+            None => {
+                return Err(format!(
+                    "Serial specification covers more than the limit of {} serials",
+                    MAX_SERIALS
+                ))
+            }
+        }
+        Ok(Self { ranges })
+    }
+
+    /// Every serial in spec order, labelled and padded as the spec writes
+    /// them. Serials listed twice appear once.
+    pub fn serials(&self) -> Vec<String> {
+        let mut seen = HashSet::new();
+        let mut serials = Vec::new();
+        for range in &self.ranges {
+            for number in range.start..=range.end {
+                if seen.insert((range.prefix.to_ascii_uppercase(), number)) {
+                    serials.push(range.label(number));
+                }
+            }
+        }
+        serials
+    }
+
+    /// Text shared by every spec covering the same serials, for keying what
+    /// is recorded against a run: ranges sorted and merged, prefixes
+    /// uppercased and numbers unpadded ("01001 - 01050" -> "1001-1050")
+    pub fn canonical(&self) -> String {
+        let mut sorted: Vec<SerialRange> = self
+            .ranges
+            .iter()
+            .map(|range| SerialRange {
+                prefix: range.prefix.to_ascii_uppercase(),
+                start: range.start,
+                end: range.end,
+                width: 0,
+            })
+            .collect();
+        sorted.sort_by(|a, b| (&a.prefix, a.start).cmp(&(&b.prefix, b.start)));
+
+        let mut ranges: Vec<SerialRange> = Vec::new();
+        for range in sorted {
+            match ranges.last_mut() {
+                Some(last) if last.prefix == range.prefix && range.start <= last.end + 1 => {
+                    last.end = last.end.max(range.end);
+                }
+                _ => ranges.push(range),
+            }
+        }
+        Self { ranges }.to_string()
+    }
+
+    /// The spec's label for a recorded serial ("001001" -> "1001"), if the
+    /// spec covers it
+    pub fn label(&self, serial: &str) -> Option<String> {
+        let (prefix, digits) = split_serial(serial)?;
+        let number = digits.parse().ok()?;
+        self.ranges
+            .iter()
+            .find(|range| range.matches(prefix, number))
+            .map(|range| range.label(number))
+    }
+
+    #[cfg(test)]
+    pub fn contains(&self, serial: &str) -> bool {
+        self.label(serial).is_some()
+    }
+
+    /// The shortest spec covering recorded serials: runs of consecutive
+    /// numbers with the same prefix become ranges. Serials without trailing
+    /// digits are skipped; None if no serial is left.
+    pub fn from_serials<'s>(serials: impl IntoIterator<Item = &'s str>) -> Option<Self> {
+        let mut numbered: Vec<(String, i64, usize)> = serials
+            .into_iter()
+            .filter_map(|serial| {
+                let (prefix, digits) = split_serial(serial)?;
+                Some((prefix.to_string(), digits.parse().ok()?, digits.len()))
+            })
+            .collect();
+        numbered.sort_by_key(|(prefix, number, _)| (prefix.to_ascii_uppercase(), *number));
+
+        let mut ranges: Vec<SerialRange> = Vec::new();
+        for (prefix, number, width) in numbered {
+            match ranges.last_mut() {
+                Some(last)
+                    if last.prefix.eq_ignore_ascii_case(&prefix) && number <= last.end + 1 =>
+                {
+                    last.end = last.end.max(number);
+                }
+                _ => ranges.push(SerialRange {
+                    prefix,
+                    start: number,
+                    end: number,
+                    width,
+                }),
+            }
+        }
+        (!ranges.is_empty()).then_some(Self { ranges })
+    }
+
+    /// Filter selecting the serials `contains` accepts
+    pub fn condition(&self, column: impl ColumnTrait) -> Condition {
+        self.ranges
+            .iter()
+            .fold(Condition::any(), |condition, range| {
+                condition.add(range.expr(column.as_str()))
+            })
+    }
+}
+
+impl fmt::Display for SerialSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, range) in self.ranges.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", range.label(range.start))?;
+            if range.end != range.start {
+                write!(f, "-{}", range.label(range.end))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lists_and_ranges() {
+        let spec = SerialSpec::parse("1001-1003, 1060,1002 ; 1070-1071").unwrap();
+        assert_eq!(
+            spec.serials(),
+            vec!["1001", "1002", "1003", "1060", "1070", "1071"]
+        );
+        assert_eq!(spec.to_string(), "1001-1003, 1060, 1002, 1070-1071");
+
+        let prefixed = SerialSpec::parse("SN-0098-SN-0101, a7").unwrap();
+        assert_eq!(
+            prefixed.serials(),
+            vec!["SN-0098", "SN-0099", "SN-0100", "SN-0101", "a7"]
+        );
+        assert_eq!(SerialSpec::parse("A001-010").unwrap().serials().len(), 10);
+
+        assert!(SerialSpec::parse("").is_err());
+        assert!(SerialSpec::parse("1050-1001").is_err());
+        assert!(SerialSpec::parse("A001-B010").is_err());
+        assert!(SerialSpec::parse("ABC").is_err());
+        assert!(SerialSpec::parse("1-1000000").is_err());
+        assert!(SerialSpec::parse("0-9223372036854775807").is_err());
+        assert!(SerialSpec::parse("1-100000, 0-9223372036854775807").is_err());
+    }
+
+    #[test]
+    fn test_match_recorded_serials() {
+        let spec = SerialSpec::parse("1001-1050, A001-A010").unwrap();
+        assert_eq!(spec.label("001001").as_deref(), Some("1001"));
+        assert_eq!(spec.label(" 1050 ").as_deref(), Some("1050"));
+        assert_eq!(spec.label("a5").as_deref(), Some("A005"));
+        assert!(!spec.contains("1051"));
+        assert!(!spec.contains("B005"));
+        assert!(!spec.contains("X1001"));
+        assert!(!spec.contains("N/A"));
+
+        // Zero-padded specs match unpadded records too
+        let padded = SerialSpec::parse("026-095").unwrap();
+        assert_eq!(padded.label("26").as_deref(), Some("026"));
+    }
+
+    #[test]
+    fn test_canonical_ignores_spelling() {
+        let canonical = |text: &str| SerialSpec::parse(text).unwrap().canonical();
+        assert_eq!(canonical("1001-1050"), "1001-1050");
+        assert_eq!(canonical("1001 - 1050"), "1001-1050");
+        assert_eq!(canonical("01001-01050"), "1001-1050");
+        assert_eq!(canonical("1026-1050, 1001-1025"), "1001-1050");
+        assert_eq!(canonical("sn-7, SN-8, 3"), "3, SN-7-SN-8");
+    }
+
+    #[test]
+    fn test_spec_from_recorded_serials() {
+        let spec =
+            SerialSpec::from_serials(["1003", "1001", "1002", "1002", "1010", "A7", "a8", "N/A"])
+                .unwrap();
+        assert_eq!(spec.to_string(), "1001-1003, 1010, A7-A8");
+        assert_eq!(spec.serials().len(), 6);
+        assert!(SerialSpec::from_serials(["N/A"]).is_none());
+    }
+}
@@ -0,0 +1,301 @@
+// Shared query API over the Voltech and manual test sources
+use crate::manual::commands::{model_to_response, ManualTestResponse};
+use crate::manual::queries as manual_queries;
+use crate::voltech::queries::{
+    self as voltech_queries, BatchListItem, BatchSearchFilter, BatchSummary, DailyStats,
+    OperatorStats, OverallStats, PartSummary, TestSearchFilter,
+};
+use crate::AppState;
+use entity_voltech::test_results;
+use sea_orm::{DbConn, DbErr};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+/// Test data source, matching `test.source_type` in the core database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TestSource {
+    Voltech,
+    Manual,
+}
+
+/// Individual test rows from either source
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum SourceTestRecords {
+    Voltech(Vec<test_results::Model>),
+    Manual(Vec<ManualTestResponse>),
+}
+
+/// Queries every test source supports
+/// `part` means the Voltech part number or the manual FG number
+pub trait SourceQueries {
+    async fn search_tests(
+        db: &DbConn,
+        filter: TestSearchFilter,
+    ) -> Result<SourceTestRecords, DbErr>;
+    async fn count_tests(db: &DbConn, filter: TestSearchFilter) -> Result<u64, DbErr>;
+    async fn get_failed_tests(db: &DbConn, limit: Option<u64>) -> Result<SourceTestRecords, DbErr>;
+    async fn search_batches(
+        db: &DbConn,
+        filter: BatchSearchFilter,
+    ) -> Result<Vec<BatchListItem>, DbErr>;
+    async fn get_batch_details(db: &DbConn, batch: &str) -> Result<Option<BatchSummary>, DbErr>;
+    async fn get_part_summary(db: &DbConn, part: &str) -> Result<Option<PartSummary>, DbErr>;
+    async fn get_daily_stats(
+        db: &DbConn,
+        date_from: Option<&str>,
+        date_to: Option<&str>,
+    ) -> Result<Vec<DailyStats>, DbErr>;
+    async fn get_operator_stats(
+        db: &DbConn,
+        date_from: Option<&str>,
+        date_to: Option<&str>,
+    ) -> Result<Vec<OperatorStats>, DbErr>;
+    async fn get_overall_stats(db: &DbConn) -> Result<Option<OverallStats>, DbErr>;
+}
+
+pub struct VoltechSource;
+pub struct ManualSource;
+
+impl SourceQueries for VoltechSource {
+    async fn search_tests(
+        db: &DbConn,
+        filter: TestSearchFilter,
+    ) -> Result<SourceTestRecords, DbErr> {
+        voltech_queries::search_tests(db, filter)
+            .await
+            .map(SourceTestRecords::Voltech)
+    }
+
+    async fn count_tests(db: &DbConn, filter: TestSearchFilter) -> Result<u64, DbErr> {
+        voltech_queries::count_tests(db, filter).await
+    }
+
+    async fn get_failed_tests(db: &DbConn, limit: Option<u64>) -> Result<SourceTestRecords, DbErr> {
+        voltech_queries::get_failed_tests(db, limit)
+            .await
+            .map(SourceTestRecords::Voltech)
+    }
+
+    async fn search_batches(
+        db: &DbConn,
+        filter: BatchSearchFilter,
+    ) -> Result<Vec<BatchListItem>, DbErr> {
+        voltech_queries::search_batches(db, filter).await
+    }
+
+    async fn get_batch_details(db: &DbConn, batch: &str) -> Result<Option<BatchSummary>, DbErr> {
+        voltech_queries::get_batch_details(db, batch).await
+    }
+
+    async fn get_part_summary(db: &DbConn, part: &str) -> Result<Option<PartSummary>, DbErr> {
+        voltech_queries::get_part_summary(db, part).await
+    }
+
+    async fn get_daily_stats(
+        db: &DbConn,
+        date_from: Option<&str>,
+        date_to: Option<&str>,
+    ) -> Result<Vec<DailyStats>, DbErr> {
+        voltech_queries::get_daily_stats(db, date_from, date_to).await
+    }
+
+    async fn get_operator_stats(
+        db: &DbConn,
+        date_from: Option<&str>,
+        date_to: Option<&str>,
+    ) -> Result<Vec<OperatorStats>, DbErr> {
+        voltech_queries::get_operator_stats(db, date_from, date_to).await
+    }
+
+    async fn get_overall_stats(db: &DbConn) -> Result<Option<OverallStats>, DbErr> {
+        voltech_queries::get_overall_stats(db).await
+    }
+}
+
+fn to_manual_filter(filter: TestSearchFilter) -> manual_queries::ManualTestSearchFilter {
+    manual_queries::ManualTestSearchFilter {
+        fg: filter.part,
+        batch: filter.batch,
+        test_name: None,
+        operator: filter.operator,
+        date_from: filter.date_from,
+        date_to: filter.date_to,
+        pass_fail: filter.pass_fail,
+        serial_num: filter.serial_num,
+        limit: filter.limit,
+        offset: filter.offset,
+    }
+}
+
+impl SourceQueries for ManualSource {
+    async fn search_tests(
+        db: &DbConn,
+        filter: TestSearchFilter,
+    ) -> Result<SourceTestRecords, DbErr> {
+        let results = manual_queries::search_manual_tests(db, to_manual_filter(filter)).await?;
+        Ok(SourceTestRecords::Manual(
+            results.into_iter().map(model_to_response).collect(),
+        ))
+    }
+
+    async fn count_tests(db: &DbConn, filter: TestSearchFilter) -> Result<u64, DbErr> {
+        manual_queries::count_manual_tests(db, to_manual_filter(filter)).await
+    }
+
+    async fn get_failed_tests(db: &DbConn, limit: Option<u64>) -> Result<SourceTestRecords, DbErr> {
+        let results = manual_queries::get_manual_failed_tests(db, None, limit).await?;
+        Ok(SourceTestRecords::Manual(
+            results.into_iter().map(model_to_response).collect(),
+        ))
+    }
+
+    async fn search_batches(
+        db: &DbConn,
+        filter: BatchSearchFilter,
+    ) -> Result<Vec<BatchListItem>, DbErr> {
+        manual_queries::search_manual_batches(db, filter).await
+    }
+
+    async fn get_batch_details(db: &DbConn, batch: &str) -> Result<Option<BatchSummary>, DbErr> {
+        manual_queries::get_manual_batch_details(db, batch).await
+    }
+
+    async fn get_part_summary(db: &DbConn, part: &str) -> Result<Option<PartSummary>, DbErr> {
+        manual_queries::get_manual_fg_summary(db, part).await
+    }
+
+    async fn get_daily_stats(
+        db: &DbConn,
+        date_from: Option<&str>,
+        date_to: Option<&str>,
+    ) -> Result<Vec<DailyStats>, DbErr> {
+        manual_queries::get_manual_daily_stats(db, date_from, date_to).await
+    }
+
+    async fn get_operator_stats(
+        db: &DbConn,
+        date_from: Option<&str>,
+        date_to: Option<&str>,
+    ) -> Result<Vec<OperatorStats>, DbErr> {
+        manual_queries::get_manual_operator_stats(db, date_from, date_to).await
+    }
+
+    async fn get_overall_stats(db: &DbConn) -> Result<Option<OverallStats>, DbErr> {
+        manual_queries::get_manual_overall_stats(db, None).await
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Dispatch a `SourceQueries` call to the right source and database
+macro_rules! dispatch {
+    ($state:expr, $source:expr, $method:ident($($arg:expr),*)) => {
+        match $source {
+            TestSource::Voltech => VoltechSource::$method(&$state.voltech_db, $($arg),*).await,
+            TestSource::Manual => ManualSource::$method(&$state.manual_db, $($arg),*).await,
+        }
+    };
+}
+
+#[tauri::command]
+pub async fn search_source_tests(
+    state: State<'_, AppState>,
+    source: TestSource,
+    filter: TestSearchFilter,
+) -> Result<SourceTestRecords, String> {
+    dispatch!(state, source, search_tests(filter))
+        .map_err(|e| format!("Failed to search tests: {}", e))
+}
+
+#[tauri::command]
+pub async fn count_source_tests(
+    state: State<'_, AppState>,
+    source: TestSource,
+    filter: TestSearchFilter,
+) -> Result<u64, String> {
+    dispatch!(state, source, count_tests(filter))
+        .map_err(|e| format!("Failed to count tests: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_source_failed_tests(
+    state: State<'_, AppState>,
+    source: TestSource,
+    limit: Option<u64>,
+) -> Result<SourceTestRecords, String> {
+    dispatch!(state, source, get_failed_tests(limit))
+        .map_err(|e| format!("Failed to get failed tests: {}", e))
+}
+
+#[tauri::command]
+pub async fn search_source_batches(
+    state: State<'_, AppState>,
+    source: TestSource,
+    filter: BatchSearchFilter,
+) -> Result<Vec<BatchListItem>, String> {
+    dispatch!(state, source, search_batches(filter))
+        .map_err(|e| format!("Failed to search batches: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_source_batch_details(
+    state: State<'_, AppState>,
+    source: TestSource,
+    batch: String,
+) -> Result<Option<BatchSummary>, String> {
+    dispatch!(state, source, get_batch_details(&batch))
+        .map_err(|e| format!("Failed to get batch details: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_source_part_summary(
+    state: State<'_, AppState>,
+    source: TestSource,
+    part: String,
+) -> Result<Option<PartSummary>, String> {
+    dispatch!(state, source, get_part_summary(&part))
+        .map_err(|e| format!("Failed to get part summary: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_source_daily_stats(
+    state: State<'_, AppState>,
+    source: TestSource,
+    date_from: Option<String>,
+    date_to: Option<String>,
+) -> Result<Vec<DailyStats>, String> {
+    dispatch!(
+        state,
+        source,
+        get_daily_stats(date_from.as_deref(), date_to.as_deref())
+    )
+    .map_err(|e| format!("Failed to get daily stats: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_source_operator_stats(
+    state: State<'_, AppState>,
+    source: TestSource,
+    date_from: Option<String>,
+    date_to: Option<String>,
+) -> Result<Vec<OperatorStats>, String> {
+    dispatch!(
+        state,
+        source,
+        get_operator_stats(date_from.as_deref(), date_to.as_deref())
+    )
+    .map_err(|e| format!("Failed to get operator stats: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_source_overall_stats(
+    state: State<'_, AppState>,
+    source: TestSource,
+) -> Result<Option<OverallStats>, String> {
+    dispatch!(state, source, get_overall_stats())
+        .map_err(|e| format!("Failed to get overall stats: {}", e))
+}
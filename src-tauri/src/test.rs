@@ -1,3 +1,4 @@
+use crate::units::normalize_uom;
 use crate::AppState;
 use entity::test;
 use sea_orm::{ActiveModelTrait, EntityTrait, Set};
@@ -71,7 +72,7 @@ pub async fn create_test(
         voltage: Set(test_data.voltage),
         minimum: Set(test_data.minimum),
         maximum: Set(test_data.maximum),
-        uo_m: Set(test_data.uo_m),
+        uo_m: Set(normalize_uom(&test_data.uo_m)),
         primary_pins: Set(test_data.primary_pins),
         secondary_pins: Set(test_data.secondary_pins),
         shorted_pins: Set(test_data.shorted_pins),
@@ -202,7 +203,7 @@ pub async fn update_test(
     test_model.voltage = Set(test_data.voltage);
     test_model.minimum = Set(test_data.minimum);
     test_model.maximum = Set(test_data.maximum);
-    test_model.uo_m = Set(test_data.uo_m);
+    test_model.uo_m = Set(normalize_uom(&test_data.uo_m));
     test_model.primary_pins = Set(test_data.primary_pins);
     test_model.secondary_pins = Set(test_data.secondary_pins);
     test_model.shorted_pins = Set(test_data.shorted_pins);
@@ -1,3 +1,4 @@
+use crate::part_mapping::{manual_parts, voltech_parts};
 use crate::AppState;
 use entity_manual::manual_test_results;
 use entity_voltech::test_results as voltech_test_results;
@@ -163,8 +164,9 @@ pub async fn get_test_type_options() -> Result<Vec<(String, String, String)>, Db
 /// Find associated tests for a given FG and test type
 ///
 /// # Arguments
-/// * `fg` - Finished Good number
+/// * `fg` - Finished Good number (any revision)
 /// * `test_type` - Test type name (e.g., "Inductance", "Leakage")
+/// * `core_db` - Core database connection, for the FG's part mappings
 /// * `voltech_db` - Voltech database connection
 /// * `manual_db` - Manual database connection
 ///
@@ -173,6 +175,7 @@ pub async fn get_test_type_options() -> Result<Vec<(String, String, String)>, Db
 pub async fn find_associated_tests(
     fg: &str,
     test_type: &str,
+    core_db: &DbConn,
     voltech_db: &DbConn,
     manual_db: &DbConn,
 ) -> Result<Vec<String>, DbErr> {
@@ -193,9 +196,10 @@ pub async fn find_associated_tests(
             // Query voltech database for tests with pattern in measurements JSON keys
             // The measurements field contains JSON like: {"LS 001": {...}, "LS   002": {...}}
             // We need to extract keys that contain the pattern
-            // Match part numbers that start with fg (e.g., "132520" matches "132520FTA", "132520PTA")
+            // Match the FG's part numbers at any revision (e.g., "132520" matches "132520FTA", "132520PTA")
+            let parts = voltech_parts(core_db, voltech_db, fg, None).await?;
             let voltech_results = voltech_test_results::Entity::find()
-                .filter(voltech_test_results::Column::Part.starts_with(fg))
+                .filter(voltech_test_results::Column::Part.is_in(parts))
                 .all(voltech_db)
                 .await?;
 
@@ -222,9 +226,10 @@ pub async fn find_associated_tests(
         }
         "Manual Electrical" | "Inspection" | "Stress Testing" | "Manual" => {
             // Query manual database for tests with pattern in test name (case-insensitive)
-            // Match FG numbers that start with fg (e.g., "132520" matches "132520FTA", "132520PTA")
+            // Match the FG's manual FG numbers at any revision (e.g., "132520" matches "132520FTA")
+            let parts = manual_parts(core_db, manual_db, fg, None).await?;
             let manual_results = manual_test_results::Entity::find()
-                .filter(manual_test_results::Column::Fg.starts_with(fg))
+                .filter(manual_test_results::Column::Fg.is_in(parts))
                 .all(manual_db)
                 .await?;
 
@@ -260,7 +265,15 @@ pub async fn find_tests_for_type(
     test_type: String,
     state: State<'_, AppState>,
 ) -> Result<Vec<String>, String> {
-    match find_associated_tests(&fg, &test_type, &state.voltech_db, &state.manual_db).await {
+    match find_associated_tests(
+        &fg,
+        &test_type,
+        &state.core_db,
+        &state.voltech_db,
+        &state.manual_db,
+    )
+    .await
+    {
         Ok(tests) => Ok(tests),
         Err(e) => Err(format!("Failed to find associated tests: {}", e)),
     }
@@ -0,0 +1,308 @@
+// Units of measure for test specs and report readings
+//
+// A unit is a base unit plus an SI prefix ("mH" = milli + henry). Voltech
+// readings are recorded in base units, so they are scaled to the unit declared
+// on the test spec before being shown next to its limits.
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SiPrefix {
+    Pico,
+    Nano,
+    Micro,
+    Milli,
+    None,
+    Kilo,
+    Mega,
+    Giga,
+}
+
+impl SiPrefix {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            'p' => Some(SiPrefix::Pico),
+            'n' => Some(SiPrefix::Nano),
+            'u' | 'µ' | 'μ' => Some(SiPrefix::Micro),
+            'm' => Some(SiPrefix::Milli),
+            'k' | 'K' => Some(SiPrefix::Kilo),
+            'M' => Some(SiPrefix::Mega),
+            'G' => Some(SiPrefix::Giga),
+            _ => None,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            SiPrefix::Pico => "p",
+            SiPrefix::Nano => "n",
+            SiPrefix::Micro => "µ",
+            SiPrefix::Milli => "m",
+            SiPrefix::None => "",
+            SiPrefix::Kilo => "k",
+            SiPrefix::Mega => "M",
+            SiPrefix::Giga => "G",
+        }
+    }
+
+    pub fn factor(&self) -> f64 {
+        match self {
+            SiPrefix::Pico => 1e-12,
+            SiPrefix::Nano => 1e-9,
+            SiPrefix::Micro => 1e-6,
+            SiPrefix::Milli => 1e-3,
+            SiPrefix::None => 1.0,
+            SiPrefix::Kilo => 1e3,
+            SiPrefix::Mega => 1e6,
+            SiPrefix::Giga => 1e9,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaseUnit {
+    Henry,
+    Ohm,
+    Farad,
+    Volt,
+    Ampere,
+    Hertz,
+    Watt,
+    Second,
+}
+
+impl BaseUnit {
+    fn parse(text: &str) -> Option<Self> {
+        match text {
+            "H" => Some(BaseUnit::Henry),
+            "Ω" | "Ω" => Some(BaseUnit::Ohm),
+            "F" => Some(BaseUnit::Farad),
+            "V" => Some(BaseUnit::Volt),
+            "A" => Some(BaseUnit::Ampere),
+            "Hz" | "HZ" => Some(BaseUnit::Hertz),
+            "W" => Some(BaseUnit::Watt),
+            "s" | "sec" => Some(BaseUnit::Second),
+            _ if text.eq_ignore_ascii_case("ohm") || text.eq_ignore_ascii_case("ohms") => {
+                Some(BaseUnit::Ohm)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            BaseUnit::Henry => "H",
+            BaseUnit::Ohm => "Ω",
+            BaseUnit::Farad => "F",
+            BaseUnit::Volt => "V",
+            BaseUnit::Ampere => "A",
+            BaseUnit::Hertz => "Hz",
+            BaseUnit::Watt => "W",
+            BaseUnit::Second => "s",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unit {
+    pub prefix: SiPrefix,
+    pub base: BaseUnit,
+}
+
+impl Unit {
+    /// Parse a unit such as "mH", "uH", "kOhm" or "Ω"
+    /// Free-text units ("Turns", "%") are not units and return None
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if let Some(base) = BaseUnit::parse(text) {
+            return Some(Unit {
+                prefix: SiPrefix::None,
+                base,
+            });
+        }
+
+        let mut chars = text.chars();
+        let prefix = SiPrefix::from_char(chars.next()?)?;
+        let base = BaseUnit::parse(chars.as_str())?;
+        Some(Unit { prefix, base })
+    }
+
+    /// Scale a value in base units (as Voltech records them) to this unit
+    pub fn scale_from_base(&self, value: f64) -> f64 {
+        value / self.prefix.factor()
+    }
+
+    /// Convert a value in this unit to another unit of the same quantity
+    pub fn convert_to(&self, value: f64, to: &Unit) -> Option<f64> {
+        (self.base == to.base).then(|| value * self.prefix.factor() / to.prefix.factor())
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.prefix.symbol(), self.base.symbol())
+    }
+}
+
+/// Canonical spelling of a declared unit ("uH" -> "µH", "kohm" -> "kΩ")
+/// Anything that is not a recognised unit is kept as typed
+pub fn normalize_uom(text: &str) -> String {
+    Unit::parse(text)
+        .map(|unit| unit.to_string())
+        .unwrap_or_else(|| text.trim().to_string())
+}
+
+// ============================================================================
+// Number Formatting
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Notation {
+    Fixed,       // 12.28
+    Scientific,  // 1.228E-2
+    Engineering, // 12.28E-3, exponent a multiple of 3
+}
+
+/// How readings and limits are displayed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NumberFormat {
+    pub significant_digits: u8,
+    pub notation: Notation,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            significant_digits: 4,
+            notation: Notation::Fixed,
+        }
+    }
+}
+
+/// Power of ten of the leading digit, rounded down to a multiple of `step`
+fn exponent(value: f64, step: i32) -> i32 {
+    if value == 0.0 || !value.is_finite() {
+        return 0;
+    }
+    let exp = value.abs().log10().floor() as i32;
+    exp.div_euclid(step) * step
+}
+
+impl NumberFormat {
+    fn digits(&self) -> i32 {
+        self.significant_digits.clamp(1, 15) as i32
+    }
+
+    /// Round to the configured significant digits
+    pub fn round(&self, value: f64) -> f64 {
+        if value == 0.0 || !value.is_finite() {
+            return value;
+        }
+        let scale = 10f64.powi(self.digits() - 1 - exponent(value, 1));
+        (value * scale).round() / scale
+    }
+
+    /// Exponent shown for a value (0 in fixed notation)
+    fn shown_exponent(&self, value: f64) -> i32 {
+        match self.notation {
+            Notation::Fixed => 0,
+            Notation::Scientific => exponent(value, 1),
+            Notation::Engineering => exponent(value, 3),
+        }
+    }
+
+    /// Digits after the decimal point for a value
+    fn decimals(&self, value: f64) -> usize {
+        let rounded = self.round(value);
+        let mantissa = rounded / 10f64.powi(self.shown_exponent(rounded));
+        (self.digits() - 1 - exponent(mantissa, 1)).max(0) as usize
+    }
+
+    /// Text for PDF and other plain-text output
+    pub fn format(&self, value: f64) -> String {
+        let rounded = self.round(value);
+        let decimals = self.decimals(value);
+        match self.notation {
+            Notation::Fixed => format!("{:.*}", decimals, rounded),
+            _ => {
+                let exp = self.shown_exponent(rounded);
+                let mantissa = rounded / 10f64.powi(exp);
+                format!("{:.*}E{:+}", decimals, mantissa, exp)
+            }
+        }
+    }
+
+    /// Excel number format for a value; the cell keeps the full-precision value
+    pub fn excel_format(&self, value: f64) -> String {
+        let decimals = self.decimals(value);
+        let fraction = if decimals > 0 {
+            format!(".{}", "0".repeat(decimals))
+        } else {
+            String::new()
+        };
+        match self.notation {
+            Notation::Fixed => format!("0{}", fraction),
+            Notation::Scientific => format!("0{}E+0", fraction),
+            Notation::Engineering => format!("##0{}E+0", fraction),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_units() {
+        let mh = Unit::parse("mH").unwrap();
+        assert_eq!(mh.prefix, SiPrefix::Milli);
+        assert_eq!(mh.base, BaseUnit::Henry);
+        assert_eq!(Unit::parse(" uH ").unwrap().to_string(), "µH");
+        assert_eq!(Unit::parse("kOhm").unwrap().to_string(), "kΩ");
+        assert_eq!(Unit::parse("Ohms").unwrap().to_string(), "Ω");
+        assert_eq!(Unit::parse("MΩ").unwrap().prefix, SiPrefix::Mega);
+        assert_eq!(Unit::parse("Hz").unwrap().base, BaseUnit::Hertz);
+        assert_eq!(Unit::parse("Turns"), None);
+        assert_eq!(Unit::parse(""), None);
+        assert_eq!(normalize_uom("%"), "%");
+        assert_eq!(normalize_uom("uh"), "uh");
+    }
+
+    #[test]
+    fn test_scale_and_convert() {
+        let mh = Unit::parse("mH").unwrap();
+        assert!((mh.scale_from_base(1.22774e-5) - 0.0122774).abs() < 1e-12);
+
+        let uh = Unit::parse("uH").unwrap();
+        assert!((mh.convert_to(1.5, &uh).unwrap() - 1500.0).abs() < 1e-9);
+        assert_eq!(mh.convert_to(1.0, &Unit::parse("Ohm").unwrap()), None);
+    }
+
+    #[test]
+    fn test_number_formats() {
+        let fixed = NumberFormat::default();
+        assert_eq!(fixed.format(0.0122774), "0.01228");
+        assert_eq!(fixed.format(1234.5678), "1235");
+        assert_eq!(fixed.format(9.99996), "10.00");
+        assert_eq!(fixed.format(0.0), "0.000");
+        assert_eq!(fixed.excel_format(0.0122774), "0.00000");
+        assert_eq!(fixed.excel_format(1234.5678), "0");
+
+        let sci = NumberFormat {
+            significant_digits: 3,
+            notation: Notation::Scientific,
+        };
+        assert_eq!(sci.format(1.22774e-5), "1.23E-5");
+        assert_eq!(sci.excel_format(1.22774e-5), "0.00E+0");
+
+        let eng = NumberFormat {
+            significant_digits: 4,
+            notation: Notation::Engineering,
+        };
+        assert_eq!(eng.format(1.22774e-5), "12.28E-6");
+        assert_eq!(eng.format(1500.0), "1.500E+3");
+        assert_eq!(eng.excel_format(1.22774e-5), "##0.00E+0");
+    }
+}
@@ -46,7 +46,7 @@ pub struct DateRangeFilter {
 // ==================== Helper Functions ====================
 
 /// Check if user has admin permission
-async fn check_admin_permission(
+pub(crate) async fn check_admin_permission(
     state: &State<'_, AppState>,
     username: &str,
 ) -> Result<bool, String> {
@@ -71,7 +71,7 @@ async fn check_admin_permission(
 }
 
 /// Get current Windows username
-fn get_current_username() -> Result<String, String> {
+pub(crate) fn get_current_username() -> Result<String, String> {
     whoami::username()
         .parse()
         .map_err(|_| "Failed to get username".to_string())
@@ -388,6 +388,11 @@ pub async fn set_voltech_setting(
         validate_path(&value)?;
     }
 
+    // Validate date order for date_format
+    if key == crate::dates::DATE_FORMAT_SETTING {
+        crate::dates::DateOrder::from_setting(&value)?;
+    }
+
     operations::set_setting(&state.voltech_db, &key, &value)
         .await
         .map_err(|e| format!("Failed to set setting: {}", e))?;
@@ -1,4 +1,5 @@
 // Parser integration with SeaORM database
+use crate::dates::{self, DateOrder, DATE_FORMAT_SETTING};
 use entity_voltech::test_results;
 use sea_orm::sea_query::OnConflict;
 use sea_orm::{entity::*, ActiveValue::NotSet, DbConn, DbErr, Set};
@@ -173,6 +174,7 @@ fn convert_to_active_model(
     operator: &str,
     batch: &str,
     date: &str,
+    date_order: DateOrder,
     file_path: &str,
 ) -> test_results::ActiveModel {
     let mut measurements = HashMap::new();
@@ -230,8 +232,8 @@ fn convert_to_active_model(
         }
     }
 
-    // Parse date to normalized_date (DD-MM-YY format to NaiveDate)
-    let normalized_date = parse_voltech_date(date);
+    // Parse date to normalized_date using the order the date was read with
+    let normalized_date = dates::parse_date(date, date_order).ok();
 
     test_results::ActiveModel {
         id: NotSet,
@@ -251,28 +253,11 @@ fn convert_to_active_model(
     }
 }
 
-/// Parse voltech date format "DD-MM-YY" to NaiveDate
-/// Example: "19-11-25" -> 2025-11-19
-fn parse_voltech_date(date: &str) -> Option<chrono::NaiveDate> {
-    // Format: DD-MM-YY
-    let parts: Vec<&str> = date.split('-').collect();
-    if parts.len() != 3 {
-        return None;
-    }
-
-    let day = parts[0].parse::<u32>().ok()?;
-    let month = parts[1].parse::<u32>().ok()?;
-    let year = parts[2].parse::<i32>().ok()?;
-
-    // Assume 20YY for two-digit year
-    let full_year = 2000 + year;
-
-    chrono::NaiveDate::from_ymd_opt(full_year, month, day)
-}
-
 /// Parse a file and return active models ready for insertion
+/// `date_order` applies to "Test Date" lines; dates taken from the file name are always DD-MM-YY
 pub fn parse_file_to_models(
     file_path: &str,
+    date_order: DateOrder,
 ) -> Result<Vec<test_results::ActiveModel>, Box<dyn std::error::Error>> {
     let content = fs::read_to_string(file_path)?;
     let all_lines: Vec<&str> = content.lines().collect();
@@ -281,6 +266,7 @@ pub fn parse_file_to_models(
     let mut operator = String::new();
     let mut batch = String::new();
     let mut date = String::new();
+    let mut block_date_order = date_order;
     let mut header: Vec<String> = Vec::new();
     let mut test_data = Vec::with_capacity(100);
 
@@ -308,7 +294,14 @@ pub fn parse_file_to_models(
             }
 
             let model = convert_to_active_model(
-                &header, &test_line, &part, &operator, &batch, &date, file_path,
+                &header,
+                &test_line,
+                &part,
+                &operator,
+                &batch,
+                &date,
+                block_date_order,
+                file_path,
             );
             test_data.push(model);
         } else if matches!(
@@ -338,8 +331,10 @@ pub fn parse_file_to_models(
             }
         } else if first_field.starts_with("Fil") {
             date = clean_date(first_field);
+            block_date_order = DateOrder::Eu;
         } else if first_field.starts_with("Test Date") {
             date = parse_test_date(first_field).to_string();
+            block_date_order = date_order;
         }
 
         i += 1;
@@ -371,8 +366,12 @@ pub async fn parse_and_insert_file(db: &DbConn, file_path: &str) -> Result<usize
         return Ok(0); // Already processed, no changes
     }
 
-    // Parse the file
-    let models = parse_file_to_models(file_path)
+    // Parse the file using the configured date order (Voltech default is DD-MM-YY)
+    let date_order = match crate::voltech::operations::get_setting(db, DATE_FORMAT_SETTING).await? {
+        Some(value) => DateOrder::from_setting(&value).map_err(DbErr::Custom)?,
+        None => DateOrder::Eu,
+    };
+    let models = parse_file_to_models(file_path, date_order)
         .map_err(|e| DbErr::Custom(format!("Failed to parse file: {}", e)))?;
     let count = models.len();
 
@@ -1,6 +1,6 @@
 use entity_voltech::{prelude::*, test_results};
 use sea_orm::*;
-use sea_query::Expr;
+use crate::serials::SerialSpec;
 use serde::{Deserialize, Serialize};
 
 /// Search tests with filters
@@ -48,15 +48,9 @@ pub async fn search_tests(
     }
 
     if let Some(serial_num) = filter.serial_num {
-        let serial_trim = serial_num.trim();
-        if serial_trim.chars().all(|c| c.is_ascii_digit()) {
-            // Try exact string match OR numeric match (handles leading zeros)
-            let n: i64 = serial_trim.parse().unwrap_or(0);
-            query = query.filter(
-                Condition::any()
-                    .add(test_results::Column::SerialNum.eq(serial_num.clone()))
-                    .add(Expr::cust(format!("CAST(serial_num AS INTEGER) = {}", n)))
-            );
+        // Serials, lists and ranges match ignoring leading zeros; anything else must match exactly
+        if let Ok(serials) = SerialSpec::parse(&serial_num) {
+            query = query.filter(serials.condition(test_results::Column::SerialNum));
         } else {
             query = query.filter(test_results::Column::SerialNum.eq(serial_num));
         }
@@ -80,15 +74,9 @@ pub async fn get_tests_by_serial(
     db: &DatabaseConnection,
     serial_num: &str,
 ) -> Result<Vec<test_results::Model>, DbErr> {
-    let serial_trim = serial_num.trim();
-    if serial_trim.chars().all(|c| c.is_ascii_digit()) {
-        let n: i64 = serial_trim.parse().unwrap_or(0);
+    if let Ok(serials) = SerialSpec::parse(serial_num) {
         TestResults::find()
-            .filter(
-                Condition::any()
-                    .add(test_results::Column::SerialNum.eq(serial_num.to_string()))
-                    .add(Expr::cust(format!("CAST(serial_num AS INTEGER) = {}", n))),
-            )
+            .filter(serials.condition(test_results::Column::SerialNum))
             .order_by_asc(test_results::Column::ResultNum)
             .all(db)
             .await
@@ -166,14 +154,8 @@ pub async fn count_tests(db: &DatabaseConnection, filter: TestSearchFilter) -> R
     }
 
     if let Some(serial_num) = filter.serial_num {
-        let serial_trim = serial_num.trim();
-        if serial_trim.chars().all(|c| c.is_ascii_digit()) {
-            let n: i64 = serial_trim.parse().unwrap_or(0);
-            query = query.filter(
-                Condition::any()
-                    .add(test_results::Column::SerialNum.eq(serial_num.clone()))
-                    .add(Expr::cust(format!("CAST(serial_num AS INTEGER) = {}", n)))
-            );
+        if let Ok(serials) = SerialSpec::parse(&serial_num) {
+            query = query.filter(serials.condition(test_results::Column::SerialNum));
         } else {
             query = query.filter(test_results::Column::SerialNum.eq(serial_num));
         }
@@ -0,0 +1,80 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { WorkbookSheets } from './excel-generator';
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type BulkFormat = 'excel' | 'pdf';
+export type BulkStatus = 'generated' | 'invalid' | 'failed' | 'cancelled';
+
+export interface BulkItem {
+	report_id: number;
+	batch: string | null;
+	serial_range: string | null;
+	selected_dates?: string[] | null; // Batch mode: "YYYY-MM-DD" test dates; null includes every date
+	job_number?: string;
+	split?: string;
+	date_code?: string;
+}
+
+// Every batch of an FG's reports tested between two dates (inclusive)
+export interface BulkQuery {
+	fg_id: number;
+	from: string; // "YYYY-MM-DD"
+	to: string;
+	job_number?: string;
+	split?: string;
+	date_code?: string;
+}
+
+export interface BulkRequest {
+	items?: BulkItem[];
+	query?: BulkQuery | null;
+	output_dir: string;
+	// Placeholders: {fg_number} {fg_revision} {customer} {report_id} {batch}
+	// {serial_range} {lot} {job_number} {split} {date_code}
+	name_template?: string | null;
+	format?: BulkFormat;
+	template_id?: number | null;
+	sheets?: WorkbookSheets;
+	include_incomplete?: boolean; // Generate items that fail validation
+}
+
+export interface ManifestEntry {
+	report_id: number;
+	fg_number: string;
+	fg_revision: string;
+	batch: string | null;
+	serial_range: string | null;
+	job_number: string;
+	split: string;
+	date_code: string;
+	status: BulkStatus;
+	file_name: string | null;
+	reasons: string[];
+}
+
+export interface BulkSummary {
+	output_dir: string;
+	generated: number;
+	invalid: number;
+	failed: number;
+	manifest_json: string;
+	manifest_csv: string;
+	entries: ManifestEntry[];
+}
+
+// ============================================================================
+// Adapter Functions
+// ============================================================================
+
+// Resolves once every report has been generated or skipped; each report runs
+// as a report job, so progress arrives through onReportJobProgress
+export async function generateBulkReports(request: BulkRequest): Promise<BulkSummary> {
+	try {
+		return await invoke<BulkSummary>('generate_bulk_reports', { request });
+	} catch (error) {
+		throw new Error(`Failed to generate reports: ${error}`);
+	}
+}
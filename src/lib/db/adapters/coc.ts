@@ -0,0 +1,122 @@
+import { invoke } from '@tauri-apps/api/core';
+import { save } from '@tauri-apps/plugin-dialog';
+import type { SessionSelection } from './report-validation';
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export interface CocRequest {
+	report_id: number;
+	batch?: string | null;
+	serial_range?: string | null;
+	selected_dates?: string[] | null;
+	sessions?: SessionSelection;
+	job_number: string;
+	quantity?: number | null; // Defaults to serials tested for serialized FGs
+	date_code: string;
+	template_id?: number | null;
+}
+
+export interface CocTestSummary {
+	test_name: string;
+	source_type: string;
+	units_tested: number;
+	units_passed: number;
+	units_failed: number;
+}
+
+export interface CocOverride {
+	id: number;
+	report_id: number;
+	scope: string;
+	reason: string;
+	approved_by: string;
+	created_at: string;
+	blocking_reasons: string[]; // Only applies while the CoC is blocked for exactly these
+}
+
+export interface CocSummary {
+	fg_number: string;
+	fg_revision: string;
+	customer: string;
+	customer_pn: string | null;
+	job_number: string;
+	quantity: number | null;
+	scope_label: string; // "Serial Range" or "Batch"
+	scope: string;
+	date_code: string;
+	issue_date: string;
+	tests: CocTestSummary[];
+	is_complete: boolean;
+	all_pass: boolean;
+	failing_units: string[];
+	blocking_reasons: string[];
+	override_record: CocOverride | null;
+	can_generate: boolean;
+}
+
+// ============================================================================
+// Adapter Functions
+// ============================================================================
+
+export async function previewCoc(request: CocRequest): Promise<CocSummary> {
+	try {
+		return await invoke<CocSummary>('preview_coc', { request });
+	} catch (error) {
+		throw new Error(`Failed to preview CoC: ${error}`);
+	}
+}
+
+export async function generateCoc(request: CocRequest): Promise<Uint8Array> {
+	try {
+		const buffer = await invoke<number[]>('generate_coc', { request });
+		return new Uint8Array(buffer);
+	} catch (error) {
+		throw new Error(`Failed to generate CoC: ${error}`);
+	}
+}
+
+/**
+ * Generate a CoC and prompt user to save
+ * Returns the saved path, or null if the dialog was cancelled
+ */
+export async function generateAndSaveCoc(
+	request: CocRequest,
+	fgNumber: string
+): Promise<string | null> {
+	try {
+		const identifier = request.serial_range || request.batch || 'shipment';
+		const defaultFilename = `${fgNumber}_${identifier}_${request.job_number}_CoC.pdf`;
+
+		const filePath = await save({
+			defaultPath: defaultFilename,
+			filters: [{ name: 'PDF Files', extensions: ['pdf'] }]
+		});
+
+		if (!filePath) {
+			return null;
+		}
+
+		await invoke('save_coc', { request, filePath });
+		return filePath;
+	} catch (error) {
+		throw new Error(`Failed to save CoC: ${error}`);
+	}
+}
+
+// Admin only; allows a CoC for incomplete or failing data, for its current
+// blocking reasons
+export async function recordCocOverride(
+	request: CocRequest,
+	reason: string
+): Promise<CocOverride> {
+	try {
+		return await invoke<CocOverride>('record_coc_override', {
+			request,
+			reason
+		});
+	} catch (error) {
+		throw new Error(`Failed to record CoC override: ${error}`);
+	}
+}
@@ -1,10 +1,44 @@
 import { invoke } from '@tauri-apps/api/core';
 import { save } from '@tauri-apps/plugin-dialog';
+import type { SessionSelection } from './report-validation';
 
 // ============================================================================
 // Excel Generation Functions
 // ============================================================================
 
+/**
+ * Extra sheets added after the report sheet
+ */
+export interface WorkbookSheets {
+    raw_data: boolean;      // Every underlying Voltech and manual row
+    traceability: boolean;  // Serial -> source file, date and operator
+    spec: boolean;          // Spec rows the report was generated against
+}
+
+function reportRequest(
+    reportId: number,
+    batch?: string,
+    serialRange?: string,
+    selectedDates?: string[],
+    templateId?: number,
+    sheets?: Partial<WorkbookSheets>,
+    sessions?: SessionSelection
+) {
+    return {
+        report_id: reportId,
+        batch: batch || null,
+        serial_range: serialRange || null,
+        selected_dates: selectedDates || null,
+        sessions: sessions ?? { include: [], exclude: [] },
+        template_id: templateId ?? null,
+        sheets: {
+            raw_data: sheets?.raw_data ?? false,
+            traceability: sheets?.traceability ?? false,
+            spec: sheets?.spec ?? false
+        }
+    };
+}
+
 /**
  * Generate Excel report and return as buffer
  */
@@ -12,14 +46,14 @@ export async function generateExcelReport(
     reportId: number,
     batch?: string,
     serialRange?: string,
-    selectedDates?: string[]
+    selectedDates?: string[],
+    templateId?: number,
+    sheets?: Partial<WorkbookSheets>,
+    sessions?: SessionSelection
 ): Promise<Uint8Array> {
     try {
         const buffer = await invoke<number[]>('generate_excel_report', {
-            reportId,
-            batch: batch || null,
-            serialRange: serialRange || null,
-            selectedDates: selectedDates || null
+            request: reportRequest(reportId, batch, serialRange, selectedDates, templateId, sheets, sessions)
         });
         return new Uint8Array(buffer);
     } catch (error) {
@@ -36,7 +70,10 @@ export async function generateAndSaveExcelReport(
     fgNumber: string,
     batch?: string,
     serialRange?: string,
-    selectedDates?: string[]
+    selectedDates?: string[],
+    templateId?: number,
+    sheets?: Partial<WorkbookSheets>,
+    sessions?: SessionSelection
 ): Promise<void> {
     try {
         // Create filename
@@ -56,14 +93,130 @@ export async function generateAndSaveExcelReport(
         if (filePath) {
             // File writing is now handled in Rust
             await invoke('save_excel_report', {
-                reportId,
-                filePath,
-                batch: batch || null,
-                serialRange: serialRange || null,
-                selectedDates: selectedDates || null
+                request: reportRequest(reportId, batch, serialRange, selectedDates, templateId, sheets, sessions),
+                filePath
             });
         }
     } catch (error) {
         throw new Error(`Failed to save Excel report: ${error}`);
     }
 }
+
+// ============================================================================
+// PDF Generation Functions
+// ============================================================================
+
+/**
+ * Generate PDF report and return as buffer
+ */
+export async function generatePdfReport(
+    reportId: number,
+    batch?: string,
+    serialRange?: string,
+    selectedDates?: string[],
+    templateId?: number,
+    sessions?: SessionSelection
+): Promise<Uint8Array> {
+    try {
+        const buffer = await invoke<number[]>('generate_pdf_report', {
+            request: reportRequest(reportId, batch, serialRange, selectedDates, templateId, undefined, sessions)
+        });
+        return new Uint8Array(buffer);
+    } catch (error) {
+        throw new Error(`Failed to generate PDF report: ${error}`);
+    }
+}
+
+/**
+ * Generate PDF report and prompt user to save
+ */
+export async function generateAndSavePdfReport(
+    reportId: number,
+    fgNumber: string,
+    batch?: string,
+    serialRange?: string,
+    selectedDates?: string[],
+    templateId?: number,
+    sessions?: SessionSelection
+): Promise<void> {
+    try {
+        const timestamp = new Date().toISOString().replace(/[:.]/g, '-').slice(0, -5);
+        const identifier = batch || serialRange || 'report';
+        const defaultFilename = `${fgNumber}_${identifier}_${timestamp}.pdf`;
+
+        const filePath = await save({
+            defaultPath: defaultFilename,
+            filters: [{
+                name: 'PDF Files',
+                extensions: ['pdf']
+            }]
+        });
+
+        if (filePath) {
+            await invoke('save_pdf_report', {
+                request: reportRequest(reportId, batch, serialRange, selectedDates, templateId, undefined, sessions),
+                filePath
+            });
+        }
+    } catch (error) {
+        throw new Error(`Failed to save PDF report: ${error}`);
+    }
+}
+
+// ============================================================================
+// BA Report Functions
+// ============================================================================
+
+export interface BaReportRequest {
+    fg: string;
+    rev: string;
+    serials: string; // Serial specification, e.g. "1001-1050, 1060"
+    tests: unknown[]; // TestResponse rows for the report
+    job_number: string;
+    split: string;
+    date_code: string;
+    template_id?: number | null;
+}
+
+/**
+ * Generate the customer BA report and return as buffer
+ */
+export async function createBaReport(request: BaReportRequest): Promise<Uint8Array> {
+    try {
+        const buffer = await invoke<number[]>('create_ba_report', { request });
+        return new Uint8Array(buffer);
+    } catch (error) {
+        throw new Error(`Failed to generate BA report: ${error}`);
+    }
+}
+
+/**
+ * Generate the customer BA report and prompt user to save
+ * Returns the saved path, or null if the dialog was cancelled
+ */
+export async function generateAndSaveBaReport(
+    request: BaReportRequest,
+    openAfterSave = false
+): Promise<string | null> {
+    try {
+        const serials = request.serials.replace(/[\s,;]+/g, '_');
+        const defaultFilename = `${request.fg}_${request.rev}_${serials}_BA.xlsx`;
+
+        const filePath = await save({
+            defaultPath: defaultFilename,
+            filters: [{
+                name: 'Excel Files',
+                extensions: ['xlsx']
+            }]
+        });
+
+        if (!filePath) {
+            return null;
+        }
+
+        await invoke('save_ba_report', { request, filePath, openAfterSave });
+        return filePath;
+    } catch (error) {
+        throw new Error(`Failed to save BA report: ${error}`);
+    }
+}
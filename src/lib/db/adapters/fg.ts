@@ -9,6 +9,7 @@ export interface FGData {
 	serialized: boolean;
 	rev: string;
 	customer: string;
+	customer_pn?: string | null;
 }
 
 export interface FGResponse {
@@ -17,6 +18,7 @@ export interface FGResponse {
 	fg: string;
 	rev: string;
 	customer: string;
+	customer_pn: string | null;
 }
 
 // ============================================================================
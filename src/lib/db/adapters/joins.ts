@@ -1,6 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
 import type { FGResponse } from './fg';
-import type { ReportResponse } from './report';
+import type { ReportResponse, RetestPolicy } from './report';
 import type { TestResponse } from './test';
 
 // ============================================================================
@@ -50,6 +50,7 @@ export interface ReportCompleteResponse {
 	id: number;
 	fg_id: number;
 	attributes: string;
+	retest_policy: RetestPolicy | null; // null until the report chooses one
 	fg: FGResponse;
 	tests: TestResponse[];
 }
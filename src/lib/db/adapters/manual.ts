@@ -0,0 +1,245 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type DateOrder = 'iso' | 'us' | 'eu';
+
+export interface ImportWarning {
+	file_path: string;
+	line_number: number;
+	sn: string;
+	test: string;
+	kind: 'verdict_mismatch' | 'spec_minimum' | 'spec_maximum' | 'spec_unit' | 'no_spec';
+	message: string;
+}
+
+export interface FileImportOutcome {
+	file_path: string;
+	status: 'imported' | 'skipped' | 'failed';
+	records_imported: number;
+	warning_count: number;
+	error: string | null;
+}
+
+export interface ManualImportResult {
+	files_processed: number;
+	records_imported: number;
+	warnings: ImportWarning[];
+	files: FileImportOutcome[]; // Per-file outcomes (folder imports only)
+}
+
+export interface FilePreview {
+	file_path: string;
+	already_processed: boolean;
+	row_count: number;
+	fgs: string[];
+	revs: string[];
+	batches: string[];
+	warnings: ImportWarning[];
+	error: string | null;
+}
+
+export interface ManualTestResponse {
+	id: number;
+	result: number;
+	test: string;
+	fg: string;
+	rev: string;
+	batch: string;
+	operator: string;
+	date: string;
+	time: string;
+	sn: string;
+	passfail: string;
+	minimum: number;
+	reading: number;
+	maximum: number;
+	uom: string;
+	file_path: string;
+	normalized_date: string;
+	tested_at: string | null;
+	computed_passfail: string;
+	verdict_mismatch: boolean;
+}
+
+export interface ManualTestSearchFilter {
+	fg?: string;
+	batch?: string;
+	test_name?: string;
+	operator?: string;
+	date_from?: string; // YYYY-MM-DD
+	date_to?: string; // YYYY-MM-DD
+	pass_fail?: string;
+	serial_num?: string;
+	limit?: number;
+	offset?: number;
+}
+
+export interface ManualTestPage {
+	items: ManualTestResponse[];
+	total: number;
+}
+
+export interface ManualFgListItem {
+	fg: string;
+	total_tests: number;
+	passed: number;
+	failed: number;
+	pass_rate: number;
+}
+
+export interface ManualTestSummary {
+	fg: string;
+	total_tests: number;
+	passed_tests: number;
+	failed_tests: number;
+	unique_batches: number;
+	unique_test_types: number;
+}
+
+// ============================================================================
+// Import Commands
+// ============================================================================
+
+export async function importManualFile(filePath: string): Promise<ManualImportResult> {
+	try {
+		return await invoke<ManualImportResult>('import_manual_file', { filePath });
+	} catch (error) {
+		throw new Error(`Failed to import manual file: ${error}`);
+	}
+}
+
+export async function importManualFgFolder(fg: string): Promise<ManualImportResult> {
+	try {
+		return await invoke<ManualImportResult>('import_manual_fg_folder', { fg });
+	} catch (error) {
+		throw new Error(`Failed to import manual FG folder: ${error}`);
+	}
+}
+
+/** Dry run of a folder import: what would be imported, without writing anything */
+export async function previewManualFgFolder(fg: string): Promise<FilePreview[]> {
+	try {
+		return await invoke<FilePreview[]>('preview_manual_fg_folder', { fg });
+	} catch (error) {
+		throw new Error(`Failed to preview manual FG folder: ${error}`);
+	}
+}
+
+// ============================================================================
+// Query Commands
+// ============================================================================
+
+export async function getManualTestNames(fg: string): Promise<string[]> {
+	try {
+		return await invoke<string[]>('get_manual_test_names', { fg });
+	} catch (error) {
+		throw new Error(`Failed to get manual test names: ${error}`);
+	}
+}
+
+export async function searchManualTests(filter: ManualTestSearchFilter): Promise<ManualTestPage> {
+	try {
+		return await invoke<ManualTestPage>('search_manual_tests', { filter });
+	} catch (error) {
+		throw new Error(`Failed to search manual tests: ${error}`);
+	}
+}
+
+export async function getManualFailedTests(
+	fg?: string,
+	limit?: number
+): Promise<ManualTestResponse[]> {
+	try {
+		return await invoke<ManualTestResponse[]>('get_manual_failed_tests', { fg, limit });
+	} catch (error) {
+		throw new Error(`Failed to get failed manual tests: ${error}`);
+	}
+}
+
+export async function getManualBatchTests(batch: string): Promise<ManualTestResponse[]> {
+	try {
+		return await invoke<ManualTestResponse[]>('get_manual_batch_tests', { batch });
+	} catch (error) {
+		throw new Error(`Failed to get manual batch tests: ${error}`);
+	}
+}
+
+export async function getManualBatchesForFg(fg: string): Promise<string[]> {
+	try {
+		return await invoke<string[]>('get_manual_batches_for_fg', { fg });
+	} catch (error) {
+		throw new Error(`Failed to get manual batches: ${error}`);
+	}
+}
+
+export async function getAllManualFgs(limit?: number): Promise<ManualFgListItem[]> {
+	try {
+		return await invoke<ManualFgListItem[]>('get_all_manual_fgs', { limit });
+	} catch (error) {
+		throw new Error(`Failed to get manual FGs: ${error}`);
+	}
+}
+
+export async function getManualSummary(fg: string): Promise<ManualTestSummary> {
+	try {
+		return await invoke<ManualTestSummary>('get_manual_summary', { fg });
+	} catch (error) {
+		throw new Error(`Failed to get manual summary: ${error}`);
+	}
+}
+
+// ============================================================================
+// Settings Commands
+// ============================================================================
+
+export async function getManualBasePath(): Promise<string> {
+	try {
+		return await invoke<string>('get_manual_base_path');
+	} catch (error) {
+		throw new Error(`Failed to get manual base path: ${error}`);
+	}
+}
+
+export async function setManualBasePath(path: string): Promise<void> {
+	try {
+		await invoke('set_manual_base_path', { path });
+	} catch (error) {
+		throw new Error(`Failed to set manual base path: ${error}`);
+	}
+}
+
+export async function getManualDateFormat(): Promise<DateOrder> {
+	try {
+		return await invoke<DateOrder>('get_manual_date_format');
+	} catch (error) {
+		throw new Error(`Failed to get manual date format: ${error}`);
+	}
+}
+
+export async function setManualDateFormat(format: DateOrder): Promise<void> {
+	try {
+		await invoke('set_manual_date_format', { format });
+	} catch (error) {
+		throw new Error(`Failed to set manual date format: ${error}`);
+	}
+}
+
+/** Comma-separated glob patterns matched against file names, e.g. "*.csv, *.txt" */
+export async function getManualIncludePatterns(): Promise<string> {
+	try {
+		return await invoke<string>('get_manual_include_patterns');
+	} catch (error) {
+		throw new Error(`Failed to get manual include patterns: ${error}`);
+	}
+}
+
+export async function setManualIncludePatterns(patterns: string): Promise<void> {
+	try {
+		await invoke('set_manual_include_patterns', { patterns });
+	} catch (error) {
+		throw new Error(`Failed to set manual include patterns: ${error}`);
+	}
+}
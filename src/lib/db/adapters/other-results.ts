@@ -0,0 +1,68 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// ============================================================================
+// Types
+// ============================================================================
+
+// Outcome of an "other" test, recorded by hand for a report run
+export interface OtherResult {
+	id: number;
+	report_id: number;
+	test_id: number;
+	scope: string; // Serial range or batch of the run
+	serial_number: string | null; // null when the outcome covers the whole run
+	value: number | null; // In the test's unit
+	result: 'PASS' | 'FAIL';
+	notes: string | null;
+	attachment: string | null; // Path or document reference
+	recorded_by: string;
+	recorded_at: string;
+}
+
+export interface OtherResultData {
+	test_id: number;
+	batch?: string | null;
+	serial_range?: string | null;
+	serial_number?: string | null;
+	value?: number | null;
+	result?: 'PASS' | 'FAIL' | null; // Judged from the value and the test's limits when omitted
+	notes?: string | null;
+	attachment?: string | null;
+}
+
+// ============================================================================
+// Adapter Functions
+// ============================================================================
+
+export async function getOtherResults(
+	reportId: number,
+	batch?: string,
+	serialRange?: string
+): Promise<OtherResult[]> {
+	try {
+		return await invoke<OtherResult[]>('get_other_results', {
+			reportId,
+			batch: batch || null,
+			serialRange: serialRange || null
+		});
+	} catch (error) {
+		throw new Error(`Failed to get other test results: ${error}`);
+	}
+}
+
+export async function recordOtherResult(result: OtherResultData): Promise<OtherResult> {
+	try {
+		return await invoke<OtherResult>('record_other_result', { result });
+	} catch (error) {
+		throw new Error(`Failed to record other test result: ${error}`);
+	}
+}
+
+// Whoever recorded the result, or an admin
+export async function deleteOtherResult(id: number): Promise<string> {
+	try {
+		return await invoke<string>('delete_other_result', { id });
+	} catch (error) {
+		throw new Error(`Failed to delete other test result: ${error}`);
+	}
+}
@@ -0,0 +1,70 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type PartSource = 'voltech' | 'manual';
+export type MappingKind = 'exact' | 'pattern';
+
+export interface PartMapping {
+	id: number;
+	fg_id: number;
+	source: PartSource;
+	kind: MappingKind;
+	value: string; // Tester part number, or a regex over the whole part number
+	rev: string | null; // Revision the part was tested at; a pattern's `rev` group overrides it
+	created_at: string;
+}
+
+export interface PartMappingData {
+	fg_id: number;
+	source: PartSource;
+	kind: MappingKind;
+	value: string;
+	rev?: string | null;
+}
+
+export interface UnmatchedParts {
+	voltech: string[];
+	manual: string[];
+}
+
+// ============================================================================
+// Adapter Functions
+// ============================================================================
+
+export async function getPartMappings(fgId: number): Promise<PartMapping[]> {
+	try {
+		return await invoke<PartMapping[]>('get_part_mappings', { fgId });
+	} catch (error) {
+		throw new Error(`Failed to get part mappings: ${error}`);
+	}
+}
+
+// Admin only
+export async function addPartMapping(mapping: PartMappingData): Promise<PartMapping> {
+	try {
+		return await invoke<PartMapping>('add_part_mapping', { mapping });
+	} catch (error) {
+		throw new Error(`Failed to add part mapping: ${error}`);
+	}
+}
+
+// Admin only
+export async function deletePartMapping(id: number): Promise<string> {
+	try {
+		return await invoke<string>('delete_part_mapping', { id });
+	} catch (error) {
+		throw new Error(`Failed to delete part mapping: ${error}`);
+	}
+}
+
+// Admin only; tester part numbers that no FG's mappings claim
+export async function getUnmatchedPartNumbers(): Promise<UnmatchedParts> {
+	try {
+		return await invoke<UnmatchedParts>('get_unmatched_part_numbers');
+	} catch (error) {
+		throw new Error(`Failed to get unmatched part numbers: ${error}`);
+	}
+}
@@ -0,0 +1,53 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type DiscrepancyKind =
+	| 'missing_voltech' // Manual data but no Voltech run
+	| 'missing_manual' // Voltech run but no manual data
+	| 'batch_mismatch' // Manual and Voltech batches differ for the serial
+	| 'rev_mismatch' // Manual rev is not the FG rev
+	| 'operator_outlier'
+	| 'date_outlier';
+
+export interface Discrepancy {
+	kind: DiscrepancyKind;
+	serial: string | null;
+	source: 'voltech' | 'manual' | null; // null when both sources are involved
+	detail: string;
+}
+
+export interface Reconciliation {
+	fg_id: number;
+	fg_number: string;
+	fg_revision: string;
+	serial_range: string | null;
+	batch: string | null;
+	voltech_records: number;
+	manual_records: number;
+	serials_checked: number;
+	discrepancies: Discrepancy[];
+}
+
+// ============================================================================
+// Adapter Functions
+// ============================================================================
+
+// Compare an FG's Voltech and manual records for a serial range and/or batch
+export async function reconcileSources(
+	fgId: number,
+	serialRange?: string,
+	batch?: string
+): Promise<Reconciliation> {
+	try {
+		return await invoke<Reconciliation>('reconcile_sources', {
+			fgId,
+			serialRange: serialRange || null,
+			batch: batch || null
+		});
+	} catch (error) {
+		throw new Error(`Failed to reconcile sources: ${error}`);
+	}
+}
@@ -1,4 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
+import type { RetestPolicy } from './report';
+import type { SessionSelection } from './report-validation';
 
 // ============================================================================
 // Types
@@ -9,6 +11,7 @@ export interface SingleTestResult {
     batch: string;
     date: string;
     result: string; // "PASS" or "FAIL"
+    retests: number; // Attempts on this serial after the first
     measurements: Record<string, unknown>; // JSON object with test-specific measurements
 }
 
@@ -25,12 +28,14 @@ export interface TestResultData {
 
 export interface ReportData {
     report_id: number;
+    fg_id: number;
     fg_number: string;
     fg_revision: string;
     fg_customer: string;
     is_serialized: boolean;
     batch: string | null;
     serial_range: string | null;
+    retest_policy: RetestPolicy;
     test_results: TestResultData[];
 }
 
@@ -42,14 +47,16 @@ export async function collectReport(
     reportId: number,
     batch?: string,
     serialRange?: string,
-    selectedDates?: string[]
+    selectedDates?: string[],
+    sessions?: SessionSelection
 ): Promise<ReportData> {
     try {
         return await invoke<ReportData>('collect_report', {
             reportId,
             batch: batch || null,
             serialRange: serialRange || null,
-            selectedDates: selectedDates || null
+            selectedDates: selectedDates || null,
+            sessions: sessions ?? null
         });
     } catch (error) {
         throw new Error(`Failed to collect report data: ${error}`);
@@ -0,0 +1,98 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { BaReportRequest } from './excel-generator';
+import type { ReportParameters } from './report-snapshots';
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type JobPhase = 'collecting' | 'rendering' | 'recording';
+export type JobStatus = 'queued' | 'running' | 'completed' | 'failed' | 'cancelled';
+
+export type ReportJobRequest =
+	| { kind: 'excel'; request: ReportParameters }
+	| { kind: 'pdf'; request: ReportParameters }
+	| { kind: 'ba'; request: BaReportRequest };
+
+export interface ReportJobInfo {
+	id: string;
+	kind: ReportJobRequest['kind'];
+	label: string;
+	extension: string; // 'xlsx' or 'pdf'
+	status: JobStatus;
+	phase: JobPhase | null; // Set while running
+	error: string | null;
+	size: number | null; // Bytes of the finished report
+	submitted_at: string;
+	finished_at: string | null;
+}
+
+// ============================================================================
+// Adapter Functions
+// ============================================================================
+
+// Queue reports for background generation; returns one job id per request
+export async function submitReportJobs(requests: ReportJobRequest[]): Promise<string[]> {
+	try {
+		return await invoke<string[]>('submit_report_jobs', { requests });
+	} catch (error) {
+		throw new Error(`Failed to submit report jobs: ${error}`);
+	}
+}
+
+export async function listReportJobs(): Promise<ReportJobInfo[]> {
+	try {
+		return await invoke<ReportJobInfo[]>('list_report_jobs');
+	} catch (error) {
+		throw new Error(`Failed to list report jobs: ${error}`);
+	}
+}
+
+// A running job stops at the start of its next phase
+export async function cancelReportJob(jobId: string): Promise<ReportJobInfo> {
+	try {
+		return await invoke<ReportJobInfo>('cancel_report_job', { jobId });
+	} catch (error) {
+		throw new Error(`Failed to cancel report job: ${error}`);
+	}
+}
+
+export async function getReportJobArtifact(jobId: string): Promise<Uint8Array> {
+	try {
+		const buffer = await invoke<number[]>('get_report_job_artifact', { jobId });
+		return new Uint8Array(buffer);
+	} catch (error) {
+		throw new Error(`Failed to get report: ${error}`);
+	}
+}
+
+export async function saveReportJobArtifact(jobId: string, filePath: string): Promise<void> {
+	try {
+		await invoke('save_report_job_artifact', { jobId, filePath });
+	} catch (error) {
+		throw new Error(`Failed to save report: ${error}`);
+	}
+}
+
+// Only finished jobs can be removed
+export async function removeReportJob(jobId: string): Promise<void> {
+	try {
+		await invoke('remove_report_job', { jobId });
+	} catch (error) {
+		throw new Error(`Failed to remove report job: ${error}`);
+	}
+}
+
+// ============================================================================
+// Event Listeners
+// ============================================================================
+
+// Called with the job's info on every change of phase or status
+export async function onReportJobProgress(
+	callback: (job: ReportJobInfo) => void
+): Promise<UnlistenFn> {
+	return await listen<ReportJobInfo>('report-job-progress', (event) => {
+		callback(event.payload);
+	});
+}
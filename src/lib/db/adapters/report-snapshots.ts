@@ -0,0 +1,172 @@
+import { invoke } from '@tauri-apps/api/core';
+import { save } from '@tauri-apps/plugin-dialog';
+import type { WorkbookSheets } from './excel-generator';
+import type { ReportData } from './report-collector';
+import type { SessionSelection } from './report-validation';
+import type { ReportTemplateDefinition } from './report-templates';
+
+// ============================================================================
+// Types
+// ============================================================================
+
+// BA workbooks and CoCs are built from live records by their own layouts;
+// their snapshots can be downloaded and diffed but not regenerated
+export type SnapshotFormat = 'xlsx' | 'pdf' | 'ba_xlsx' | 'coc_pdf';
+
+export interface ReportParameters {
+	report_id: number;
+	batch: string | null;
+	serial_range: string | null;
+	selected_dates: string[] | null;
+	sessions: SessionSelection;
+	template_id: number | null;
+	sheets: WorkbookSheets;
+}
+
+export interface RawRecords {
+	voltech: Record<string, unknown>[];
+	manual: Record<string, unknown>[];
+}
+
+export interface ReportSnapshotSummary {
+	id: number;
+	report_id: number;
+	fg_id: number;
+	format: SnapshotFormat;
+	parameters: string; // ReportParameters JSON
+	file_hash: string; // SHA-256 hex
+	file_size: number;
+	generated_by: string;
+	created_at: string;
+}
+
+export interface ReportSnapshot {
+	id: number;
+	report_id: number;
+	format: SnapshotFormat;
+	parameters: ReportParameters;
+	report_data: ReportData;
+	spec_rows: Record<string, unknown>[]; // Test rows as they were when generated
+	raw_records: RawRecords | null; // Source rows behind the raw data and traceability sheets
+	template: ReportTemplateDefinition;
+	file_hash: string;
+	generated_by: string;
+	created_at: string;
+}
+
+export interface FieldChange {
+	item: string;
+	field: string;
+	from: string | null;
+	to: string | null;
+}
+
+export interface ResultChange {
+	test_name: string;
+	key: string; // "<serial or batch> @ <date>"
+	change: 'added' | 'removed' | 'changed';
+	from: string | null;
+	to: string | null;
+}
+
+export interface SnapshotDiff {
+	from_id: number;
+	to_id: number;
+	identical_output: boolean;
+	parameter_changes: FieldChange[];
+	spec_changes: FieldChange[];
+	result_changes: ResultChange[];
+}
+
+// ============================================================================
+// Adapter Functions
+// ============================================================================
+
+export async function listReportSnapshots(reportId?: number): Promise<ReportSnapshotSummary[]> {
+	try {
+		return await invoke<ReportSnapshotSummary[]>('list_report_snapshots', {
+			reportId: reportId ?? null
+		});
+	} catch (error) {
+		throw new Error(`Failed to list report snapshots: ${error}`);
+	}
+}
+
+export async function getReportSnapshot(id: number): Promise<ReportSnapshot> {
+	try {
+		return await invoke<ReportSnapshot>('get_report_snapshot', { id });
+	} catch (error) {
+		throw new Error(`Failed to get report snapshot: ${error}`);
+	}
+}
+
+export async function diffReportSnapshots(fromId: number, toId: number): Promise<SnapshotDiff> {
+	try {
+		return await invoke<SnapshotDiff>('diff_report_snapshots', { fromId, toId });
+	} catch (error) {
+		throw new Error(`Failed to diff report snapshots: ${error}`);
+	}
+}
+
+function snapshotExtension(format: SnapshotFormat): 'pdf' | 'xlsx' {
+	return format === 'pdf' || format === 'coc_pdf' ? 'pdf' : 'xlsx';
+}
+
+function snapshotFilters(format: SnapshotFormat) {
+	return snapshotExtension(format) === 'pdf'
+		? [{ name: 'PDF Files', extensions: ['pdf'] }]
+		: [{ name: 'Excel Files', extensions: ['xlsx'] }];
+}
+
+/** Whether a snapshot can be re-rendered from its stored data */
+export function canRegenerateSnapshot(snapshot: ReportSnapshotSummary): boolean {
+	return snapshot.format === 'xlsx' || snapshot.format === 'pdf';
+}
+
+/**
+ * Save the stored file of a snapshot, byte-for-byte as shipped
+ * Returns the saved path, or null if the dialog was cancelled
+ */
+export async function downloadReportSnapshot(
+	snapshot: ReportSnapshotSummary
+): Promise<string | null> {
+	try {
+		const filePath = await save({
+			defaultPath: `snapshot_${snapshot.id}.${snapshotExtension(snapshot.format)}`,
+			filters: snapshotFilters(snapshot.format)
+		});
+
+		if (!filePath) {
+			return null;
+		}
+
+		await invoke('download_report_snapshot', { id: snapshot.id, filePath });
+		return filePath;
+	} catch (error) {
+		throw new Error(`Failed to download report snapshot: ${error}`);
+	}
+}
+
+/**
+ * Re-render a snapshot from its stored data and template and prompt user to save
+ * Returns the saved path, or null if the dialog was cancelled
+ */
+export async function regenerateReportSnapshot(
+	snapshot: ReportSnapshotSummary
+): Promise<string | null> {
+	try {
+		const filePath = await save({
+			defaultPath: `snapshot_${snapshot.id}_regenerated.${snapshotExtension(snapshot.format)}`,
+			filters: snapshotFilters(snapshot.format)
+		});
+
+		if (!filePath) {
+			return null;
+		}
+
+		await invoke('regenerate_report_snapshot', { id: snapshot.id, filePath });
+		return filePath;
+	} catch (error) {
+		throw new Error(`Failed to regenerate report snapshot: ${error}`);
+	}
+}
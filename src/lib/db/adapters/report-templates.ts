@@ -0,0 +1,168 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export interface TemplateHeaderField {
+	label: string; // May contain placeholders, e.g. "{lot_label}"
+	value: string; // e.g. "{fg_number}"
+}
+
+export interface TemplateInfoCell {
+	text: string;
+	span: number;
+}
+
+export interface TemplateSpecRow {
+	label: string;
+	field: string; // Test column, e.g. "test_type", "minimum", "uo_m"
+}
+
+export interface TemplatePageSetup {
+	landscape: boolean;
+	paper_size: number; // 0 = printer default, 1 = Letter, 9 = A4
+	margin_left: number;
+	margin_right: number;
+	margin_top: number;
+	margin_bottom: number;
+	fit_to_width: boolean;
+	page_numbers: boolean; // "Page X of Y" footer
+}
+
+export interface TemplateLogo {
+	path: string;
+	row: number;
+	col: number;
+	scale: number;
+}
+
+export interface TemplateStatistics {
+	enabled: boolean;
+	live_formulas: boolean; // Excel formulas over the data rows instead of plain numbers
+}
+
+export interface TemplateNumberFormat {
+	significant_digits: number;
+	notation: 'fixed' | 'scientific' | 'engineering'; // engineering: exponent a multiple of 3
+}
+
+export interface TemplateBaLayout {
+	title: string;
+	info_cells: TemplateInfoCell[];
+	spec_rows: TemplateSpecRow[];
+	serial_label: string;
+	columns_per_page: number;
+	rows_per_page: number; // Serial rows per printed page
+	label_column_width: number;
+	data_column_width: number;
+	title_row_height: number;
+	info_row_height: number;
+}
+
+export interface TemplateStyle {
+	header_color: string; // "#RRGGBB"
+	header_text_color: string;
+	pass_color: string;
+	fail_color: string;
+	spec_header_color: string;
+	result_header_color: string;
+	title_font: string;
+	title_font_size: number;
+	heading_font: string;
+	heading_font_size: number;
+	body_font: string;
+	body_font_size: number;
+}
+
+/** Template definition; omitted fields fall back to the built-in default */
+export interface ReportTemplateDefinition {
+	header_start_row: number;
+	header_col: number;
+	header_fields: TemplateHeaderField[];
+	test_section_start_row: number;
+	test_section_col: number;
+	result_columns: Array<'serial' | 'batch' | 'date' | 'result' | 'retests' | 'measurements'>;
+	column_widths: number[];
+	rows_between_tests: number;
+	spec_table_width: number;
+	result_table_max_width: number;
+	page: TemplatePageSetup;
+	logo: TemplateLogo | null;
+	signature_block: string[];
+	statistics: TemplateStatistics;
+	numbers: TemplateNumberFormat; // Readings and limits, shown in the test's declared unit
+	ba: TemplateBaLayout;
+	style_config: TemplateStyle;
+}
+
+export interface ReportTemplateRecord {
+	id: number;
+	name: string;
+	customer: string | null;
+	fg_id: number | null;
+	definition: string; // JSON-encoded ReportTemplateDefinition
+	is_default: boolean;
+	created_at: string;
+	updated_at: string;
+}
+
+export interface ReportTemplateInput {
+	id?: number; // Omit to create a new template
+	name: string;
+	customer?: string | null;
+	fg_id?: number | null;
+	definition: Partial<ReportTemplateDefinition>;
+	is_default?: boolean;
+}
+
+// ============================================================================
+// Template Commands
+// ============================================================================
+
+export async function listReportTemplates(): Promise<ReportTemplateRecord[]> {
+	try {
+		return await invoke<ReportTemplateRecord[]>('list_report_templates');
+	} catch (error) {
+		throw new Error(`Failed to list report templates: ${error}`);
+	}
+}
+
+export async function getReportTemplate(id: number): Promise<ReportTemplateRecord | null> {
+	try {
+		return await invoke<ReportTemplateRecord | null>('get_report_template', { id });
+	} catch (error) {
+		throw new Error(`Failed to get report template: ${error}`);
+	}
+}
+
+/** Create or update a template (admin only); returns the template id */
+export async function saveReportTemplate(template: ReportTemplateInput): Promise<number> {
+	try {
+		return await invoke<number>('save_report_template', { template });
+	} catch (error) {
+		throw new Error(`Failed to save report template: ${error}`);
+	}
+}
+
+/** Delete a template (admin only); returns false if it did not exist */
+export async function deleteReportTemplate(id: number): Promise<boolean> {
+	try {
+		return await invoke<boolean>('delete_report_template', { id });
+	} catch (error) {
+		throw new Error(`Failed to delete report template: ${error}`);
+	}
+}
+
+/** Built-in template, used as a starting point for new templates */
+export async function getDefaultReportTemplate(): Promise<ReportTemplateDefinition> {
+	try {
+		return await invoke<ReportTemplateDefinition>('get_default_report_template');
+	} catch (error) {
+		throw new Error(`Failed to get default report template: ${error}`);
+	}
+}
+
+export function parseTemplateDefinition(record: ReportTemplateRecord): Partial<ReportTemplateDefinition> {
+	return JSON.parse(record.definition);
+}
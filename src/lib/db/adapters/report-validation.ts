@@ -4,10 +4,25 @@ import { invoke } from '@tauri-apps/api/core';
 // Types
 // ============================================================================
 
+// One block of a results file: a part/batch/operator header on one test date
 export interface AvailableSession {
+    id: string; // Stable across re-imports; used to include or exclude the session
+    source: 'voltech' | 'manual';
     date: string;
     batch: string;
-    test_count: number;
+    part: string;
+    operator: string;
+    tester: string | null; // Voltech export folder; null for manual
+    file_path: string;
+    started_at: string | null; // "YYYY-MM-DD HH:MM:SS"
+    ended_at: string | null;
+    record_count: number;
+}
+
+// Batch mode: sessions to include or exclude; a non-empty include replaces the date selection
+export interface SessionSelection {
+    include: string[];
+    exclude: string[];
 }
 
 export interface TestStatus {
@@ -15,16 +30,39 @@ export interface TestStatus {
     test_name: string;
     source_type: string;
     associated_test: string | null;
-    has_data: boolean;
+    has_data: boolean; // Serialized: every serial in the range has a passing final result; other: an outcome is recorded
     record_count: number;
     available_sessions: AvailableSession[];
+    search_method: 'serial_range' | 'batch';
+}
+
+export type CellStatus = 'pass' | 'fail_only' | 'failing' | 'missing' | 'multiple_attempts';
+
+export interface MatrixCell {
+    test_id: number;
+    status: CellStatus;
+    attempts: number;
+}
+
+export interface SerialRow {
+    serial: string; // As written in the serial specification
+    cells: MatrixCell[];
+}
+
+/** Serial x test grid; tests with no data source ("other") have no column */
+export interface CompletenessMatrix {
+    test_ids: number[];
+    rows: SerialRow[];
+    complete_serials: number;
+    total_serials: number;
 }
 
 export interface ValidationResult {
-    is_valid: boolean;
-    is_serialized: boolean;
-    tests: TestStatus[];
-    message: string;
+    is_complete: boolean;
+    fg_serialized: boolean;
+    test_statuses: TestStatus[];
+    missing_test_count: number;
+    matrix: CompletenessMatrix | null; // Serialized mode only
 }
 
 // ============================================================================
@@ -34,13 +72,17 @@ export interface ValidationResult {
 export async function validateReport(
     reportId: number,
     batch?: string,
-    serialRange?: string
+    serialRange?: string,
+    selectedDates?: string[],
+    sessions?: SessionSelection
 ): Promise<ValidationResult> {
     try {
         return await invoke<ValidationResult>('validate_report', {
             reportId,
             batch: batch || null,
-            serialRange: serialRange || null
+            serialRange: serialRange || null,
+            selectedDates: selectedDates || null,
+            sessions: sessions ?? null
         });
     } catch (error) {
         throw new Error(`Failed to validate report: ${error}`);
@@ -4,16 +4,23 @@ import { invoke } from '@tauri-apps/api/core';
 // Types
 // ============================================================================
 
+// Which attempts a report shows when a serial is retested
+export type RetestPolicy = 'latest_attempt' | 'latest_pass' | 'first_pass' | 'all_attempts';
+
 export interface ReportData {
 	fg_id: number;
 	attributes: string;
 	added_by?: number | null;
+	retest_policy?: RetestPolicy | null; // Left unchanged on update when omitted
 }
 
 export interface ReportResponse {
 	id: number;
 	fg_id: number;
 	attributes: string;
+	// null until chosen: collected reports show all attempts, the BA serialized
+	// report each serial's latest pass
+	retest_policy: RetestPolicy | null;
 }
 
 // ============================================================================
@@ -0,0 +1,128 @@
+import { invoke } from '@tauri-apps/api/core';
+import type {
+	BatchListItem,
+	BatchSearchFilter,
+	BatchSummary,
+	DailyStats,
+	OperatorStats,
+	OverallStats,
+	PartSummary,
+	TestResult,
+	TestSearchFilter
+} from './voltech';
+import type { ManualTestResponse } from './manual';
+
+// ============================================================================
+// Types
+// ============================================================================
+
+// For manual data, `part` is the FG number and dates are YYYY-MM-DD
+export type TestSource = 'voltech' | 'manual';
+
+export type SourceTestRecord = TestResult | ManualTestResponse;
+
+// ============================================================================
+// Unified Query Commands
+// ============================================================================
+
+export async function searchSourceTests(
+	source: TestSource,
+	filter: TestSearchFilter
+): Promise<SourceTestRecord[]> {
+	try {
+		return await invoke<SourceTestRecord[]>('search_source_tests', { source, filter });
+	} catch (error) {
+		throw new Error(`Failed to search ${source} tests: ${error}`);
+	}
+}
+
+export async function countSourceTests(
+	source: TestSource,
+	filter: TestSearchFilter
+): Promise<number> {
+	try {
+		return await invoke<number>('count_source_tests', { source, filter });
+	} catch (error) {
+		throw new Error(`Failed to count ${source} tests: ${error}`);
+	}
+}
+
+export async function getSourceFailedTests(
+	source: TestSource,
+	limit?: number
+): Promise<SourceTestRecord[]> {
+	try {
+		return await invoke<SourceTestRecord[]>('get_source_failed_tests', { source, limit });
+	} catch (error) {
+		throw new Error(`Failed to get failed ${source} tests: ${error}`);
+	}
+}
+
+export async function searchSourceBatches(
+	source: TestSource,
+	filter: BatchSearchFilter
+): Promise<BatchListItem[]> {
+	try {
+		return await invoke<BatchListItem[]>('search_source_batches', { source, filter });
+	} catch (error) {
+		throw new Error(`Failed to search ${source} batches: ${error}`);
+	}
+}
+
+export async function getSourceBatchDetails(
+	source: TestSource,
+	batch: string
+): Promise<BatchSummary | null> {
+	try {
+		return await invoke<BatchSummary | null>('get_source_batch_details', { source, batch });
+	} catch (error) {
+		throw new Error(`Failed to get ${source} batch details: ${error}`);
+	}
+}
+
+export async function getSourcePartSummary(
+	source: TestSource,
+	part: string
+): Promise<PartSummary | null> {
+	try {
+		return await invoke<PartSummary | null>('get_source_part_summary', { source, part });
+	} catch (error) {
+		throw new Error(`Failed to get ${source} part summary: ${error}`);
+	}
+}
+
+export async function getSourceDailyStats(
+	source: TestSource,
+	dateFrom?: string,
+	dateTo?: string
+): Promise<DailyStats[]> {
+	try {
+		return await invoke<DailyStats[]>('get_source_daily_stats', { source, dateFrom, dateTo });
+	} catch (error) {
+		throw new Error(`Failed to get ${source} daily stats: ${error}`);
+	}
+}
+
+export async function getSourceOperatorStats(
+	source: TestSource,
+	dateFrom?: string,
+	dateTo?: string
+): Promise<OperatorStats[]> {
+	try {
+		return await invoke<OperatorStats[]>('get_source_operator_stats', {
+			source,
+			dateFrom,
+			dateTo
+		});
+	} catch (error) {
+		throw new Error(`Failed to get ${source} operator stats: ${error}`);
+	}
+}
+
+export async function getSourceOverallStats(source: TestSource): Promise<OverallStats | null> {
+	try {
+		return await invoke<OverallStats | null>('get_source_overall_stats', { source });
+	} catch (error) {
+		throw new Error(`Failed to get ${source} overall stats: ${error}`);
+	}
+}
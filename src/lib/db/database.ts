@@ -10,6 +10,16 @@ export * as testTypes from './adapters/test-types';
 export * as reportValidation from './adapters/report-validation';
 export * as reportCollector from './adapters/report-collector';
 export * as excelGenerator from './adapters/excel-generator';
+export * as manual from './adapters/manual';
+export * as sources from './adapters/sources';
+export * as reportTemplates from './adapters/report-templates';
+export * as coc from './adapters/coc';
+export * as reportSnapshots from './adapters/report-snapshots';
+export * as partMappings from './adapters/part-mappings';
+export * as reconciliation from './adapters/reconciliation';
+export * as otherResults from './adapters/other-results';
+export * as reportJobs from './adapters/report-jobs';
+export * as bulkReports from './adapters/bulk-reports';
 
 // Export types
 export * from './adapters/user';
@@ -23,3 +33,13 @@ export * from './adapters/test-types';
 export * from './adapters/report-validation';
 export * from './adapters/report-collector';
 export * from './adapters/excel-generator';
+export * from './adapters/manual';
+export * from './adapters/sources';
+export * from './adapters/report-templates';
+export * from './adapters/coc';
+export * from './adapters/report-snapshots';
+export * from './adapters/part-mappings';
+export * from './adapters/reconciliation';
+export * from './adapters/other-results';
+export * from './adapters/report-jobs';
+export * from './adapters/bulk-reports';
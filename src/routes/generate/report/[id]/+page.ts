@@ -1,12 +1,12 @@
 import type { PageLoad } from './$types';
-import { getReportWithTests } from '$lib/db/database';
+import { getReportComplete } from '$lib/db/database';
 import { superValidate } from 'sveltekit-superforms';
 import { zod4 } from 'sveltekit-superforms/adapters';
 import { newReportSchema } from '$types';
 
 export const load = (async ({ params }) => {
     const [report] = await Promise.all([
-        getReportWithTests(Number(params.id))
+        getReportComplete(Number(params.id))
     ]);
 
     const form = await superValidate(
@@ -21,6 +21,7 @@ export type fgNew = {
 	fg: string;
 	rev: string;
 	customer: string;
+	customer_pn?: string;
 	attributes: string[];
 	tests: eTest[];
 };
@@ -42,6 +43,7 @@ export const newFgSchema = z.object({
 	fg: z.string().min(1),
 	rev: z.string().min(1),
 	customer: z.string(),
+	customer_pn: z.string().optional(),
 	attributes: z.array(z.string()),
 	tests: z.array(eTestSchema)
 });